@@ -0,0 +1,32 @@
+//! Generates the C header for the `ffi` feature.
+
+fn main() {
+    #[cfg(feature = "ffi")]
+    write_header();
+}
+
+/// Write the declarations of the functions in `src/ffi.rs` to `rn.h` in the
+/// build script's output directory, leaving the source tree untouched.
+#[cfg(feature = "ffi")]
+fn write_header() {
+    use std::{env, path::Path};
+
+    let dir = env::var("CARGO_MANIFEST_DIR").expect("cargo sets the manifest directory");
+    let dir = Path::new(&dir);
+    let out = env::var("OUT_DIR").expect("cargo sets the output directory");
+    let mut config = cbindgen::Config::default();
+    config.usize_is_size_t = true;
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(dir.join("src/ffi.rs"))
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("RN_H")
+        .with_header("/* Generated by cbindgen from src/ffi.rs. Do not edit. */")
+        .with_sys_include("stddef.h")
+        .with_sys_include("stdint.h")
+        .with_no_includes()
+        .generate()
+        .expect("the bindings can be generated")
+        .write_to_file(Path::new(&out).join("rn.h"));
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+}
@@ -0,0 +1,73 @@
+//! Adding seximal durations to times of day, and subtracting them.
+
+use std::error::Error;
+
+use rn::{
+    mk,
+    systems::{civil_time_formatter, misalian_kunimunean_time_formatter},
+};
+
+use crate::{parse_millis_since_midnight, parse_mk_duration, shift_millis};
+
+/// Get the time of day `duration` after `time`, or before it if `subtract`
+/// is set, in milliseconds since midnight. The duration is parsed by
+/// [`parse_mk_duration`], optionally starting with the operator `+` or `-`.
+fn offset_time(time: &str, duration: &[String], subtract: bool) -> Result<u32, Box<dyn Error>> {
+    let operator = if subtract { "-" } else { "+" };
+    let duration = match duration {
+        [first, rest @ ..] if first == operator => rest,
+        _ => duration,
+    };
+    let millis = parse_mk_duration(&duration.join(" "))?;
+    // round towards the start of the resulting snap, which the rounded-up
+    // time is already past
+    let offset = match subtract {
+        true => -((millis.floor().to_integer() % mk::MILLIS_PER_DAY as u64) as i64),
+        false => (millis.ceil().to_integer() % mk::MILLIS_PER_DAY as u64) as i64,
+    };
+    Ok(shift_millis(parse_millis_since_midnight(time)?, offset))
+}
+
+/// Print the time of day `duration` after `time`, or before it if `subtract`
+/// is set, in both standard and seximal time, as for `rn add` and `rn sub`.
+pub fn run(time: &str, duration: &[String], subtract: bool) -> Result<(), Box<dyn Error>> {
+    let millis = offset_time(time, duration, subtract)? as u64;
+    println!(
+        "{} ({})",
+        civil_time_formatter().render(millis),
+        misalian_kunimunean_time_formatter().render(millis)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+    use clap::Parser;
+
+    use crate::{Args, Command};
+
+    #[test]
+    fn add_and_subtract_durations() {
+        let mkt = misalian_kunimunean_time_formatter();
+        let words = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+        let offset = |time, duration, subtract| {
+            mkt.render(offset_time(time, &words(duration), subtract).unwrap() as u64)
+        };
+
+        check!(offset("00:00:00.0", "+ 2 lapses 15 moments", false) == "02:00:15.0");
+        check!(offset("31:44:45.4", "1 lull and 1 snap", false) == "31:45:45.5");
+        check!(offset("50:00:00.0", "+ 10 lapses", false) == "00:00:00.0");
+        check!(offset("31:44:45.4", "- 1 lapse 4 snaps", true) == "30:44:45.0");
+        check!(offset("01:00:00.0", "2 lapses", true) == "55:00:00.0");
+        check!(offset_time("14:30", &words("- 2 lapses"), false).is_err());
+        check!(offset_time("14:30", &words("2 hours"), false).is_err());
+
+        check!(offset("31:44:45.4", "+ 0:1:0.1", false) == "31:45:45.5");
+
+        let args = Args::parse_from(["rn", "sub", "14:30", "-", "2", "lapses"]);
+        check!(matches!(args.command, Some(Command::Sub { duration, .. }) if duration.len() == 3));
+    }
+}
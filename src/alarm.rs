@@ -0,0 +1,71 @@
+//! Alarms at seximal times of day.
+
+use std::{
+    error::Error,
+    io::{self, Write},
+};
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+
+use crate::{parse_seximal_target, until::next_occurrence, wait, SelectedZone};
+
+/// How often to repeat an alarm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Repeat {
+    /// At the same time every day, on the clocks of the selected time zone.
+    Daily,
+}
+
+/// Sound an alarm at the next occurrence of the seximal time of day `time`,
+/// as for `rn alarm`.
+pub fn run(
+    time: &str,
+    message: Option<&str>,
+    repeat: Option<Repeat>,
+    bell: bool,
+    notification: bool,
+    tz: SelectedZone,
+) -> Result<(), Box<dyn Error>> {
+    let target = parse_seximal_target(time)?;
+    let message = message.map_or_else(|| format!("It is {}", target), String::from);
+    let daily = repeat == Some(Repeat::Daily);
+    let next = |after: DateTime<Utc>| {
+        let millis = next_occurrence(after.timestamp_millis(), target.millis(), tz);
+        DateTime::from_timestamp_millis(millis).unwrap_or(after)
+    };
+    wait::alarm(next, daily, || {
+        let mut stdout = io::stdout();
+        writeln!(stdout, "{}{}", message, if bell { "\x07" } else { "" })?;
+        stdout.flush()?;
+        if notification {
+            // the alarm has still gone off if the notification can't be shown
+            if let Err(err) = wait::notify("rn alarm", &message) {
+                eprintln!("could not show a notification: {}", err);
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+    use clap::Parser;
+
+    use crate::{Args, Command};
+
+    #[test]
+    fn parse_alarm_command() {
+        let args = Args::parse_from([
+            "rn", "alarm", "43:00:00", "-m", "stand up", "--repeat", "daily",
+        ]);
+        check!(matches!(
+            args.command,
+            Some(Command::Alarm { message: Some(m), repeat: Some(Repeat::Daily), .. }) if m == "stand up"
+        ));
+        check!(Args::try_parse_from(["rn", "alarm", "43:00:00", "--repeat", "hourly"]).is_err());
+    }
+}
@@ -0,0 +1,36 @@
+//! Running a command at a seximal time of day.
+
+use std::{error::Error, ffi::OsString};
+
+use crate::{parse_seximal_target, time_since_midnight, until::millis_until, wait, SelectedZone};
+
+/// Run `command` at the next occurrence of the seximal time of day `time`,
+/// as for `rn at`.
+pub fn run(time: &str, command: &[OsString], tz: SelectedZone) -> Result<(), Box<dyn Error>> {
+    let target = parse_seximal_target(time)?.millis();
+    let now = time_since_midnight(tz)?.as_millis() as u32;
+    wait::run_at(millis_until(now, target), command)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+    use clap::Parser;
+
+    use crate::{Args, Command};
+
+    #[test]
+    fn parse_at_command() {
+        let args = Args::parse_from(["rn", "at", "43:00:00", "--", "notify-send", "-u", "lunch"]);
+        check!(matches!(args.command, Some(Command::At { command, .. }) if command.len() == 3));
+        check!(Args::try_parse_from(["rn", "at", "43:00:00"]).is_err());
+
+        let target = parse_seximal_target("43:00:00").unwrap();
+        check!(target.to_string() == "43:00:00.0");
+        check!(parse_seximal_target("4300000") == Ok(target));
+        check!(parse_seximal_target("430") == Ok(target));
+        check!(parse_seximal_target("16:30").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! Output for status bars.
+
+use std::{
+    error::Error,
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::watch::MAX_SLEEP;
+
+/// A line of output for a Waybar custom module with `"return-type": "json"`.
+#[derive(Debug, Serialize)]
+pub struct Waybar<'a> {
+    /// The text shown in the bar.
+    pub text: &'a str,
+    /// The text shown when hovering over the module.
+    pub tooltip: &'a str,
+    /// The CSS class of the module.
+    pub class: &'a str,
+}
+
+impl Waybar<'_> {
+    /// Serialize this as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("serializing strings can't fail")
+    }
+}
+
+/// The tmux names of the standard terminal colors, by ANSI color code.
+const TMUX_COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// Convert text rendered with ANSI styles into markup for a tmux status line:
+/// each style becomes a `#[...]` directive, and `#` is doubled so that tmux
+/// shows it as it is rather than expanding it.
+pub fn tmux(styled: &str) -> String {
+    let mut out = String::with_capacity(styled.len());
+    let mut rest = styled;
+    while let Some(i) = rest.find(['#', '\x1b']) {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if let Some(after) = rest.strip_prefix('#') {
+            out.push_str("##");
+            rest = after;
+            continue;
+        }
+        // an escape sequence, as written by the formatter's styles
+        let (codes, after) = match rest.strip_prefix("\x1b[").and_then(|r| r.split_once('m')) {
+            Some(sequence) => sequence,
+            None => {
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let attributes: Vec<_> = codes
+            .split(';')
+            .filter_map(|code| match code.parse::<usize>().unwrap_or(0) {
+                0 => Some("default".to_string()),
+                1 => Some("bold".to_string()),
+                2 => Some("dim".to_string()),
+                code @ 30..=37 => Some(format!("fg={}", TMUX_COLORS[code - 30])),
+                _ => None,
+            })
+            .collect();
+        if !attributes.is_empty() {
+            out.push_str(&format!("#[{}]", attributes.join(",")));
+        }
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A block of the i3bar protocol.
+#[derive(Debug, Serialize)]
+pub struct I3barBlock<'a> {
+    /// The text shown in the bar.
+    pub full_text: &'a str,
+    /// The name of the program writing the block.
+    pub name: &'a str,
+    /// Which of the program's blocks this is.
+    pub instance: &'a str,
+}
+
+/// Speak the i3bar protocol, for use as the `status_command` of i3bar or
+/// swaybar: write the header, then a status line with a single block whenever
+/// the rendered time returned by `now` changes. `now` also returns how long to
+/// wait before the time may next change. Runs until the bar closes the pipe,
+/// or `now` fails.
+pub fn i3bar<F>(instance: &str, now: F) -> Result<(), Box<dyn Error>>
+where
+    F: Fn() -> Result<(String, Duration), Box<dyn Error>>,
+{
+    let mut stdout = io::stdout();
+    writeln!(stdout, "{{\"version\":1}}")?;
+    write!(stdout, "[")?;
+
+    let mut last = None;
+    loop {
+        let (current, wait) = now()?;
+        if last.as_ref() != Some(&current) {
+            let block = I3barBlock {
+                full_text: &current,
+                name: "rn",
+                instance,
+            };
+            // every status line after the first is preceded by a comma
+            let separator = if last.is_some() { "," } else { "" };
+            writeln!(stdout, "{}[{}]", separator, serde_json::to_string(&block)?)?;
+            stdout.flush()?;
+            last = Some(current);
+        }
+        thread::sleep(wait.min(MAX_SLEEP));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn waybar_json() {
+        let waybar = Waybar {
+            text: "31:44:45.4",
+            tooltip: "13:12:01.888",
+            class: "extended",
+        };
+        check!(
+            waybar.to_json()
+                == r#"{"text":"31:44:45.4","tooltip":"13:12:01.888","class":"extended"}"#
+        );
+        let quoted = Waybar {
+            text: "\"lp\"\n",
+            ..waybar
+        };
+        check!(quoted.to_json().starts_with(r#"{"text":"\"lp\"\n","#));
+    }
+
+    #[test]
+    fn tmux_markup() {
+        check!(tmux("31:44:45.4") == "31:44:45.4");
+        check!(tmux("#1 #{lapse}") == "##1 ##{lapse}");
+        check!(
+            tmux("\x1b[1;35m31\x1b[0m\x1b[2m:\x1b[0m")
+                == "#[bold,fg=magenta]31#[default]#[dim]:#[default]"
+        );
+        check!(tmux("\x1b[4m4\x1b") == "4");
+    }
+
+    #[test]
+    fn i3bar_block_json() {
+        let block = I3barBlock {
+            full_text: "31:44:45.4",
+            name: "rn",
+            instance: "extended",
+        };
+        check!(
+            serde_json::to_string(&block).unwrap()
+                == r#"{"full_text":"31:44:45.4","name":"rn","instance":"extended"}"#
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Converting times read from a file, one per line.
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+/// Print `header`, if any, then `convert` each line of the file at `path`, or
+/// of standard input if it is `-`. See [`write_converted`].
+pub fn convert_lines<F>(
+    path: &Path,
+    header: Option<&str>,
+    blank: &str,
+    convert: F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: Fn(&str) -> Result<String, Box<dyn Error>>,
+{
+    let input: Box<dyn BufRead> = match path == Path::new("-") {
+        true => Box::new(io::stdin().lock()),
+        false => Box::new(BufReader::new(
+            File::open(path).map_err(|err| format!("{}: {}", path.display(), err))?,
+        )),
+    };
+    write_converted(input, io::stdout().lock(), header, blank, convert)
+}
+
+/// Write `header`, if any, then one line to `output` for each line of `input`:
+/// the line after `convert`, or `blank` if the line is blank or can't be
+/// converted, so that output lines match input lines. Lines that can't be
+/// converted are reported on standard error, failing once every line has been
+/// read.
+fn write_converted<F>(
+    input: impl BufRead,
+    mut output: impl Write,
+    header: Option<&str>,
+    blank: &str,
+    convert: F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: Fn(&str) -> Result<String, Box<dyn Error>>,
+{
+    if let Some(header) = header {
+        writeln!(output, "{}", header)?;
+    }
+    let mut failed = 0;
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            writeln!(output, "{}", blank)?;
+            continue;
+        }
+        match convert(line.trim()) {
+            Ok(converted) => writeln!(output, "{}", converted)?,
+            Err(err) => {
+                eprintln!("warning: line {}: {}", i + 1, err);
+                writeln!(output, "{}", blank)?;
+                failed += 1;
+            }
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        1 => Err("1 line couldn't be converted".into()),
+        n => Err(format!("{} lines couldn't be converted", n).into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+    use clap::Parser;
+
+    use crate::Args;
+
+    #[test]
+    fn batch_input() {
+        check!(Args::parse_from(["rn", "--stdin"]).stdin);
+        check!(Args::try_parse_from(["rn", "--stdin", "--from", "times.txt"]).is_err());
+        check!(Args::try_parse_from(["rn", "--stdin", "--watch"]).is_err());
+        check!(
+            convert_lines(Path::new("/nonexistent/times.txt"), None, "", |line| Ok(
+                line.into()
+            ))
+            .is_err()
+        );
+
+        let input = "1:00\n\nlater\n2:30\n";
+        let mut output = Vec::new();
+        let result = write_converted(input.as_bytes(), &mut output, Some("h"), ",", |line| {
+            Ok(line
+                .split(':')
+                .next()
+                .ok_or("no hour")?
+                .parse::<u8>()?
+                .to_string())
+        });
+        check!(result.unwrap_err().to_string() == "1 line couldn't be converted");
+        check!(String::from_utf8(output).unwrap() == "h\n1\n,\n,\n2\n");
+    }
+}
@@ -0,0 +1,234 @@
+//! Large multi-row glyphs for displaying times in big digits, from FIGlet
+//! fonts.
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The bundled font, used when no other is given.
+const BLOCKS: &str = include_str!("blocks.flf");
+
+/// The German letters that every FIGlet font has glyphs for, in order, after
+/// those for printable ASCII.
+const GERMAN: [char; 7] = ['Ä', 'Ö', 'Ü', 'ä', 'ö', 'ü', 'ß'];
+
+/// Error returned when a FIGlet font cannot be loaded.
+#[derive(Debug)]
+pub enum FontError {
+    /// The file could not be read.
+    Io(PathBuf, io::Error),
+    /// The file is not a valid FIGlet font. Lines are numbered from one.
+    Invalid(PathBuf, usize, &'static str),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "could not read {}: {}", path.display(), err),
+            Self::Invalid(path, line, reason) => {
+                write!(f, "invalid font {}:{}: {}", path.display(), line, reason)
+            }
+        }
+    }
+}
+
+impl Error for FontError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(_, err) => Some(err),
+            Self::Invalid(..) => None,
+        }
+    }
+}
+
+/// A FIGlet font. Glyphs are set side by side at their full width, without
+/// any kerning or smushing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Font {
+    /// The height of each glyph, in rows.
+    height: usize,
+    /// The rows of each glyph, all as wide as each other.
+    glyphs: HashMap<char, Vec<String>>,
+}
+
+impl Default for Font {
+    /// The bundled font of block digits, with letters for digits above 9.
+    fn default() -> Self {
+        Self::parse(BLOCKS).expect("the bundled font is valid")
+    }
+}
+
+impl Font {
+    /// Load the FIGlet font (`.flf`) at `path`.
+    pub fn load(path: &Path) -> Result<Self, FontError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| FontError::Io(path.to_owned(), err))?;
+        Self::parse(&contents)
+            .map_err(|(line, reason)| FontError::Invalid(path.to_owned(), line, reason))
+    }
+
+    /// Parse the contents of a FIGlet font, returning the number of the line
+    /// and the reason if it is invalid.
+    fn parse(flf: &str) -> Result<Self, (usize, &'static str)> {
+        let mut lines = flf.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let (_, header) = lines.next().ok_or((1, "the font is empty"))?;
+        let mut header = header
+            .strip_prefix("flf2a")
+            .ok_or((1, "the header must start with `flf2a`"))?
+            .chars();
+        let hardblank = header.next().ok_or((1, "the header has no hardblank"))?;
+        // the old layout is negative in many fonts
+        let fields: Vec<i64> = header
+            .as_str()
+            .split_whitespace()
+            .map_while(|field| field.parse().ok())
+            .collect();
+        let (height, comments) = match fields[..] {
+            [height, _, _, _, comments, ..] if height > 0 && comments >= 0 => {
+                (height as usize, comments)
+            }
+            [height, ..] if height <= 0 => return Err((1, "the height must be at least 1")),
+            _ => return Err((1, "the header must give the height and number of comments")),
+        };
+        for _ in 0..comments {
+            lines.next().ok_or((1, "the font ends in its comments"))?;
+        }
+
+        // the end mark of each row is its last character, which may be
+        // doubled on the last row of a glyph
+        let glyph = |lines: &mut dyn Iterator<Item = (usize, &str)>| {
+            let mut rows = Vec::with_capacity(height);
+            for _ in 0..height {
+                let row = lines.next()?.1.trim_end();
+                let row = match row.chars().last() {
+                    Some(end) => {
+                        let row = row.strip_suffix(end).unwrap_or(row);
+                        row.strip_suffix(end).unwrap_or(row)
+                    }
+                    None => row,
+                };
+                rows.push(row.replace(hardblank, " "));
+            }
+            let width = rows.iter().map(|row| row.chars().count()).max();
+            for row in &mut rows {
+                let padding = width.unwrap_or(0) - row.chars().count();
+                row.push_str(&" ".repeat(padding));
+            }
+            Some(rows)
+        };
+
+        let mut glyphs = HashMap::new();
+        for c in (' '..='~').chain(GERMAN) {
+            let rows = glyph(&mut lines).ok_or((
+                flf.lines().count(),
+                "the font ends before all of ASCII and the German letters",
+            ))?;
+            glyphs.insert(c, rows);
+        }
+        while let Some((line, tag)) = lines.next() {
+            let code = tag.split_whitespace().next().unwrap_or_default();
+            let code = match code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+                Some(hex) => i64::from_str_radix(hex, 16),
+                None if code.len() > 1 && code.starts_with('0') => i64::from_str_radix(code, 8),
+                None => code.parse(),
+            }
+            .map_err(|_| (line, "expected the code of a character"))?;
+            let rows = glyph(&mut lines).ok_or((line, "the font ends in a character"))?;
+            // negative codes aren't characters
+            if let Some(c) = u32::try_from(code).ok().and_then(char::from_u32) {
+                glyphs.insert(c, rows);
+            }
+        }
+        Ok(Self { height, glyphs })
+    }
+
+    /// Whether this font has a glyph for `c`.
+    #[cfg(feature = "png")]
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    /// Render `text` in large glyphs, returning one string per row.
+    /// Characters without a glyph are drawn as themselves on the middle row.
+    /// Columns that are blank in every row are trimmed from the end.
+    pub fn render(&self, text: &str) -> Vec<String> {
+        let mut rows = vec![String::new(); self.height];
+        for c in text.chars() {
+            for (r, row) in rows.iter_mut().enumerate() {
+                match self.glyphs.get(&c) {
+                    Some(glyph) => row.push_str(&glyph[r]),
+                    None if r == self.height / 2 => row.extend([c, ' ']),
+                    None => row.push_str("  "),
+                }
+            }
+        }
+        let width = rows
+            .iter()
+            .map(|row| row.trim_end().chars().count())
+            .max()
+            .unwrap_or(0);
+        rows.into_iter()
+            .map(|row| row.chars().take(width).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn render_big() {
+        let font = Font::default();
+        check!(font.render("").len() == 5);
+        check!(
+            font.render("1:0")
+                == [
+                    " █    ███",
+                    "██  █ █ █",
+                    " █    █ █",
+                    " █  █ █ █",
+                    "███   ███"
+                ]
+        );
+        check!(font.render("5.") == ["███  ", "█    ", "███  ", "  █  ", "███ █"]);
+        check!(font.render("jS") == font.render("Js"));
+        check!(font.render(" 1")[0] == "     █ ");
+        check!(font.render("\u{2007}1") == font.render(" 1"));
+        check!(font.render("~")[2] == "~");
+        check!(font.render("é")[2] == "é");
+    }
+
+    #[test]
+    fn figlet_fonts() {
+        // two rows, a hardblank of `$`, and one comment; every glyph is empty
+        // but `1` and the tagged `é`
+        let mut flf = String::from("flf2a$ 2 1 4 -1 1\nA test font.\n");
+        for c in (' '..='~').chain(GERMAN) {
+            flf += match c {
+                '1' => " |$@\n |$@@\n",
+                _ => "@\n@@\n",
+            };
+        }
+        flf += "0xE9  LATIN SMALL LETTER E WITH ACUTE\n_#\ne##\n";
+        let font = Font::parse(&flf).unwrap();
+        check!(font.render("11") == [" |  |", " |  |"]);
+        check!(font.render("é1") == ["_ |", "e |"]);
+        check!(font.render("2") == ["", ""]);
+
+        check!(Font::parse("").is_err());
+        check!(Font::parse("tlf2a$ 2 1 4 -1 0").is_err());
+        check!(Font::parse("flf2a$ 0 1 4 -1 0").is_err());
+        check!(Font::parse("flf2a$ 2").is_err());
+        check!(Font::parse("flf2a$ 2 1 4 -1 0\n@\n@@").unwrap_err().0 == 3);
+        let bad_tag = Font::parse(&(flf + "e9\n@\n@@\n")).unwrap_err();
+        check!(bad_tag == (210, "expected the code of a character"));
+        check!(Font::load(Path::new("/nonexistent/font.flf")).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! Full-screen terminal clock.
+
+use std::{
+    error::Error,
+    io::{self, Write},
+};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    execute, queue,
+    style::Print,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use rn::systems::{
+    misalian_kunimunean_time_formatter, mk_snap_time_formatter, mk_span_time_formatter,
+};
+
+use crate::{big::Font, time_since_midnight, watch::POLL_INTERVAL, SelectedZone};
+
+/// Run the full-screen clock, in large digits of `font`, until the user quits
+/// with `q`, Escape, or Ctrl-C.
+pub fn run(tz: SelectedZone, font: &Font) -> Result<(), Box<dyn Error>> {
+    let _full_screen = FullScreen::enter()?;
+    run_loop(&mut io::stdout(), tz, font)
+}
+
+/// The terminal in raw mode on the alternate screen with the cursor hidden,
+/// as full-screen displays need it. The terminal is restored when this is
+/// dropped, even if the display failed.
+pub struct FullScreen(());
+
+impl FullScreen {
+    /// Switch the terminal to raw mode and the alternate screen, and hide the
+    /// cursor.
+    pub fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let full_screen = Self(());
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(full_screen)
+    }
+}
+
+impl Drop for FullScreen {
+    fn drop(&mut self) {
+        // there's nowhere left to report a failure to restore the terminal
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Redraw the clock whenever the time changes or the terminal is resized.
+fn run_loop<W: Write>(out: &mut W, tz: SelectedZone, font: &Font) -> Result<(), Box<dyn Error>> {
+    let extended = misalian_kunimunean_time_formatter();
+    let basic = mk_snap_time_formatter();
+    let span = mk_span_time_formatter();
+
+    let mut screen = Screen::default();
+    let mut last = String::new();
+    loop {
+        let mut dirty = false;
+        if event::poll(POLL_INTERVAL)? {
+            match event::read()? {
+                Event::Key(key) if is_quit(key) => return Ok(()),
+                Event::Resize(..) => dirty = true,
+                _ => {}
+            }
+        }
+
+        let since = time_since_midnight(tz)?;
+        let current = extended.render_duration(since);
+        if dirty || current != last {
+            let secondary = format!(
+                "basic {}   span {}",
+                basic.render_duration(since),
+                span.render_duration(since)
+            );
+            draw(out, &mut screen, dirty, font, &current, &secondary)?;
+            last = current;
+        }
+    }
+}
+
+/// Whether the key event should exit the clock, or another interactive display.
+pub fn is_quit(key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => true,
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
+/// Draw the time in large digits of `font` centered on the `screen`, with the
+/// secondary readout underneath, clearing it first if `full`. Falls back to
+/// plain text if the terminal is too narrow or too short.
+fn draw<W: Write>(
+    out: &mut W,
+    screen: &mut Screen,
+    full: bool,
+    font: &Font,
+    time: &str,
+    secondary: &str,
+) -> io::Result<()> {
+    let (cols, rows) = terminal::size()?;
+    let mut lines = font.render(time);
+    let width = lines.iter().map(|line| line.chars().count()).max();
+    if width.unwrap_or(0) > cols as usize || lines.len() + 2 > rows as usize {
+        lines = vec![time.to_string()];
+    }
+    lines.push(String::new());
+    lines.push(secondary.to_string());
+    screen.draw(out, lines, (cols, rows), full)
+}
+
+/// The lines last drawn on a full-screen display, so that only the characters
+/// that changed need drawing again.
+#[derive(Debug, Default)]
+pub struct Screen {
+    /// The column and row each line starts at, and its text.
+    lines: Vec<(u16, u16, String)>,
+}
+
+impl Screen {
+    /// Draw `lines` centered on a terminal `cols` wide and `rows` tall. If
+    /// `full` is set, or the lines have moved, the screen is cleared and every
+    /// line is drawn; otherwise, each line is drawn only from its first
+    /// changed character.
+    pub fn draw<W: Write>(
+        &mut self,
+        out: &mut W,
+        lines: Vec<String>,
+        (cols, rows): (u16, u16),
+        full: bool,
+    ) -> io::Result<()> {
+        let top = (rows as usize).saturating_sub(lines.len()) / 2;
+        let lines: Vec<_> = lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let left = (cols as usize).saturating_sub(line.chars().count()) / 2;
+                (left as u16, (top + i) as u16, line)
+            })
+            .collect();
+        let moved = lines.len() != self.lines.len()
+            || lines
+                .iter()
+                .zip(&self.lines)
+                .any(|(new, old)| (new.0, new.1) != (old.0, old.1));
+
+        if full || moved {
+            queue!(out, Clear(ClearType::All))?;
+            for (left, row, line) in &lines {
+                queue!(out, MoveTo(*left, *row), Print(line))?;
+            }
+        } else {
+            for ((left, row, line), (_, _, old)) in lines.iter().zip(&self.lines) {
+                if let Some((column, rest)) = changed_suffix(old, line) {
+                    queue!(
+                        out,
+                        MoveTo(left + column as u16, *row),
+                        Print(rest),
+                        Clear(ClearType::UntilNewLine)
+                    )?;
+                }
+            }
+        }
+        self.lines = lines;
+        out.flush()
+    }
+}
+
+/// Find where `new` first differs from `old`, returning the number of
+/// characters before it and the rest of `new`, or `None` if they're the same.
+fn changed_suffix<'n>(old: &str, new: &'n str) -> Option<(usize, &'n str)> {
+    if old == new {
+        return None;
+    }
+    let mut old = old.chars();
+    let (column, (start, _)) = new
+        .char_indices()
+        .enumerate()
+        .find(|(_, (_, c))| old.next() != Some(*c))
+        .unwrap_or((new.chars().count(), (new.len(), ' ')));
+    Some((column, &new[start..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn changed_characters() {
+        check!(changed_suffix("31:44:45.4", "31:44:45.5") == Some((9, "5")));
+        check!(changed_suffix("31:44:45.5", "31:44:50.0") == Some((6, "50.0")));
+        check!(changed_suffix("█ █", "███") == Some((1, "██")));
+        check!(changed_suffix("31:44", "31") == Some((2, "")));
+        check!(changed_suffix("", "31") == Some((0, "31")));
+        check!(changed_suffix("31", "31").is_none());
+    }
+
+    #[test]
+    fn redraw_changes() {
+        let mut screen = Screen::default();
+        let mut out = Vec::new();
+        let lines = || vec!["███".to_string(), "31:44:45.4".to_string()];
+        screen.draw(&mut out, lines(), (20, 4), false).unwrap();
+        check!(String::from_utf8_lossy(&out).contains("31:44:45.4"));
+
+        out.clear();
+        screen.draw(&mut out, lines(), (20, 4), false).unwrap();
+        check!(out.is_empty());
+
+        out.clear();
+        let changed = vec!["███".to_string(), "31:44:45.5".to_string()];
+        screen.draw(&mut out, changed, (20, 4), false).unwrap();
+        let written = String::from_utf8_lossy(&out);
+        check!(!written.contains("31:44"));
+        check!(written.contains('5'));
+    }
+}
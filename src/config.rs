@@ -0,0 +1,120 @@
+//! User configuration file for default options.
+
+use std::{
+    env,
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+/// Default options, loaded from the configuration file. Options given on the
+/// command line take precedence over these.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Use the system time zone instead of UTC.
+    pub local: bool,
+    /// The name of the system to use when none is given on the command line.
+    #[serde(alias = "output")]
+    pub system: Option<String>,
+    /// Whether to zero-pad units to a fixed width.
+    pub padding: Option<bool>,
+    /// The delimiters placed after the lapse, lull, and moment in extended form.
+    pub delimiters: Option<[String; 3]>,
+    /// The template to use when none is given on the command line.
+    pub format: Option<String>,
+}
+
+/// Error returned when the configuration file cannot be loaded.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(PathBuf, io::Error),
+    /// The file is not a valid configuration.
+    Toml(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "could not read {}: {}", path.display(), err),
+            Self::Toml(path, err) => write!(f, "invalid config {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(_, err) => Some(err),
+            Self::Toml(_, err) => Some(err),
+        }
+    }
+}
+
+impl Config {
+    /// The directory containing the configuration file: `$XDG_CONFIG_HOME/rn`,
+    /// or `~/.config/rn` if `XDG_CONFIG_HOME` is not set.
+    pub fn dir() -> Option<PathBuf> {
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(dir.join("rn"))
+    }
+
+    /// The default location of the configuration file, `config.toml` in
+    /// [`Config::dir`].
+    pub fn default_path() -> Option<PathBuf> {
+        Some(Self::dir()?.join("config.toml"))
+    }
+
+    /// Load the configuration at `path`.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_owned(), err))?;
+        toml::from_str(&contents).map_err(|err| ConfigError::Toml(path.to_owned(), err))
+    }
+
+    /// Load the configuration at the default location, if there is one.
+    pub fn load_default() -> Result<Self, ConfigError> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+            local = true
+            system = "basic"
+            padding = false
+            delimiters = ["h", "m", "s"]
+            "#,
+        )
+        .unwrap();
+
+        check!(config.local);
+        check!(config.system.as_deref() == Some("basic"));
+        check!(config.padding == Some(false));
+        check!(config.delimiters == Some(["h".into(), "m".into(), "s".into()]));
+        check!(config.format.is_none());
+    }
+
+    #[test]
+    fn reject_unknown_options() {
+        check!(toml::from_str::<Config>("loacl = true").is_err());
+        check!(toml::from_str::<Config>("system = 6").is_err());
+    }
+}
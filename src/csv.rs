@@ -0,0 +1,41 @@
+//! Comma-separated values of the time.
+
+use rn::systems::{
+    civil_time_formatter, misalian_kunimunean_time_formatter, mk_snap_time_formatter,
+    mk_span_time_formatter,
+};
+
+/// The header row of CSV output.
+pub const HEADER: &str = "civil,extended,basic,span";
+
+/// Get a row of CSV output for the time `millis` milliseconds after midnight:
+/// the civil time, and the seximal time in extended, basic, and span forms.
+pub fn row(millis: u64) -> String {
+    format!(
+        "{},{},{},{}",
+        civil_time_formatter().render(millis),
+        misalian_kunimunean_time_formatter().render(millis),
+        mk_snap_time_formatter().render(millis),
+        mk_span_time_formatter().render(millis),
+    )
+}
+
+/// A row of CSV output with every field empty, for a time that couldn't be
+/// converted.
+pub fn blank_row() -> String {
+    ",".repeat(HEADER.matches(',').count())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn csv_rows() {
+        check!(HEADER.split(',').count() == row(0).split(',').count());
+        check!(row(47_521_888) == "13:12:01.888,31:44:45.4,3144454,314");
+        check!(blank_row() == ",,,");
+    }
+}
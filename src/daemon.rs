@@ -0,0 +1,143 @@
+//! Daemon running commands at the seximal times of day in a schedule file.
+
+use std::{error::Error, fs, path::Path, process, thread};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+
+use rn::mk::MkTime;
+
+use crate::{
+    config::ConfigError,
+    parse_seximal_target,
+    until::next_occurrence,
+    wait::{interrupt_flag, sleep_until},
+    SelectedZone,
+};
+
+/// The entries of a schedule file.
+///
+/// ```toml
+/// [[entry]]
+/// time = "43:00:00"
+/// command = ["notify-send", "lunch"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schedule {
+    /// The commands to run, and when.
+    #[serde(default, rename = "entry")]
+    pub entries: Vec<Entry>,
+}
+
+/// A command to run at the same time every day.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Entry {
+    /// When to run the command, in any form accepted by `rn at`.
+    #[serde(deserialize_with = "deserialize_time")]
+    pub time: MkTime,
+    /// The program to run, followed by its arguments.
+    pub command: Vec<String>,
+}
+
+/// Deserialize a seximal time of day, as accepted by `rn at`.
+fn deserialize_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MkTime, D::Error> {
+    let time = String::deserialize(deserializer)?;
+    parse_seximal_target(&time).map_err(serde::de::Error::custom)
+}
+
+impl Schedule {
+    /// Load the schedule file at `path`.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_owned(), err))?;
+        toml::from_str(&contents).map_err(|err| ConfigError::Toml(path.to_owned(), err))
+    }
+}
+
+/// Run each entry of `schedule` at its time every day, on the clocks of the
+/// time zone `tz`, until interrupted with Ctrl-C. Commands that can't be
+/// started are reported without stopping the daemon.
+pub fn run(schedule: &Schedule, tz: SelectedZone) -> Result<(), Box<dyn Error>> {
+    if schedule.entries.is_empty() {
+        return Err("the schedule has no entries".into());
+    }
+    let running = interrupt_flag()?;
+
+    let next = |entry: &Entry, after: DateTime<Utc>| {
+        let millis = next_occurrence(after.timestamp_millis(), entry.time.millis(), tz);
+        DateTime::from_timestamp_millis(millis).unwrap_or(after)
+    };
+    let start = Utc::now();
+    let mut next_runs: Vec<DateTime<Utc>> = schedule
+        .entries
+        .iter()
+        .map(|entry| next(entry, start))
+        .collect();
+
+    loop {
+        let (i, deadline) = next_runs
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|&(_, deadline)| deadline)
+            .expect("the schedule has entries");
+        if !sleep_until(deadline, &running) {
+            return Ok(());
+        }
+        let entry = &schedule.entries[i];
+        if let Err(err) = spawn(&entry.command) {
+            eprintln!("{}: could not run {:?}: {}", entry.time, entry.command, err);
+        }
+        next_runs[i] = next(entry, deadline + chrono::Duration::milliseconds(1));
+    }
+}
+
+/// Start `command` in the background, without waiting for it to finish.
+fn spawn(command: &[String]) -> Result<(), Box<dyn Error>> {
+    let (program, args) = command.split_first().ok_or("the command is empty")?;
+    let mut child = process::Command::new(program).args(args).spawn()?;
+    // reap the child when it exits
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn parse_schedule() {
+        let schedule: Schedule = toml::from_str(
+            r#"
+            [[entry]]
+            time = "43:00:00"
+            command = ["notify-send", "lunch"]
+
+            [[entry]]
+            time = "3144454"
+            command = ["true"]
+            "#,
+        )
+        .unwrap();
+
+        check!(schedule.entries.len() == 2);
+        check!(schedule.entries[0].time.to_string() == "43:00:00.0");
+        check!(schedule.entries[0].command == ["notify-send", "lunch"]);
+        check!(schedule.entries[1].time.to_string() == "31:44:45.4");
+        check!(toml::from_str::<Schedule>("").unwrap().entries.is_empty());
+    }
+
+    #[test]
+    fn reject_invalid_schedules() {
+        let entry = |time: &str| format!("[[entry]]\ntime = {:?}\ncommand = [\"true\"]", time);
+        check!(toml::from_str::<Schedule>(&entry("16:30")).is_err());
+        check!(toml::from_str::<Schedule>(&entry("60:00:00")).is_err());
+        check!(toml::from_str::<Schedule>("[[entry]]\ntime = \"43:00:00\"").is_err());
+        check!(toml::from_str::<Schedule>("[[entries]]").is_err());
+        check!(spawn(&[]).is_err());
+    }
+}
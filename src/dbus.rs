@@ -0,0 +1,176 @@
+//! D-Bus service publishing the current time.
+
+use std::{
+    error::Error,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+use zbus::{
+    blocking::{connection, Connection},
+    interface,
+    object_server::SignalEmitter,
+};
+
+use crate::{wait::interrupt_flag, watch::MAX_SLEEP};
+
+/// The well-known name the service is registered under.
+const BUS_NAME: &str = "io.github.ethwu.rn";
+/// The path of the clock object.
+const OBJECT_PATH: &str = "/io/github/ethwu/rn";
+
+/// A reading of the time to publish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reading {
+    /// The time as rendered.
+    pub text: String,
+    /// The seximal moment of the day the reading was taken in, so that
+    /// subscribers are told when it changes.
+    pub moment: u32,
+    /// How long to wait before the rendered time may next change.
+    pub wait: Duration,
+}
+
+/// The clock object, holding the most recently rendered time.
+struct Clock {
+    /// The current time, as rendered.
+    now: String,
+}
+
+#[interface(name = "io.github.ethwu.rn.Clock")]
+impl Clock {
+    /// The current time, as rendered by `rn`.
+    fn now(&self) -> String {
+        self.now.clone()
+    }
+
+    /// Emitted with the new time at the start of each seximal moment.
+    #[zbus(signal)]
+    async fn changed(emitter: &SignalEmitter<'_>, time: &str) -> zbus::Result<()>;
+}
+
+/// Register the clock on the session bus, keeping its time up to date with
+/// the readings returned by `now` and emitting `Changed` whenever the moment
+/// changes, until interrupted with Ctrl-C or `now` fails.
+pub fn serve<F>(now: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut() -> Result<Reading, Box<dyn Error>>,
+{
+    let running = interrupt_flag()?;
+    let mut now = now;
+    let first = now()?;
+    let connection = connection::Builder::session()?
+        .name(BUS_NAME)?
+        .serve_at(
+            OBJECT_PATH,
+            Clock {
+                now: first.text.clone(),
+            },
+        )?
+        .build()?;
+    publish(&connection, first, now, &running)
+}
+
+/// Keep the clock served on `connection` up to date with the readings
+/// returned by `now`, starting from the reading `last`, until `running` is
+/// cleared or `now` fails.
+fn publish<F>(
+    connection: &Connection,
+    mut last: Reading,
+    mut now: F,
+    running: &AtomicBool,
+) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut() -> Result<Reading, Box<dyn Error>>,
+{
+    let clock = connection
+        .object_server()
+        .interface::<_, Clock>(OBJECT_PATH)?;
+
+    while running.load(Ordering::SeqCst) {
+        thread::sleep(last.wait.min(MAX_SLEEP));
+        let reading = now()?;
+        if reading.text != last.text {
+            clock.get_mut().now = reading.text.clone();
+        }
+        if reading.moment != last.moment {
+            zbus::block_on(Clock::changed(clock.signal_emitter(), &reading.text))?;
+        }
+        last = reading;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::{os::unix::net::UnixStream, sync::mpsc};
+
+    use assert2::check;
+    use zbus::{blocking::MessageIterator, message::Type, Guid};
+
+    #[test]
+    fn publish_readings() {
+        let reading = |text: &str, moment| Reading {
+            text: text.to_string(),
+            moment,
+            wait: Duration::ZERO,
+        };
+        let (service, client) = UnixStream::pair().unwrap();
+        let (send, readings) = mpsc::channel();
+        let first = reading("00:00:00.5", 0);
+        let service = thread::spawn(move || {
+            let connection = connection::Builder::async_io_unix_stream(service)
+                .server(Guid::generate())
+                .unwrap()
+                .p2p()
+                .serve_at(
+                    OBJECT_PATH,
+                    Clock {
+                        now: first.text.clone(),
+                    },
+                )
+                .unwrap()
+                .build()
+                .unwrap();
+            let now = || Ok(readings.recv()?);
+            publish(&connection, first, now, &AtomicBool::new(true)).map_err(|err| err.to_string())
+        });
+
+        let client = connection::Builder::async_io_unix_stream(client)
+            .p2p()
+            .build()
+            .unwrap();
+        let mut signals = MessageIterator::from(&client)
+            .map(Result::unwrap)
+            .filter(|message| message.message_type() == Type::Signal)
+            .map(|signal| signal.body().deserialize::<String>().unwrap());
+        let now = || {
+            let reply = client
+                .call_method(
+                    None::<&str>,
+                    OBJECT_PATH,
+                    Some("io.github.ethwu.rn.Clock"),
+                    "Now",
+                    &(),
+                )
+                .unwrap();
+            reply.body().deserialize::<String>().unwrap()
+        };
+        check!(now() == "00:00:00.5");
+
+        send.send(reading("00:00:01.0", 1)).unwrap();
+        check!(signals.next().as_deref() == Some("00:00:01.0"));
+        check!(now() == "00:00:01.0");
+        // a new time within the same moment is published without a signal
+        send.send(reading("00:00:01.1", 1)).unwrap();
+        send.send(reading("00:00:02.0", 2)).unwrap();
+        check!(signals.next().as_deref() == Some("00:00:02.0"));
+        check!(now() == "00:00:02.0");
+
+        drop(send);
+        check!(service.join().unwrap().is_err());
+    }
+}
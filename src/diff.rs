@@ -0,0 +1,51 @@
+//! The difference between two times of day.
+
+use std::error::Error;
+
+use rn::systems::misalian_kunimunean_time_formatter;
+
+use crate::parse_millis_since_midnight;
+
+/// Get the number of milliseconds from time of day `a` to time of day `b`,
+/// which is negative if `b` is earlier in the day.
+fn millis_between(a: &str, b: &str) -> Result<i64, Box<dyn Error>> {
+    Ok(parse_millis_since_midnight(b)? as i64 - parse_millis_since_midnight(a)? as i64)
+}
+
+/// Print the signed difference from time of day `a` to time of day `b`, as
+/// for `rn diff`.
+pub fn run(a: &str, b: &str) -> Result<(), Box<dyn Error>> {
+    let difference = millis_between(a, b)?;
+    println!(
+        "{}",
+        misalian_kunimunean_time_formatter().render_signed(difference)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+    use clap::Parser;
+
+    use crate::{Args, Command};
+
+    #[test]
+    fn parse_diff_command() {
+        let mkt = misalian_kunimunean_time_formatter();
+        let args = Args::parse_from(["rn", "diff", "12:00", "31:44:45.4"]);
+        check!(matches!(args.command, Some(Command::Diff { .. })));
+        check!(Args::try_parse_from(["rn", "diff", "12:00"]).is_err());
+
+        let difference = millis_between("12:00", "16:30").unwrap();
+        check!(mkt.render_signed(difference) == "10:43:00.0");
+        let difference = millis_between("16:30", "12:00").unwrap();
+        check!(mkt.render_signed(difference) == "-10:43:00.0");
+        let difference = millis_between("30:00:00.0", "31:44:45.4").unwrap();
+        check!(mkt.render_signed(difference) == "01:44:45.4");
+        check!(millis_between("12:00", "noon").unwrap() == 0);
+        check!(millis_between("12:00", "teatime").is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! Full-screen analog seximal clock, drawn in braille.
+
+use std::{
+    error::Error,
+    f64::consts::TAU,
+    io::{self, Write},
+};
+
+use crossterm::{
+    event::{self, Event},
+    terminal,
+};
+
+use rn::{mk, systems::misalian_kunimunean_time_formatter};
+
+use crate::{
+    clock::{is_quit, FullScreen, Screen},
+    time_since_midnight,
+    watch::POLL_INTERVAL,
+    SelectedZone,
+};
+
+/// The number of moments in a day.
+const MOMENTS_PER_DAY: u64 = 36 * 36 * 36;
+
+/// The smallest face worth drawing, in dots across.
+const MIN_DIAMETER: usize = 16;
+
+/// Run the clock face until the user quits with `q`, Escape, or Ctrl-C.
+pub fn run(tz: SelectedZone) -> Result<(), Box<dyn Error>> {
+    let _full_screen = FullScreen::enter()?;
+    run_loop(&mut io::stdout(), tz)
+}
+
+/// Redraw the face whenever the time shown under it changes or the terminal is
+/// resized.
+fn run_loop<W: Write>(out: &mut W, tz: SelectedZone) -> Result<(), Box<dyn Error>> {
+    let extended = misalian_kunimunean_time_formatter();
+
+    let mut screen = Screen::default();
+    let mut last = String::new();
+    loop {
+        let mut dirty = false;
+        if event::poll(POLL_INTERVAL)? {
+            match event::read()? {
+                Event::Key(key) if is_quit(key) => return Ok(()),
+                Event::Resize(..) => dirty = true,
+                _ => {}
+            }
+        }
+
+        let since = time_since_midnight(tz)?;
+        let current = extended.render_duration(since);
+        if dirty || current != last {
+            let moments = since.as_millis() as u64 * MOMENTS_PER_DAY / mk::MILLIS_PER_DAY as u64;
+            let (cols, rows) = terminal::size()?;
+            // leave room for the time underneath
+            let diameter = (cols as usize * 2).min(rows.saturating_sub(2) as usize * 4);
+            let mut lines = match diameter < MIN_DIAMETER {
+                true => vec![],
+                false => face(moments, diameter),
+            };
+            lines.push(String::new());
+            lines.push(current.clone());
+            screen.draw(out, lines, (cols, rows), dirty)?;
+            last = current;
+        }
+    }
+}
+
+/// A grid of dots, drawn as braille characters of two dots across and four
+/// down.
+struct Canvas {
+    width: usize,
+    height: usize,
+    dots: Vec<bool>,
+}
+
+impl Canvas {
+    /// Construct an empty canvas `width` dots across and `height` dots down.
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            dots: vec![false; width * height],
+        }
+    }
+
+    /// Set the dot nearest to (`x`, `y`), if it is on the canvas.
+    fn set(&mut self, x: f64, y: f64) {
+        let (x, y) = (x.round(), y.round());
+        if x >= 0.0 && y >= 0.0 && (x as usize) < self.width && (y as usize) < self.height {
+            self.dots[y as usize * self.width + x as usize] = true;
+        }
+    }
+
+    /// Draw a line from `from` to `to`.
+    fn line(&mut self, from: (f64, f64), to: (f64, f64)) {
+        let steps = (to.0 - from.0)
+            .abs()
+            .max((to.1 - from.1).abs())
+            .ceil()
+            .max(1.0);
+        for i in 0..=steps as usize {
+            let t = i as f64 / steps;
+            self.set(from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        }
+    }
+
+    /// Get the rows of braille characters, with blank cells as spaces.
+    fn rows(&self) -> Vec<String> {
+        // the bit of each dot in a cell, indexed by row and then column
+        const BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+        (0..self.height.div_ceil(4))
+            .map(|row| {
+                (0..self.width.div_ceil(2))
+                    .map(|col| {
+                        let mut bits = 0;
+                        for (dy, row_bits) in BITS.iter().enumerate() {
+                            for (dx, bit) in row_bits.iter().enumerate() {
+                                let (x, y) = (col * 2 + dx, row * 4 + dy);
+                                if x < self.width
+                                    && y < self.height
+                                    && self.dots[y * self.width + x]
+                                {
+                                    bits |= bit;
+                                }
+                            }
+                        }
+                        match bits {
+                            0 => ' ',
+                            bits => char::from_u32(0x2800 + bits).expect("braille is valid"),
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Get how far round the face each hand is, as a fraction of a turn, at
+/// `moments` moments after midnight: the lapse hand, which goes round once a
+/// day, the lull hand, once a lapse, and the moment hand, once a lull.
+fn hands(moments: u64) -> [f64; 3] {
+    [
+        (moments % MOMENTS_PER_DAY) as f64 / MOMENTS_PER_DAY as f64,
+        (moments % (36 * 36)) as f64 / (36 * 36) as f64,
+        (moments % 36) as f64 / 36.0,
+    ]
+}
+
+/// Draw the face `diameter` dots across at `moments` moments after midnight,
+/// with 36 tick marks, longer every sixth, and a hand for each of the lapse,
+/// the lull, and the moment.
+fn face(moments: u64, diameter: usize) -> Vec<String> {
+    let mut canvas = Canvas::new(diameter, diameter);
+    let radius = (diameter - 1) as f64 / 2.0;
+    // a point `length` of the way out along a turn of `fraction`, clockwise
+    // from the top
+    let point = |fraction: f64, length: f64| {
+        let (sin, cos) = (fraction * TAU).sin_cos();
+        (
+            radius + sin * length * radius,
+            radius - cos * length * radius,
+        )
+    };
+
+    for tick in 0..36 {
+        let inner = match tick % 6 {
+            0 => 0.8,
+            _ => 0.9,
+        };
+        let fraction = tick as f64 / 36.0;
+        canvas.line(point(fraction, inner), point(fraction, 1.0));
+    }
+    for (fraction, length) in hands(moments).iter().zip([0.45, 0.65, 0.75]) {
+        canvas.line(point(0.0, 0.0), point(*fraction, length));
+    }
+    canvas.rows()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn hand_positions() {
+        check!(hands(0) == [0.0, 0.0, 0.0]);
+        // 31:44:45, in senary
+        let moments = (19 * 36 + 28) * 36 + 29;
+        let [lapse, lull, moment] = hands(moments);
+        check!(lapse == moments as f64 / MOMENTS_PER_DAY as f64);
+        check!(lull == (28 * 36 + 29) as f64 / 1296.0);
+        check!(moment == 29.0 / 36.0);
+        check!(hands(MOMENTS_PER_DAY + 1) == hands(1));
+    }
+
+    #[test]
+    fn braille_dots() {
+        let mut canvas = Canvas::new(4, 4);
+        canvas.line((0.0, 0.0), (3.0, 0.0));
+        check!(canvas.rows() == ["⠉⠉"]);
+        canvas.set(3.0, 3.0);
+        canvas.set(9.0, -1.0);
+        check!(canvas.rows() == ["⠉⢉"]);
+    }
+
+    #[test]
+    fn draw_face() {
+        let midnight = face(0, 20);
+        check!(midnight.len() == 5);
+        check!(midnight.iter().all(|row| row.chars().count() == 10));
+        let blank = |rows: &[String], row: usize| rows[row].chars().nth(5) == Some(' ');
+        // every hand points up through the top tick at midnight, and the
+        // lapse hand points down at noon
+        check!((0..3).all(|row| !blank(&midnight, row)));
+        check!(blank(&midnight, 3));
+        check!(!blank(&face(MOMENTS_PER_DAY / 2, 20), 3));
+    }
+}
@@ -0,0 +1,128 @@
+//! C functions for rendering and parsing times, so that status bars and
+//! plugins written in C or C++ can call the formatters directly. The header
+//! declaring them, `rn.h`, is generated in the build script's output directory
+//! (`target/<profile>/build/rn-<hash>/out`) when building with the `ffi`
+//! feature.
+//!
+//! Rendering functions write a NUL-terminated string into a caller-provided
+//! buffer, truncating it to fit, and return the length of the whole string
+//! without the NUL, like `snprintf`. A return value of `len` or more means the
+//! string was truncated.
+
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_int},
+    ptr,
+};
+
+use crate::systems::{misalian_kunimunean_time_formatter, Registry};
+
+/// Render the time `ms` milliseconds after midnight in extended form into
+/// `buf`, which holds `len` bytes.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes, or null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn rn_render_mk(ms: u64, buf: *mut c_char, len: usize) -> usize {
+    write_c_str(&misalian_kunimunean_time_formatter().render(ms), buf, len)
+}
+
+/// Render the time `ms` milliseconds after the epoch of the built-in system
+/// named `system` into `buf`, which holds `len` bytes. Negative times are
+/// shown with a `-`. Returns -1, writing nothing, if there is no such system.
+///
+/// # Safety
+///
+/// `system` must be a valid NUL-terminated string, and `buf` must be valid
+/// for writes of `len` bytes, or null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn rn_render_system(
+    system: *const c_char,
+    ms: i64,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    let registry = Registry::builtin();
+    let system = match CStr::from_ptr(system)
+        .to_str()
+        .map(|name| registry.get(name))
+    {
+        Ok(Ok(system)) => system,
+        _ => return -1,
+    };
+    write_c_str(&system.formatter.render_signed(ms), buf, len) as isize
+}
+
+/// Parse a seximal time of day in extended, basic, or span form into
+/// milliseconds after midnight, stored in `ms`. Returns 0 on success, or -1 if
+/// `s` isn't a seximal time.
+///
+/// # Safety
+///
+/// `s` must be a valid NUL-terminated string, and `ms` must be valid for a
+/// write.
+#[no_mangle]
+pub unsafe extern "C" fn rn_parse_mk(s: *const c_char, ms: *mut u64) -> c_int {
+    match CStr::from_ptr(s).to_str().map(crate::mk::parse) {
+        Ok(Ok(time)) => {
+            *ms = time.millis() as u64;
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// Copy `s` into `buf`, which holds `len` bytes, truncating it to leave room
+/// for a NUL terminator. Returns the length of `s`.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes, or null if `len` is zero.
+unsafe fn write_c_str(s: &str, buf: *mut c_char, len: usize) -> usize {
+    if len > 0 && !buf.is_null() {
+        let copied = s.len().min(len - 1);
+        ptr::copy_nonoverlapping(s.as_ptr(), buf as *mut u8, copied);
+        *buf.add(copied) = 0;
+    }
+    s.len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    /// Call `render` with a buffer of `len` bytes, returning what it wrote and
+    /// what it returned.
+    fn rendered<R>(len: usize, render: impl FnOnce(*mut c_char, usize) -> R) -> (String, R) {
+        let mut buf = vec![1 as c_char; len.max(1)];
+        let returned = render(buf.as_mut_ptr(), len);
+        let written = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        (written.to_string_lossy().into_owned(), returned)
+    }
+
+    #[test]
+    fn render_into_buffers() {
+        let render = |buf, len| unsafe { rn_render_mk(47_521_888, buf, len) };
+        check!(rendered(16, render) == ("31:44:45.4".to_string(), 10));
+        check!(rendered(6, render) == ("31:44".to_string(), 10));
+        check!(unsafe { rn_render_mk(0, ptr::null_mut(), 0) } == 10);
+
+        let span =
+            |buf, len| unsafe { rn_render_system(b"span\0".as_ptr().cast(), 47_521_888, buf, len) };
+        check!(rendered(4, span) == ("314".to_string(), 3));
+        let unknown =
+            |buf, len| unsafe { rn_render_system(b"nope\0".as_ptr().cast(), 0, buf, len) };
+        check!(rendered(4, unknown).1 == -1);
+    }
+
+    #[test]
+    fn parse_from_c() {
+        let mut ms = 0;
+        check!(unsafe { rn_parse_mk(b"3144454\0".as_ptr().cast(), &mut ms) } == 0);
+        check!(ms == 47_521_605);
+        check!(unsafe { rn_parse_mk(b"60:00:00.0\0".as_ptr().cast(), &mut ms) } == -1);
+    }
+}
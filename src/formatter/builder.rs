@@ -0,0 +1,337 @@
+use alloc::vec::Vec;
+
+use num::rational::Ratio;
+
+use super::{
+    unit::{DEFAULT_PAD, DEFAULT_WIDTH},
+    Hierarchy, Rounding, Segment, SignPlacement, TimeFormatter, TimeUnit,
+};
+
+/// Marker for a required field of a builder that hasn't been set yet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Missing;
+
+/// Builder for a [`TimeFormatter`], created by [`TimeFormatter::builder`].
+///
+/// Segments are added in display order. The base is required, and so are the
+/// value and limit of each unit; leaving any of them out is a compile error.
+///
+/// ```
+/// use rn::formatter::TimeFormatter;
+///
+/// let formatter = TimeFormatter::builder()
+///     .base((1, 1))
+///     .unit("hour").value(3_600_000).limit(24)
+///     .literal(":")
+///     .unit("minute").value(60_000).limit(60)
+///     .build();
+/// assert_eq!(formatter.render(49_029_000), "13:37");
+/// ```
+///
+/// ```compile_fail
+/// use rn::formatter::TimeFormatter;
+///
+/// // no base
+/// let formatter = TimeFormatter::builder()
+///     .unit("hour").value(3_600_000).limit(24)
+///     .build();
+/// ```
+///
+/// ```compile_fail
+/// use rn::formatter::TimeFormatter;
+///
+/// // no limit
+/// let formatter = TimeFormatter::builder()
+///     .base((1, 1))
+///     .unit("hour").value(3_600_000)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeFormatterBuilder<'f, B> {
+    /// The proportion of base units to milliseconds, once set.
+    base: B,
+    /// The segments added so far.
+    segments: Vec<Segment<'f>>,
+    /// How to handle the part of a time too small for the smallest unit.
+    rounding: Rounding,
+    /// Where the sign of a negative time is written.
+    sign: SignPlacement,
+}
+
+impl<'f> TimeFormatterBuilder<'f, Missing> {
+    /// Construct a new builder with no base and no segments.
+    pub(super) fn new() -> Self {
+        Self {
+            base: Missing,
+            segments: Vec::new(),
+            rounding: Rounding::default(),
+            sign: SignPlacement::default(),
+        }
+    }
+
+    /// Set the proportion of base units to milliseconds.
+    pub fn base<R>(self, base: R) -> TimeFormatterBuilder<'f, Ratio<u64>>
+    where
+        R: Into<Ratio<u64>>,
+    {
+        TimeFormatterBuilder {
+            base: base.into(),
+            segments: self.segments,
+            rounding: self.rounding,
+            sign: self.sign,
+        }
+    }
+}
+
+impl<'f, B> TimeFormatterBuilder<'f, B> {
+    /// Add a literal string.
+    pub fn literal(mut self, literal: &'f str) -> Self {
+        self.segments.push(Segment::Literal(literal));
+        self
+    }
+
+    /// Set how to handle the part of a time too small for the smallest unit.
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Set where to write the sign of a negative time.
+    pub fn sign(mut self, sign: SignPlacement) -> Self {
+        self.sign = sign;
+        self
+    }
+
+    /// Start adding a unit with the passed name. The unit is decimal and padded
+    /// to two digits unless its radix or width are set.
+    pub fn unit(self, name: &'f str) -> UnitBuilder<'f, B, Missing, Missing> {
+        UnitBuilder {
+            formatter: self,
+            name,
+            radix: 10,
+            width: DEFAULT_WIDTH,
+            pad: DEFAULT_PAD,
+            digits: None,
+            uppercase: false,
+            unbounded: false,
+            value: Missing,
+            limit: Missing,
+        }
+    }
+}
+
+impl<'f> TimeFormatterBuilder<'f, Ratio<u64>> {
+    /// Build the formatter.
+    pub fn build(self) -> TimeFormatter<'f> {
+        let mut formatter = TimeFormatter::new(self.base, self.segments);
+        formatter.set_rounding(self.rounding);
+        formatter.set_sign(self.sign);
+        formatter
+    }
+}
+
+/// Builder for a unit of a [`TimeFormatter`], created by
+/// [`TimeFormatterBuilder::unit`]. Once its value and limit are set, the unit
+/// is added by continuing with the next segment or building the formatter.
+#[derive(Debug, Clone)]
+pub struct UnitBuilder<'f, B, V, L> {
+    /// The builder to add the unit to.
+    formatter: TimeFormatterBuilder<'f, B>,
+    /// The name of the unit.
+    name: &'f str,
+    /// The radix of the unit.
+    radix: u8,
+    /// How wide to pad the unit.
+    width: usize,
+    /// The character to pad the unit with.
+    pad: char,
+    /// The glyph for each digit of the unit, if set.
+    digits: Option<&'f str>,
+    /// Whether the unit's digits above 9 are uppercase letters.
+    uppercase: bool,
+    /// Whether the unit has no limit.
+    unbounded: bool,
+    /// The value of the unit in base units, once set.
+    value: V,
+    /// The number of the unit that fit in the next larger unit, once set.
+    limit: L,
+}
+
+impl<'f, B, V, L> UnitBuilder<'f, B, V, L> {
+    /// Set the radix to display the unit in.
+    pub fn radix(mut self, radix: u8) -> Self {
+        self.radix = radix;
+        self
+    }
+
+    /// Set how wide to pad the unit. A width of zero disables padding.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the character to pad the unit with, instead of `0`.
+    pub fn pad(mut self, pad: char) -> Self {
+        self.pad = pad;
+        self
+    }
+
+    /// Set the glyph for each digit of the unit, in order of value.
+    pub fn digits(mut self, digits: &'f str) -> Self {
+        self.digits = Some(digits);
+        self
+    }
+
+    /// Write the unit's digits above 9 as uppercase letters.
+    pub fn uppercase(mut self) -> Self {
+        self.uppercase = true;
+        self
+    }
+}
+
+impl<'f, B> UnitBuilder<'f, B, Missing, Missing> {
+    /// Set the value and limit of the unit to those derived for it in
+    /// `hierarchy`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the unit isn't in the hierarchy.
+    pub fn of(self, hierarchy: &Hierarchy) -> UnitBuilder<'f, B, u32, u32> {
+        let (value, limit) = hierarchy
+            .unit(self.name)
+            .unwrap_or_else(|| panic!("unit `{}` isn't in the hierarchy", self.name));
+        self.value(value).limit(limit)
+    }
+}
+
+impl<'f, B, L> UnitBuilder<'f, B, Missing, L> {
+    /// Set the value of the unit, in base units.
+    pub fn value(self, value: u32) -> UnitBuilder<'f, B, u32, L> {
+        UnitBuilder {
+            formatter: self.formatter,
+            name: self.name,
+            radix: self.radix,
+            width: self.width,
+            pad: self.pad,
+            digits: self.digits,
+            uppercase: self.uppercase,
+            unbounded: self.unbounded,
+            value,
+            limit: self.limit,
+        }
+    }
+}
+
+impl<'f, B, V> UnitBuilder<'f, B, V, Missing> {
+    /// Set how many of the unit fit in the next larger unit.
+    pub fn limit(self, limit: u32) -> UnitBuilder<'f, B, V, u32> {
+        UnitBuilder {
+            formatter: self.formatter,
+            name: self.name,
+            radix: self.radix,
+            width: self.width,
+            pad: self.pad,
+            digits: self.digits,
+            uppercase: self.uppercase,
+            unbounded: self.unbounded,
+            value: self.value,
+            limit,
+        }
+    }
+
+    /// Give the unit no limit, so that it counts every one of it in the time,
+    /// such as a count of days. It should be the largest unit.
+    pub fn unbounded(self) -> UnitBuilder<'f, B, V, u32> {
+        UnitBuilder {
+            unbounded: true,
+            ..self.limit(u32::MAX)
+        }
+    }
+}
+
+impl<'f, B> UnitBuilder<'f, B, u32, u32> {
+    /// Add the unit to the formatter.
+    fn finish(self) -> TimeFormatterBuilder<'f, B> {
+        let mut formatter = self.formatter;
+        let mut unit =
+            TimeUnit::with_radix(self.radix, self.name, self.value, self.limit, self.width);
+        unit.set_pad(self.pad);
+        unit.digits = self.digits;
+        unit.set_uppercase(self.uppercase);
+        unit.set_unbounded(self.unbounded);
+        formatter.segments.push(Segment::Value(unit));
+        formatter
+    }
+
+    /// Add the unit, followed by a literal string.
+    pub fn literal(self, literal: &'f str) -> TimeFormatterBuilder<'f, B> {
+        self.finish().literal(literal)
+    }
+
+    /// Add the unit, and start adding another unit with the passed name.
+    pub fn unit(self, name: &'f str) -> UnitBuilder<'f, B, Missing, Missing> {
+        self.finish().unit(name)
+    }
+}
+
+impl<'f> UnitBuilder<'f, Ratio<u64>, u32, u32> {
+    /// Add the unit, and build the formatter.
+    pub fn build(self) -> TimeFormatter<'f> {
+        self.finish().build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn build_senary_formatter() {
+        let mkt = TimeFormatter::builder()
+            .base((36 * 36 * 36 * 6, 86_400_000))
+            .unit("lapse")
+            .radix(6)
+            .value(7776)
+            .limit(36)
+            .literal(":")
+            .unit("lull")
+            .radix(6)
+            .value(216)
+            .limit(36)
+            .literal(":")
+            .unit("moment")
+            .radix(6)
+            .value(6)
+            .limit(36)
+            .literal(".")
+            .unit("snap")
+            .radix(6)
+            .value(1)
+            .limit(6)
+            .width(0)
+            .build();
+
+        check!(mkt.render(0) == "00:00:00.0");
+        check!(mkt.render(47521888) == "31:44:45.4");
+        check!(
+            mkt.units().map(TimeUnit::name).collect::<Vec<_>>()
+                == ["lapse", "lull", "moment", "snap"]
+        );
+    }
+
+    #[test]
+    fn build_in_any_order() {
+        let hours = TimeFormatter::builder()
+            .literal("T+")
+            .base((1, 3_600_000))
+            .unit("hour")
+            .limit(24)
+            .width(0)
+            .value(1)
+            .build();
+
+        check!(hours.render(7_200_000) == "T+2");
+    }
+}
@@ -0,0 +1,96 @@
+use alloc::{vec, vec::Vec};
+
+/// A declaration of units from the largest down, each a whole number of the
+/// next, from which the value and limit of every unit are derived.
+///
+/// ```
+/// use rn::formatter::{Hierarchy, TimeFormatter};
+///
+/// // 36 lapses a day; a lapse is 36 lulls, a lull is 36 moments, and a
+/// // moment is 6 snaps
+/// let units = Hierarchy::new("lapse", 36)
+///     .then("lull", 36)
+///     .then("moment", 36)
+///     .then("snap", 6);
+/// assert_eq!(units.unit("lull"), Some((216, 36)));
+///
+/// let formatter = TimeFormatter::builder()
+///     .base((units.total(), 86_400_000))
+///     .unit("lapse").of(&units).radix(6)
+///     .literal(":")
+///     .unit("lull").of(&units).radix(6)
+///     .build();
+/// assert_eq!(formatter.render(47_521_888), "31:44");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hierarchy<'h> {
+    /// The name of each unit and how many of it make up the next larger unit,
+    /// from the largest unit down.
+    units: Vec<(&'h str, u32)>,
+}
+
+impl<'h> Hierarchy<'h> {
+    /// Start a hierarchy with its largest unit, of which there are `count` in
+    /// the whole (e.g. in a day).
+    pub fn new(name: &'h str, count: u32) -> Self {
+        Self {
+            units: vec![(name, count)],
+        }
+    }
+
+    /// Declare that the smallest unit so far is made up of `count` of the unit
+    /// `name`.
+    pub fn then(mut self, name: &'h str, count: u32) -> Self {
+        self.units.push((name, count));
+        self
+    }
+
+    /// Get the value (in the smallest units) and the limit of the unit `name`,
+    /// if it is in the hierarchy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value doesn't fit in a `u32`.
+    pub fn unit(&self, name: &str) -> Option<(u32, u32)> {
+        let index = self.units.iter().position(|&(n, _)| n == name)?;
+        let value = self.units[index + 1..]
+            .iter()
+            .try_fold(1u32, |value, &(_, count)| value.checked_mul(count))
+            .expect("unit value overflows a u32");
+        Some((value, self.units[index].1))
+    }
+
+    /// The number of the smallest unit in the whole.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total doesn't fit in a `u64`.
+    pub fn total(&self) -> u64 {
+        self.units
+            .iter()
+            .try_fold(1u64, |total, &(_, count)| total.checked_mul(count as u64))
+            .expect("hierarchy total overflows a u64")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn derive_values_and_limits() {
+        let units = Hierarchy::new("lapse", 36)
+            .then("lull", 36)
+            .then("moment", 36)
+            .then("snap", 6);
+
+        check!(units.unit("lapse") == Some((7776, 36)));
+        check!(units.unit("lull") == Some((216, 36)));
+        check!(units.unit("moment") == Some((6, 36)));
+        check!(units.unit("snap") == Some((1, 6)));
+        check!(units.unit("span").is_none());
+        check!(units.total() == 36 * 36 * 36 * 6);
+    }
+}
@@ -0,0 +1,84 @@
+//! Conversions to and from the types of the `time` crate.
+
+use alloc::string::String;
+use core::convert::TryFrom;
+
+use time::{Duration, OffsetDateTime, Time};
+
+use super::{ParseError, TimeFormatter};
+
+/// The number of nanoseconds in a second.
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+/// The number of nanoseconds in a day.
+const NANOS_PER_DAY: u128 = 86_400 * NANOS_PER_SECOND;
+
+impl TimeFormatter<'_> {
+    /// Render the time of day `time`, including any fraction of a millisecond.
+    pub fn render_time(&self, time: Time) -> String {
+        let (hour, minute, second, nanos) = time.as_hms_nano();
+        let seconds = (hour as u128 * 60 + minute as u128) * 60 + second as u128;
+        self.render_nanos(seconds * NANOS_PER_SECOND + nanos as u128)
+    }
+
+    /// Render the time of day of `datetime`, in its own offset from UTC.
+    pub fn render_offset_date_time(&self, datetime: OffsetDateTime) -> String {
+        self.render_time(datetime.time())
+    }
+
+    /// Render the time `duration` after the start of the day (or of whichever
+    /// epoch the units count from), including any fraction of a millisecond.
+    /// Negative durations saturate to zero.
+    pub fn render_time_duration(&self, duration: Duration) -> String {
+        self.render_nanos(u128::try_from(duration.whole_nanoseconds()).unwrap_or_default())
+    }
+
+    /// Parse a string rendered by this formatter back into a time of day.
+    /// Rounds up, like [`parse`](Self::parse), and times of a day or more wrap
+    /// around.
+    pub fn parse_time(&self, s: &str) -> Result<Time, ParseError> {
+        let nanos = self.parse_nanos(s)? % NANOS_PER_DAY;
+        Ok(Time::MIDNIGHT + nanos_to_duration(nanos)?)
+    }
+
+    /// Parse a string rendered by this formatter back into the duration since
+    /// the start of the day (or of whichever epoch the units count from).
+    /// Rounds up, like [`parse`](Self::parse).
+    pub fn parse_time_duration(&self, s: &str) -> Result<Duration, ParseError> {
+        nanos_to_duration(self.parse_nanos(s)?)
+    }
+}
+
+/// Convert a number of nanoseconds to a duration, if it fits.
+fn nanos_to_duration(nanos: u128) -> Result<Duration, ParseError> {
+    let seconds = i64::try_from(nanos / NANOS_PER_SECOND).map_err(|_| ParseError::Overflow)?;
+    Ok(Duration::new(seconds, (nanos % NANOS_PER_SECOND) as i32))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+    use time::UtcOffset;
+
+    use crate::systems::misalian_kunimunean_time_formatter;
+
+    #[test]
+    fn time_round_trip() {
+        let mkt = misalian_kunimunean_time_formatter();
+        let time = Time::from_hms_milli(13, 12, 1, 888).unwrap();
+
+        check!(mkt.render_time(time) == "31:44:45.4");
+        check!(mkt.render_time_duration(Duration::milliseconds(47_521_888)) == "31:44:45.4");
+        check!(mkt.render_time_duration(Duration::milliseconds(-1)) == "00:00:00.0");
+        let datetime = OffsetDateTime::UNIX_EPOCH.replace_time(time);
+        check!(mkt.render_offset_date_time(datetime) == "31:44:45.4");
+        let offset = UtcOffset::from_hms(1, 0, 0).unwrap();
+        check!(mkt.render_offset_date_time(datetime.to_offset(offset)) == "33:14:45.4");
+
+        let parsed = mkt.parse_time("31:44:45.4").unwrap();
+        check!(mkt.render_time(parsed) == "31:44:45.4");
+        check!(Time::MIDNIGHT + mkt.parse_time_duration("31:44:45.4").unwrap() == parsed);
+        check!(mkt.parse_time("31:44:45.4 ") == Err(ParseError::TrailingInput(" ".into())));
+    }
+}
@@ -1,11 +1,54 @@
+//! Formatting of time expressions in arbitrary systems of units.
+
+mod builder;
+mod hierarchy;
+#[cfg(feature = "time")]
+mod interop;
+#[cfg(feature = "serde")]
+mod owned;
+mod parse;
+mod rounding;
 mod segment;
+mod sign;
+mod style;
+mod template;
 mod unit;
+mod validate;
 
-use std::iter::FromIterator;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    convert::TryFrom,
+    fmt::{self, Write},
+    iter::FromIterator,
+    slice,
+    time::Duration,
+};
+#[cfg(feature = "std")]
+use std::time::SystemTime;
 
-use num::rational::Ratio;
-pub use segment::Segment;
+pub use builder::{Missing, TimeFormatterBuilder, UnitBuilder};
+pub use hierarchy::Hierarchy;
+use num::{rational::Ratio, CheckedDiv, CheckedMul};
+#[cfg(feature = "serde")]
+pub use owned::{
+    OwnedSegment, OwnedTimeFormatter, OwnedTimeUnit, SegmentNames, UnsupportedSegment,
+};
+pub use parse::ParseError;
+pub use rounding::{Rounding, UnknownRounding};
+pub use segment::{RenderFn, Segment};
+pub use sign::SignPlacement;
+pub use style::{Color, Style, Theme};
+pub use template::TemplateError;
 pub use unit::TimeUnit;
+pub use validate::FormatterError;
+
+/// The number of nanoseconds in a millisecond.
+const NANOS_PER_MILLI: u128 = 1_000_000;
 
 /// A system of units for formatting time expressions.
 #[derive(Debug, Clone)]
@@ -14,10 +57,15 @@ pub struct TimeFormatter<'f> {
     /// tuple of the numerator and the denominator. For example, the Misalian
     /// Seximal Units use the snap as their basic unit. There are 279,936 snaps
     /// per every 86,400,000 milliseconds (279,936 snaps per day), so the `base`
-    /// would be `(279_936, 86_400_000)`.
+    /// would be `(279_936, 86_400_000)`. Times are converted to base units
+    /// from nanoseconds, so that no precision is lost to whole milliseconds.
     base: Ratio<u64>,
     /// The segments to render, in the order that they are displayed.
     segments: Vec<Segment<'f>>,
+    /// How to handle the part of a time too small for the smallest unit.
+    rounding: Rounding,
+    /// Where the sign of a negative time is written.
+    sign: SignPlacement,
 }
 
 impl<'f> TimeFormatter<'f> {
@@ -30,21 +78,390 @@ impl<'f> TimeFormatter<'f> {
         Self {
             base: base.into(),
             segments: Vec::from_iter(spec),
+            rounding: Rounding::default(),
+            sign: SignPlacement::default(),
+        }
+    }
+
+    /// Construct a new `TimeFormatter` with the passed specification, checking
+    /// that it makes sense (see [`validate`](Self::validate)).
+    pub fn try_new<R, I>(base: R, spec: I) -> Result<Self, FormatterError>
+    where
+        R: Into<Ratio<u64>>,
+        I: IntoIterator<Item = Segment<'f>>,
+    {
+        let formatter = Self::new(base, spec);
+        formatter.validate()?;
+        Ok(formatter)
+    }
+
+    /// Check that this formatter makes sense for a 24-hour day: the base isn't
+    /// zero, there is at least one unit, every unit has a radix of at least 2
+    /// that it has enough distinct digits for and a nonzero value and limit,
+    /// and each unit's limit makes it fill exactly one of a larger unit or the
+    /// whole day.
+    pub fn validate(&self) -> Result<(), FormatterError> {
+        self.validate_day(86_400_000)
+    }
+
+    /// Check that this formatter makes sense, as for
+    /// [`validate`](Self::validate), for a day of `day` milliseconds, such as
+    /// a Martian sol.
+    pub fn validate_day<D: Into<Ratio<u64>>>(&self, day: D) -> Result<(), FormatterError> {
+        validate::validate(self.base, day.into(), &self.segments)
+    }
+
+    /// Start building a new `TimeFormatter` one segment at a time.
+    pub fn builder() -> TimeFormatterBuilder<'f, Missing> {
+        TimeFormatterBuilder::new()
+    }
+
+    /// Construct a new `TimeFormatter` from a template such as
+    /// `"{lapse}:{lull}"`, where each placeholder names one of `units`. Format
+    /// codes such as `%L` may also be used as shorthand for the units they are
+    /// mapped to in `codes`.
+    pub fn from_template<R>(
+        base: R,
+        template: &'f str,
+        units: &[TimeUnit<'f>],
+        codes: &[(char, &str)],
+    ) -> Result<Self, TemplateError>
+    where
+        R: Into<Ratio<u64>>,
+    {
+        Ok(Self::new(base, template::compile(template, units, codes)?))
+    }
+
+    /// Construct a new `TimeFormatter` with the same base as this one from a
+    /// template whose placeholders name the units of this formatter.
+    pub fn with_template(&self, template: &'f str) -> Result<Self, TemplateError> {
+        let units: Vec<_> = self.units().cloned().collect();
+        Ok(Self {
+            base: self.base,
+            segments: template::compile(template, &units, &[])?,
+            rounding: self.rounding,
+            sign: self.sign,
+        })
+    }
+
+    /// Construct a new `TimeFormatter` with the same base as this one that
+    /// lists the value and name of each unit of this one that isn't zero, such
+    /// as `2 lapses, 3 lulls and 5 snaps`.
+    pub fn long_form(&self) -> Self {
+        Self {
+            base: self.base,
+            segments: vec![Segment::List(self.units().cloned().collect())],
+            rounding: self.rounding,
+            sign: self.sign,
         }
     }
 
-    pub fn render(&self, ms: u32) -> String {
+    /// The proportion of base units to milliseconds.
+    pub fn base(&self) -> Ratio<u64> {
+        self.base
+    }
+
+    /// How the part of a time too small for the smallest unit is handled.
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Set how to handle the part of a time too small for the smallest unit.
+    /// Times are floored unless set otherwise.
+    pub fn set_rounding(&mut self, rounding: Rounding) {
+        self.rounding = rounding;
+    }
+
+    /// Where the sign of a negative time is written.
+    pub fn sign(&self) -> SignPlacement {
+        self.sign
+    }
+
+    /// Set where to write the sign of a negative time. The sign leads unless
+    /// set otherwise.
+    pub fn set_sign(&mut self, sign: SignPlacement) {
+        self.sign = sign;
+    }
+
+    /// The units rendered by this formatter, in display order.
+    pub fn units(&self) -> impl Iterator<Item = &TimeUnit<'f>> {
+        self.segments.iter().flat_map(|segment| match segment {
+            Segment::Value(u) => slice::from_ref(u),
+            Segment::List(units) => units,
+            Segment::Literal(_)
+            | Segment::Name(..)
+            | Segment::UnitName(..)
+            | Segment::Conditional(..)
+            | Segment::Dynamic(_)
+            | Segment::Styled(..) => &[],
+        })
+    }
+
+    /// The units rendered by this formatter, in display order, for
+    /// modification.
+    pub fn units_mut(&mut self) -> impl Iterator<Item = &mut TimeUnit<'f>> {
+        self.segments.iter_mut().flat_map(|segment| match segment {
+            Segment::Value(u) => slice::from_mut(u),
+            Segment::List(units) => units,
+            Segment::Literal(_)
+            | Segment::Name(..)
+            | Segment::UnitName(..)
+            | Segment::Conditional(..)
+            | Segment::Dynamic(_)
+            | Segment::Styled(..) => &mut [],
+        })
+    }
+
+    /// The smallest unit rendered by this formatter, for modification. Of
+    /// units the same size, the first shown is returned.
+    pub fn smallest_unit_mut(&mut self) -> Option<&mut TimeUnit<'f>> {
+        self.units_mut().min_by_key(|unit| unit.value)
+    }
+
+    /// Render the time `ms` milliseconds after the start of the day (or of
+    /// whichever epoch the units count from).
+    ///
+    /// Every unit, including the largest, wraps around at its limit, so a time
+    /// a day or more after the start of the day is shown as the same time of
+    /// day. Times with more base units than fit in a `u64` saturate.
+    pub fn render(&self, ms: u64) -> String {
+        self.render_nanos(ms as u128 * NANOS_PER_MILLI)
+    }
+
+    /// Render the time `ns` nanoseconds after the start of the day (or of
+    /// whichever epoch the units count from).
+    pub fn render_nanos(&self, ns: u128) -> String {
         // assume that usually the string will have something like two digits
         // and a separator per section (e.g. "02:08:33.4" has three segments
         // with three characters each and one segment with one character).
         let mut out = String::with_capacity(self.segments.len() * 3);
-        // the amount of time to be formatted, adjusted to be in base units
-        let total = self.base * ms as u64;
-        for segment in &self.segments {
-            out += &segment.render(total);
-        }
+        write!(out, "{}", self.display_nanos(ns)).expect("writing to a string can't fail");
         out
     }
+
+    /// Render the time `duration` after the start of the day (or of whichever
+    /// epoch the units count from), including any fraction of a millisecond.
+    pub fn render_duration(&self, duration: Duration) -> String {
+        self.render_nanos(duration.as_nanos())
+    }
+
+    /// Render the time `duration` after the start of the day (or of whichever
+    /// epoch the units count from), including any fraction of a millisecond.
+    /// Negative durations saturate to zero.
+    #[cfg(feature = "chrono")]
+    pub fn render_chrono(&self, duration: chrono::Duration) -> String {
+        self.render_duration(duration.to_std().unwrap_or_default())
+    }
+
+    /// Render the instant `time`, counting from the instant `day_anchor`.
+    ///
+    /// Pass the start of any day (e.g. a midnight in the time zone to show)
+    /// as `day_anchor` to show the time of day, since the units wrap around
+    /// at their limits, or [`SystemTime::UNIX_EPOCH`] to count from the Unix
+    /// epoch. Times before `day_anchor` are shown as negative, as with
+    /// [`display_signed`](Self::display_signed).
+    #[cfg(feature = "std")]
+    pub fn render_system_time(&self, time: SystemTime, day_anchor: SystemTime) -> String {
+        let ns = match time.duration_since(day_anchor) {
+            Ok(after) => i128::try_from(after.as_nanos()).unwrap_or(i128::MAX),
+            Err(before) => i128::try_from(before.duration().as_nanos()).map_or(i128::MIN, |ns| -ns),
+        };
+        self.display_signed_nanos(ns).to_string()
+    }
+
+    /// Get the number of nanoseconds from the time `ns` nanoseconds after the
+    /// start of the day until the smallest unit (or its last fraction digit)
+    /// next ticks over, which is when the rendered time next changes. With
+    /// rounding other than [`Rounding::Floor`], the rendered time can also
+    /// change halfway between ticks, so the time until the next half tick is
+    /// returned instead. Never zero, and `None` if there are no units or the
+    /// time is too large.
+    pub fn until_next_change(&self, ns: u128) -> Option<u128> {
+        let step = self.units().map(TimeUnit::step).min()?;
+        let step = Ratio::new(
+            *step.numer() as u128 * NANOS_PER_MILLI,
+            *step.denom() as u128,
+        );
+        let base = Ratio::new(*self.base.numer() as u128, *self.base.denom() as u128);
+        let mut step = step.checked_div(&base)?;
+        if self.rounding != Rounding::Floor {
+            step /= 2;
+        }
+        let elapsed = Ratio::from_integer(ns);
+        let next = (elapsed.checked_div(&step)?.floor() + 1).checked_mul(&step)?;
+        Some((next - elapsed).ceil().to_integer())
+    }
+
+    /// The whole number of each unit of this formatter in the time `ns`
+    /// nanoseconds after the start of the day (or of whichever epoch the units
+    /// count from), less any larger units, in display order. The time is
+    /// rounded as when rendering it.
+    pub fn unit_values(&self, ns: u128) -> Vec<(&TimeUnit<'f>, u64)> {
+        let total = self.base_units(ns);
+        self.units()
+            .map(|unit| (unit, unit.count(total).to_integer()))
+            .collect()
+    }
+
+    /// Parse a string rendered by this formatter back into the number of base
+    /// units it represents.
+    pub fn parse_base_units(&self, s: &str) -> Result<u64, ParseError> {
+        parse::parse(&self.segments, s)
+    }
+
+    /// Parse a string rendered by this formatter back into a number of
+    /// milliseconds. Rounds up, so that rendering the result gives back the
+    /// same string (up to padding). Formatters that display the same time in
+    /// more than one set of units can't be parsed.
+    pub fn parse(&self, s: &str) -> Result<u64, ParseError> {
+        let total = Ratio::from_integer(self.parse_base_units(s)?);
+        let ms = total.checked_div(&self.base).ok_or(ParseError::Overflow)?;
+        Ok(ms.ceil().to_integer())
+    }
+
+    /// Parse a string rendered by this formatter back into a number of
+    /// nanoseconds. Rounds up, like [`parse`](Self::parse).
+    pub fn parse_nanos(&self, s: &str) -> Result<u128, ParseError> {
+        let total = Ratio::from_integer(self.parse_base_units(s)? as u128);
+        let base = Ratio::new(*self.base.numer() as u128, *self.base.denom() as u128);
+        let ns = total
+            .checked_div(&base)
+            .and_then(|ms| ms.checked_mul(&Ratio::from_integer(NANOS_PER_MILLI)))
+            .ok_or(ParseError::Overflow)?;
+        Ok(ns.ceil().to_integer())
+    }
+
+    /// Display the time `ms` milliseconds after the start of the day (or of
+    /// whichever epoch the units count from). Unlike [`render`](Self::render),
+    /// the segments are written straight to the output rather than collected
+    /// into a string first. Displaying it with `{:#}` shows the styles of units and styled groups.
+    pub fn display(&self, ms: u64) -> impl fmt::Display + '_ {
+        self.display_nanos(ms as u128 * NANOS_PER_MILLI)
+    }
+
+    /// Display the time `ns` nanoseconds after the start of the day (or of
+    /// whichever epoch the units count from).
+    pub fn display_nanos(&self, ns: u128) -> impl fmt::Display + '_ {
+        self.time_display(ns, false)
+    }
+
+    /// Display the time `ms` milliseconds after the start of the day (or of
+    /// whichever epoch the units count from), or before it if `ms` is negative.
+    /// The time before is shown as the time after, with a `-` placed as set by
+    /// [`set_sign`](Self::set_sign).
+    pub fn display_signed(&self, ms: i64) -> impl fmt::Display + '_ {
+        self.time_display(ms.unsigned_abs() as u128 * NANOS_PER_MILLI, ms < 0)
+    }
+
+    /// Display the time `ns` nanoseconds after the start of the day (or of
+    /// whichever epoch the units count from), or before it if `ns` is negative.
+    /// See [`display_signed`](Self::display_signed).
+    pub fn display_signed_nanos(&self, ns: i128) -> impl fmt::Display + '_ {
+        self.time_display(ns.unsigned_abs(), ns < 0)
+    }
+
+    /// Render the time `ms` milliseconds after the start of the day (or of
+    /// whichever epoch the units count from), or before it if `ms` is negative.
+    /// See [`display_signed`](Self::display_signed).
+    pub fn render_signed(&self, ms: i64) -> String {
+        self.display_signed(ms).to_string()
+    }
+
+    /// Render the time `ms` milliseconds after the start of the day (or of
+    /// whichever epoch the units count from), with the styles of its units and
+    /// styled groups as ANSI escape sequences. The same as displaying
+    /// [`display`](Self::display) with `{:#}`.
+    pub fn render_styled(&self, ms: u64) -> String {
+        format!("{:#}", self.display(ms))
+    }
+
+    /// Style the units of this formatter as `theme` gives, and put the literal
+    /// text between them in the theme's style for separators. The styles are
+    /// only shown when rendering with styles.
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.segments
+            .iter_mut()
+            .for_each(|segment| segment.apply_theme(theme));
+    }
+
+    /// Render the time `ms` milliseconds after the start of the day (or of
+    /// whichever epoch the units count from) into `out`, so that the caller
+    /// controls allocation. The output is appended to whatever `out` holds.
+    pub fn render_to<W: Write>(&self, ms: u64, out: &mut W) -> fmt::Result {
+        write!(out, "{}", self.display(ms))
+    }
+
+    /// Display a number of nanoseconds, which are before the start of the day
+    /// if `negative` is set.
+    fn time_display(&self, ns: u128, negative: bool) -> TimeDisplay<'_, 'f> {
+        TimeDisplay {
+            segments: &self.segments,
+            total: self.base_units(ns),
+            negative,
+            sign: self.sign,
+        }
+    }
+
+    /// Convert a number of nanoseconds to base units, saturating rather than
+    /// overflowing, and round it to the smallest part of a unit that is shown.
+    fn base_units(&self, ns: u128) -> Ratio<u64> {
+        // multiplying in u128 and reducing may bring the total back into range
+        let base = Ratio::new(*self.base.numer() as u128, *self.base.denom() as u128);
+        let total = base
+            .checked_mul(&Ratio::new(ns, NANOS_PER_MILLI))
+            .unwrap_or_else(|| Ratio::from_integer(u128::MAX));
+        let total = match (u64::try_from(*total.numer()), u64::try_from(*total.denom())) {
+            (Ok(numer), Ok(denom)) => Ratio::new_raw(numer, denom),
+            // too precise to keep the fraction, which no unit could show
+            _ => Ratio::from_integer(u64::try_from(total.to_integer()).unwrap_or(u64::MAX)),
+        };
+        let smallest = self.units().map(TimeUnit::step).min();
+        match (self.rounding, smallest) {
+            // each unit already drops what it can't show
+            (Rounding::Floor, _) | (_, None) => total,
+            (rounding, Some(step)) => rounding.round(total, step),
+        }
+    }
+}
+
+/// A time to be displayed by a formatter's segments.
+struct TimeDisplay<'d, 'f> {
+    /// The segments to display.
+    segments: &'d [Segment<'f>],
+    /// The amount of time to be displayed, in base units.
+    total: Ratio<u64>,
+    /// Whether the time is before the start of the day.
+    negative: bool,
+    /// Where to write the sign if the time is negative.
+    sign: SignPlacement,
+}
+
+impl fmt::Display for TimeDisplay<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the index of the segment to write the sign before; one past the end
+        // writes it after every segment
+        let sign = match (self.negative, self.sign) {
+            (false, _) => None,
+            (true, SignPlacement::Leading) => Some(0),
+            (true, SignPlacement::BeforeUnits) => Some(
+                self.segments
+                    .iter()
+                    .position(|segment| !segment.is_literal())
+                    .unwrap_or(self.segments.len()),
+            ),
+            (true, SignPlacement::Trailing) => Some(self.segments.len()),
+        };
+        for (i, segment) in self.segments.iter().enumerate() {
+            if sign == Some(i) {
+                f.write_char('-')?;
+            }
+            segment.render_fmt(f, self.total)?;
+        }
+        if sign == Some(self.segments.len()) {
+            f.write_char('-')?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -65,12 +482,449 @@ mod test {
                 Segment::Literal(":"),
                 Segment::Value((10, "second", 1_000, 60).into()),
                 Segment::Literal("."),
-                Segment::Value((10, "millisecond", 1, 1_000, 0).into()),
+                Segment::Value((10, "millisecond", 1, 1_000, 3).into()),
             ],
         );
 
-        check!(si_time_units.render(0) == "00:00:00.0");
+        check!(si_time_units.render(0) == "00:00:00.000");
         check!(si_time_units.render(7_679_092) == "02:07:59.092");
-        check!(si_time_units.render(49_029_000) == "13:37:09.0");
+        check!(si_time_units.render(49_029_000) == "13:37:09.000");
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let si_time_units = TimeFormatter::new(
+            (1, 1),
+            [
+                Segment::Value(("hour", 3_600_000, 24).into()),
+                Segment::Literal(":"),
+                Segment::Value(("minute", 60_000, 60).into()),
+                Segment::Literal(":"),
+                Segment::Value(("second", 1_000, 60).into()),
+            ],
+        );
+
+        check!(si_time_units.parse("02:07:59") == Ok(7_679_000));
+        check!(si_time_units.parse("2:7:59") == Ok(7_679_000));
+        check!(si_time_units.parse("02:07:60").is_err());
+        for ms in [0, 7_679_000, 49_029_000, 86_399_000] {
+            check!(si_time_units.parse(&si_time_units.render(ms)) == Ok(ms));
+        }
+    }
+
+    #[test]
+    fn display_matches_render() {
+        let si_time_units = TimeFormatter::new(
+            (1, 1),
+            [
+                Segment::Value(("hour", 3_600_000, 24).into()),
+                Segment::Literal(":"),
+                Segment::Value(("minute", 60_000, 60).into()),
+                Segment::Literal("."),
+                Segment::Value(("millisecond", 1, 1_000, 3).into()),
+            ],
+        );
+
+        for ms in [0, 7_679_092, 49_029_000, u64::MAX] {
+            check!(si_time_units.display(ms).to_string() == si_time_units.render(ms));
+        }
+        check!(format!("[{}]", si_time_units.display(7_679_092)) == "[02:07.092]");
+    }
+
+    #[test]
+    fn render_into_buffer() {
+        let si_time_units = TimeFormatter::new(
+            (1, 1),
+            [
+                Segment::Value(("hour", 3_600_000, 24).into()),
+                Segment::Literal(":"),
+                Segment::Value(("minute", 60_000, 60).into()),
+            ],
+        );
+
+        let mut buffer = String::from("> ");
+        check!(si_time_units.render_to(7_679_092, &mut buffer).is_ok());
+        check!(buffer == "> 02:07");
+
+        buffer.clear();
+        check!(si_time_units.render_to(49_029_000, &mut buffer).is_ok());
+        check!(buffer == "13:37");
+    }
+
+    #[test]
+    fn render_durations() {
+        // ms.µs
+        let micros = TimeFormatter::new(
+            (1_000, 1), // 1000µs = 1ms
+            [
+                Segment::Value(("millisecond", 1_000, 1_000, 3).into()),
+                Segment::Literal("."),
+                Segment::Value(("microsecond", 1, 1_000, 3).into()),
+            ],
+        );
+
+        check!(micros.render(1) == "001.000");
+        check!(micros.render_duration(Duration::from_micros(1_500)) == "001.500");
+        check!(micros.render_duration(Duration::from_nanos(2_000_999)) == "002.000");
+        #[cfg(feature = "chrono")]
+        {
+            check!(micros.render_chrono(chrono::Duration::microseconds(1_500)) == "001.500");
+            check!(micros.render_chrono(chrono::Duration::microseconds(-1_500)) == "000.000");
+        }
+
+        // saturates instead of overflowing
+        check!(micros.render(u64::MAX) == micros.render_duration(Duration::MAX));
+    }
+
+    #[test]
+    fn omit_zero_units() {
+        let unit = |name, value, limit| TimeUnit::with_radix(6, name, value, limit, 0);
+        let group = |name, value, limit, literal| {
+            Segment::nonzero(
+                unit(name, value, limit),
+                [
+                    Segment::Value(unit(name, value, limit)),
+                    Segment::Literal(literal),
+                ],
+            )
+        };
+        let humanized = TimeFormatter::new(
+            (36 * 36 * 36 * 6, 86_400_000),
+            [
+                group("lapse", 7776, 36, " lapses "),
+                group("lull", 216, 36, " lulls "),
+                group("moment", 6, 36, " moments "),
+                group("snap", 1, 6, " snaps"),
+            ],
+        );
+
+        let ms = |snaps: u64| (snaps * 86_400_000).div_ceil(36 * 36 * 36 * 6);
+        check!(humanized.render(ms(3 * 216 + 4)) == "3 lulls 4 snaps");
+        check!(humanized.display(ms(3 * 216 + 4)).to_string() == "3 lulls 4 snaps");
+        check!(humanized.render(ms(7776 + 6 + 1)) == "1 lapses 1 moments 1 snaps");
+        check!(humanized.render(0).is_empty());
+        check!(humanized.parse("3 lulls 4 snaps") == Ok(ms(3 * 216 + 4)));
+    }
+
+    #[test]
+    fn render_unit_names() {
+        let lapse = || TimeUnit::with_radix(6, "lapse", 7776, 36, 0).with_abbreviation("lp");
+        let lull = || TimeUnit::with_radix(6, "lull", 216, 36, 0).with_abbreviation("ll");
+        let formatter = TimeFormatter::new(
+            (36 * 36 * 36 * 6, 86_400_000),
+            [
+                Segment::Value(lapse()),
+                Segment::Literal(" "),
+                Segment::UnitName(lapse(), false),
+                Segment::Literal(" "),
+                Segment::Value(lull()),
+                Segment::UnitName(lull(), true),
+            ],
+        );
+
+        check!(formatter.render(47_521_888) == "31 lapses 44ll");
+        check!(formatter.parse_base_units("31 lapses 44ll") == Ok(19 * 7776 + 28 * 216));
+        check!(formatter.render(7776 * 86_400_000 / 279_936) == "1 lapse 0ll");
+        check!(
+            formatter.parse_base_units("31 lapes 44ll")
+                == Err(ParseError::ExpectedName("lapse".into()))
+        );
+    }
+
+    #[test]
+    fn render_long_form() {
+        let units = Hierarchy::new("lapse", 36)
+            .then("lull", 36)
+            .then("moment", 36)
+            .then("snap", 6);
+        let formatter = TimeFormatter::builder()
+            .base((units.total(), 86_400_000))
+            .unit("lapse")
+            .of(&units)
+            .radix(6)
+            .literal(":")
+            .unit("lull")
+            .of(&units)
+            .radix(6)
+            .literal(":")
+            .unit("moment")
+            .of(&units)
+            .radix(6)
+            .literal(".")
+            .unit("snap")
+            .of(&units)
+            .radix(6)
+            .build()
+            .long_form();
+
+        let ms = |snaps: u64| (snaps * 86_400_000).div_ceil(units.total());
+        let time = ms(((2 * 36 + 3) * 36 + 4) * 6 + 5);
+        check!(formatter.render(time) == "2 lapses, 3 lulls, 4 moments and 5 snaps");
+        check!(formatter.parse("2 lapses, 3 lulls, 4 moments and 5 snaps") == Ok(time));
+        check!(formatter.render(ms(216 + 1)) == "1 lull and 1 snap");
+        check!(formatter.parse("1 lull and 1 snap") == Ok(ms(216 + 1)));
+        check!(formatter.render(ms(19 * 7776)) == "31 lapses");
+        check!(formatter.render(0) == "0 snaps");
+        check!(
+            formatter.parse("1 lull and 10 snaps") == Err(ParseError::OutOfRange("snap".into()))
+        );
+        check!(formatter.parse("1 lulls, 2") == Err(ParseError::ExpectedValue("snap".into())));
+        check!(formatter.parse("2 lapses 4 moments") == Ok(ms((2 * 36 * 36 + 4) * 6)));
+        check!(formatter.parse("1 lull 1 snap") == formatter.parse("1 lull and 1 snap"));
+        check!(formatter.parse("1 lull ") == Err(ParseError::TrailingInput(" ".into())));
+    }
+
+    #[test]
+    fn render_dynamic_segments() {
+        const WORDS: [&str; 6] = ["zero", "one", "two", "three", "four", "five"];
+        let formatter = TimeFormatter::new(
+            (36 * 36 * 36 * 6, 86_400_000),
+            [
+                Segment::Value((6, "span", 1296, 216, 3).into()),
+                Segment::Literal(" and "),
+                Segment::dynamic(|total: Ratio<u64>| {
+                    let snaps = total.to_integer() % 6;
+                    format!("{} snaps", WORDS[snaps as usize])
+                }),
+            ],
+        );
+
+        check!(formatter.render(47_521_888) == "314 and four snaps");
+        check!(formatter.display(47_521_888).to_string() == "314 and four snaps");
+        check!(formatter.parse("314 and four snaps") == Err(ParseError::Dynamic));
+
+        // formatters with dynamic segments can still be shared between threads
+        fn shareable<T: Send + Sync>(_: &T) {}
+        shareable(&formatter);
+    }
+
+    #[test]
+    fn render_padding() {
+        let mut formatter = TimeFormatter::new(
+            (1, 1),
+            [
+                Segment::Value(("hour", 3_600_000, 24).into()),
+                Segment::Literal(":"),
+                Segment::Value(("minute", 60_000, 60, 3).into()),
+            ],
+        );
+        formatter
+            .units_mut()
+            .for_each(|unit| unit.set_pad('\u{2007}'));
+
+        check!(formatter.render(25_200_000) == "\u{2007}7:\u{2007}\u{2007}0");
+        check!(formatter.render(49_029_000) == "13:\u{2007}37");
+        check!(formatter.parse("\u{2007}7:\u{2007}\u{2007}0") == Ok(25_200_000));
+    }
+
+    #[test]
+    fn render_custom_digits() {
+        let dozenal = TimeFormatter::builder()
+            .base((1, 1))
+            .unit("hour")
+            .value(3_600_000)
+            .limit(24)
+            .radix(12)
+            .digits("0123456789XE")
+            .literal(":")
+            .unit("minute")
+            .value(60_000)
+            .limit(60)
+            .radix(12)
+            .digits("0123456789XE")
+            .build();
+
+        check!(dozenal.render(82_800_000 + 59 * 60_000) == "1E:4E");
+        check!(dozenal.render(10 * 3_600_000) == "0X:00");
+        check!(dozenal.parse("1E:4E") == Ok(82_800_000 + 59 * 60_000));
+        check!(dozenal.parse("1b:4b") == Err(ParseError::ExpectedLiteral(":".into())));
+        check!(dozenal.validate() == Ok(()));
+    }
+
+    #[test]
+    fn render_styles_and_themes() {
+        let mut formatter = TimeFormatter::builder()
+            .base((1, 1))
+            .unit("hour")
+            .value(3_600_000)
+            .limit(24)
+            .literal(":")
+            .unit("minute")
+            .value(60_000)
+            .limit(60)
+            .build();
+        let theme = Theme {
+            units: vec![("hour", Style::new().color(Color::Red).bold())],
+            separators: Style::new().dim(),
+        };
+        formatter.apply_theme(&theme);
+
+        check!(formatter.render(49_029_000) == "13:37");
+        check!(formatter.render_styled(49_029_000) == "\x1b[1;31m13\x1b[0m\x1b[2m:\x1b[0m37");
+        check!(formatter.parse("13:37") == Ok(49_020_000));
+        check!(formatter.validate() == Ok(()));
+
+        #[cfg(feature = "serde")]
+        {
+            let owned = OwnedTimeFormatter::try_from(&formatter).unwrap();
+            let serialized = toml::to_string(&owned).unwrap();
+            check!(toml::from_str::<OwnedTimeFormatter>(&serialized).as_ref() == Ok(&owned));
+            let names = owned.names();
+            check!(
+                owned.formatter(&names).render_styled(49_029_000)
+                    == formatter.render_styled(49_029_000)
+            );
+        }
+    }
+
+    #[test]
+    fn render_uppercase_digits() {
+        let mut hex = TimeFormatter::builder()
+            .base((65_536, 86_400_000))
+            .unit("hour")
+            .value(4096)
+            .limit(16)
+            .radix(16)
+            .width(1)
+            .uppercase()
+            .literal("_")
+            .unit("maxime")
+            .value(16)
+            .limit(256)
+            .radix(16)
+            .uppercase()
+            .build();
+
+        check!(hex.render(80_000_000) == "E_D0");
+        check!(hex.parse("E_D0") == Ok(79_987_500));
+        check!(hex.parse("e_d0") == Ok(79_987_500));
+        hex.units_mut().for_each(|unit| unit.set_uppercase(false));
+        check!(hex.render(80_000_000) == "e_d0");
+    }
+
+    #[test]
+    fn render_with_rounding() {
+        let mut hms = TimeFormatter::builder()
+            .base((1, 1))
+            .rounding(Rounding::Ceil)
+            .unit("hour")
+            .value(3_600_000)
+            .limit(24)
+            .literal(":")
+            .unit("minute")
+            .value(60_000)
+            .limit(60)
+            .literal(":")
+            .unit("second")
+            .value(1000)
+            .limit(60)
+            .build();
+
+        check!(hms.render(49_079_600) == "13:38:00");
+        check!(hms.render(86_399_001) == "00:00:00");
+        check!(hms.render(49_020_000) == "13:37:00");
+        hms.set_rounding(Rounding::HalfEven);
+        check!(hms.render(49_079_600) == "13:38:00");
+        check!(hms.render(49_078_500) == "13:37:58");
+        check!(hms.render(49_077_500) == "13:37:58");
+        hms.set_rounding(Rounding::Floor);
+        check!(hms.render(49_079_600) == "13:37:59");
+    }
+
+    #[test]
+    fn render_fraction_digits() {
+        let mut mkt = crate::systems::misalian_kunimunean_time_formatter();
+        let snap = mkt.smallest_unit_mut().unwrap();
+        check!(snap.name() == "snap");
+        snap.set_fraction_digits(2);
+
+        check!(mkt.render(47_521_888) == "31:44:45.453");
+        check!(mkt.render(0) == "00:00:00.000");
+        mkt.set_rounding(Rounding::Ceil);
+        check!(mkt.render(47_521_888) == "31:44:45.454");
+        check!(mkt.parse_base_units("31:44:45.454") == Ok(153_970));
+        check!(mkt.parse_base_units("31:44:45.4").is_err());
+        check!(mkt.long_form().render(47_521_888) == "31 lapses, 44 lulls, 45 moments and 4 snaps");
+        check!(mkt.long_form().parse_base_units("31 lapses and 4 snaps") == Ok(147_748));
+
+        let mut lull = TimeUnit::with_radix(6, "lull", 216, 36, 2);
+        lull.set_point(".");
+        lull.set_fraction_digits(2);
+        let lulls = TimeFormatter::new((279_936, 86_400_000), [Segment::Value(lull)]);
+        check!(lulls.render(47_521_888) == "44.45");
+        check!(lulls.parse_base_units("44.45") == Ok(6_048));
+    }
+
+    #[test]
+    fn render_signed_times() {
+        let mut beats = TimeFormatter::builder()
+            .base((1, 86_400))
+            .literal("@")
+            .unit("beat")
+            .value(1)
+            .limit(1000)
+            .width(3)
+            .build();
+
+        check!(beats.render_signed(43_200_000) == "@500");
+        check!(beats.render_signed(-43_200_000) == "-@500");
+        check!(beats.render_signed(i64::MIN).starts_with("-@"));
+        beats.set_sign(SignPlacement::BeforeUnits);
+        check!(beats.render_signed(-86_400) == "@-001");
+        beats.set_sign(SignPlacement::Trailing);
+        check!(beats.render_signed(-86_400) == "@001-");
+        check!(beats.render_signed(86_400) == "@001");
+        check!(beats.display_signed_nanos(-86_400_000_000).to_string() == "@001-");
+        check!(beats.display_signed_nanos(86_399_999_999).to_string() == "@000");
+    }
+
+    #[test]
+    fn time_until_next_change() {
+        let mut mkt = crate::systems::misalian_kunimunean_time_formatter();
+        // a snap is 308_641_975.3 nanoseconds
+        check!(mkt.until_next_change(0) == Some(308_641_976));
+        check!(mkt.until_next_change(308_641_976) == Some(308_641_975));
+        check!(mkt.until_next_change(308_641_975) == Some(1));
+        mkt.set_rounding(Rounding::HalfEven);
+        check!(mkt.until_next_change(0) == Some(154_320_988));
+        mkt.set_rounding(Rounding::Floor);
+        mkt.smallest_unit_mut().unwrap().set_fraction_digits(1);
+        check!(mkt.until_next_change(0) == Some(51_440_330));
+        check!(TimeFormatter::new((1, 1), [Segment::Literal("@")])
+            .until_next_change(0)
+            .is_none());
+    }
+
+    #[test]
+    fn values_of_units() {
+        let mkt = crate::systems::misalian_kunimunean_time_formatter();
+        let values: Vec<_> = mkt
+            .unit_values(47_521_888 * NANOS_PER_MILLI)
+            .into_iter()
+            .map(|(unit, value)| (unit.name(), value, unit.render(Ratio::from_integer(value))))
+            .collect();
+        check!(
+            values
+                == [
+                    ("lapse", 19, "31".to_string()),
+                    ("lull", 28, "44".to_string()),
+                    ("moment", 29, "45".to_string()),
+                    ("snap", 4, "4".to_string()),
+                ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn render_system_times() {
+        let mkt = crate::systems::misalian_kunimunean_time_formatter();
+        let midnight = SystemTime::UNIX_EPOCH + Duration::from_secs(19_675 * 86_400);
+        let time = midnight + Duration::from_millis(47_521_888);
+
+        check!(mkt.render_system_time(time, midnight) == "31:44:45.4");
+        check!(mkt.render_system_time(time, SystemTime::UNIX_EPOCH) == "31:44:45.4");
+        check!(mkt.render_system_time(midnight, time) == "-31:44:45.4");
+        let later = time + Duration::from_nanos(999_999);
+        check!(mkt.render_system_time(later, midnight) == "31:44:45.4");
     }
 }
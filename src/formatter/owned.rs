@@ -0,0 +1,384 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{convert::TryFrom, error::Error, fmt, slice};
+
+use serde::{ser, Deserialize, Serialize, Serializer};
+
+use super::{unit::DEFAULT_PAD, Rounding, Segment, SignPlacement, Style, TimeFormatter, TimeUnit};
+
+/// A [`TimeFormatter`] that owns its strings, so that it can be deserialized
+/// and kept independently of whatever it was read from. Borrow it as a
+/// `TimeFormatter` with [`formatter`](Self::formatter) to render times, passing
+/// the lists of names borrowed by [`names`](Self::names):
+///
+/// ```
+/// use rn::formatter::OwnedTimeFormatter;
+/// # use rn::systems::misalian_kunimunean_time_formatter;
+/// # use std::convert::TryFrom;
+/// # let owned = OwnedTimeFormatter::try_from(&misalian_kunimunean_time_formatter()).unwrap();
+///
+/// let names = owned.names();
+/// let formatter = owned.formatter(&names);
+/// assert_eq!(formatter.render(47_521_888), "31:44:45.4");
+/// ```
+///
+/// A `TimeFormatter` serializes the same way as the `OwnedTimeFormatter` made
+/// from it. Formatters with conditional or dynamic segments can't be made owned
+/// or serialized, since those segments hold functions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnedTimeFormatter {
+    /// The proportion of base units to milliseconds, as a numerator and a
+    /// denominator.
+    pub base: (u64, u64),
+    /// How to handle the part of a time too small for the smallest unit.
+    #[serde(default)]
+    pub rounding: Rounding,
+    /// Where the sign of a negative time is written.
+    #[serde(default)]
+    pub sign: SignPlacement,
+    /// The segments to render, in the order that they are displayed.
+    pub segments: Vec<OwnedSegment>,
+}
+
+/// A [`Segment`] that owns its strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OwnedSegment {
+    /// A unit, displayed as the name at the index given by its value.
+    Name {
+        /// The name of each value.
+        names: Vec<String>,
+        /// The unit whose value selects the name.
+        unit: OwnedTimeUnit,
+    },
+    /// The name of a unit, abbreviated if `abbreviated` is set.
+    UnitName {
+        /// Whether to use the unit's abbreviation.
+        abbreviated: bool,
+        /// The unit to name.
+        unit: OwnedTimeUnit,
+    },
+    /// A unit, displayed as its value.
+    Value {
+        /// The unit to display.
+        unit: OwnedTimeUnit,
+    },
+    /// The value and name of each unit that isn't zero, in a list.
+    List {
+        /// The units to list.
+        units: Vec<OwnedTimeUnit>,
+    },
+    /// A literal string.
+    Literal {
+        /// The string to display.
+        literal: String,
+    },
+    /// A group of segments, displayed in a style.
+    Styled {
+        /// The style to display the group in.
+        style: Style,
+        /// The segments in the group.
+        segments: Vec<OwnedSegment>,
+    },
+}
+
+/// A [`TimeUnit`] that owns its name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnedTimeUnit {
+    /// The name of the unit.
+    pub name: String,
+    /// A short form of the name of the unit, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abbreviation: Option<String>,
+    /// The plural of the name of the unit, if it isn't regular.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plural: Option<String>,
+    /// The radix the unit is displayed in.
+    pub radix: u8,
+    /// The value of the unit in base units.
+    pub value: u32,
+    /// How many of the unit fit in the next larger unit.
+    pub limit: u32,
+    /// Whether the unit has no limit.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub unbounded: bool,
+    /// How wide to pad the unit.
+    pub width: usize,
+    /// The character to pad the unit with.
+    #[serde(default = "default_pad")]
+    pub pad: char,
+    /// Whether the usual digits above 9 are uppercase letters.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub uppercase: bool,
+    /// The glyph for each digit, in order of value, if not the usual ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digits: Option<String>,
+    /// How many digits of the part of the unit below one to show after it.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub fraction_digits: usize,
+    /// The radix point written before the fraction digits, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub point: Option<String>,
+    /// The style to display the unit in, if not plain.
+    #[serde(default, skip_serializing_if = "Style::is_plain")]
+    pub style: Style,
+}
+
+/// The names of each name segment of an [`OwnedTimeFormatter`] or
+/// [`OwnedSegment`], in order, borrowed as the slices that a borrowed
+/// [`Segment::Name`] holds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SegmentNames<'o>(Vec<Vec<&'o str>>);
+
+/// The character that units are padded with unless another is given.
+fn default_pad() -> char {
+    DEFAULT_PAD
+}
+
+/// Whether `value` is false, so that it can be left out when serializing.
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Whether `value` is zero, so that it can be left out when serializing.
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// Error returned when a segment that holds a function is made owned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedSegment;
+
+impl fmt::Display for UnsupportedSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "conditional and dynamic segments can't be made owned")
+    }
+}
+
+impl Error for UnsupportedSegment {}
+
+impl OwnedTimeFormatter {
+    /// Borrow the names of this formatter's name segments, for
+    /// [`formatter`](Self::formatter).
+    pub fn names(&self) -> SegmentNames<'_> {
+        let mut names = SegmentNames::default();
+        for segment in &self.segments {
+            segment.collect_names(&mut names.0);
+        }
+        names
+    }
+
+    /// Borrow this as a [`TimeFormatter`], whose name segments borrow their
+    /// names from `names`, as returned by [`names`](Self::names). Name
+    /// segments without a list in `names` have no names.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the denominator of the base is zero.
+    pub fn formatter<'a>(&'a self, names: &'a SegmentNames<'a>) -> TimeFormatter<'a> {
+        let mut names = names.0.iter();
+        let segments: Vec<_> = self
+            .segments
+            .iter()
+            .map(|segment| segment.borrow(&mut names))
+            .collect();
+        let mut formatter = TimeFormatter::new(self.base, segments);
+        formatter.set_rounding(self.rounding);
+        formatter.set_sign(self.sign);
+        formatter
+    }
+}
+
+impl OwnedSegment {
+    /// Borrow the names of this segment's name segments, for
+    /// [`segment`](Self::segment).
+    pub fn names(&self) -> SegmentNames<'_> {
+        let mut names = SegmentNames::default();
+        self.collect_names(&mut names.0);
+        names
+    }
+
+    /// Borrow this as a [`Segment`], whose name segments borrow their names
+    /// from `names`, as returned by [`names`](Self::names).
+    pub fn segment<'a>(&'a self, names: &'a SegmentNames<'a>) -> Segment<'a> {
+        self.borrow(&mut names.0.iter())
+    }
+
+    /// Add the names of each name segment in this segment to `names`.
+    fn collect_names<'o>(&'o self, names: &mut Vec<Vec<&'o str>>) {
+        match self {
+            Self::Name { names: own, .. } => names.push(own.iter().map(String::as_str).collect()),
+            Self::Styled { segments, .. } => {
+                for segment in segments {
+                    segment.collect_names(names);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Borrow this as a [`Segment`], taking the names of each name segment in
+    /// turn from `names`.
+    fn borrow<'a>(&'a self, names: &mut slice::Iter<'a, Vec<&'a str>>) -> Segment<'a> {
+        match self {
+            Self::Literal { literal } => Segment::Literal(literal),
+            Self::Value { unit } => Segment::Value(unit.unit()),
+            Self::UnitName { abbreviated, unit } => Segment::UnitName(unit.unit(), *abbreviated),
+            Self::List { units } => Segment::List(units.iter().map(OwnedTimeUnit::unit).collect()),
+            Self::Name { unit, .. } => {
+                Segment::Name(unit.unit(), names.next().map_or(&[], Vec::as_slice))
+            }
+            Self::Styled { style, segments } => Segment::Styled(
+                *style,
+                segments
+                    .iter()
+                    .map(|segment| segment.borrow(names))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl OwnedTimeUnit {
+    /// Borrow this as a [`TimeUnit`].
+    pub fn unit(&self) -> TimeUnit<'_> {
+        let mut unit =
+            TimeUnit::with_radix(self.radix, &self.name, self.value, self.limit, self.width);
+        unit.abbreviation = self.abbreviation.as_deref();
+        unit.plural = self.plural.as_deref();
+        unit.set_pad(self.pad);
+        unit.set_uppercase(self.uppercase);
+        unit.set_unbounded(self.unbounded);
+        unit.digits = self.digits.as_deref();
+        unit.set_fraction_digits(self.fraction_digits);
+        unit.point = self.point.as_deref();
+        unit.set_style(self.style);
+        unit
+    }
+}
+
+impl TryFrom<&TimeFormatter<'_>> for OwnedTimeFormatter {
+    type Error = UnsupportedSegment;
+
+    fn try_from(formatter: &TimeFormatter) -> Result<Self, Self::Error> {
+        Ok(Self {
+            base: (*formatter.base.numer(), *formatter.base.denom()),
+            rounding: formatter.rounding,
+            sign: formatter.sign,
+            segments: formatter
+                .segments
+                .iter()
+                .map(OwnedSegment::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<&Segment<'_>> for OwnedSegment {
+    type Error = UnsupportedSegment;
+
+    fn try_from(segment: &Segment) -> Result<Self, Self::Error> {
+        Ok(match segment {
+            Segment::Literal(literal) => Self::Literal {
+                literal: literal.to_string(),
+            },
+            Segment::Value(unit) => Self::Value { unit: unit.into() },
+            Segment::UnitName(unit, abbreviated) => Self::UnitName {
+                abbreviated: *abbreviated,
+                unit: unit.into(),
+            },
+            Segment::Name(unit, names) => Self::Name {
+                names: names.iter().map(|name| name.to_string()).collect(),
+                unit: unit.into(),
+            },
+            Segment::List(units) => Self::List {
+                units: units.iter().map(OwnedTimeUnit::from).collect(),
+            },
+            Segment::Styled(style, segments) => Self::Styled {
+                style: *style,
+                segments: segments
+                    .iter()
+                    .map(OwnedSegment::try_from)
+                    .collect::<Result<_, _>>()?,
+            },
+            Segment::Conditional(..) | Segment::Dynamic(_) => return Err(UnsupportedSegment),
+        })
+    }
+}
+
+impl From<&TimeUnit<'_>> for OwnedTimeUnit {
+    fn from(unit: &TimeUnit) -> Self {
+        Self {
+            name: unit.name().to_string(),
+            abbreviation: unit.abbreviation.map(str::to_string),
+            plural: unit.plural.map(str::to_string),
+            radix: unit.radix(),
+            value: unit.value,
+            limit: unit.limit,
+            unbounded: unit.is_unbounded(),
+            width: unit.width(),
+            pad: unit.pad(),
+            uppercase: unit.uppercase(),
+            digits: unit.digits.map(str::to_string),
+            fraction_digits: unit.fraction_digits(),
+            point: unit.point().map(str::to_string),
+            style: unit.style(),
+        }
+    }
+}
+
+impl Serialize for TimeFormatter<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        OwnedTimeFormatter::try_from(self)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl Serialize for Segment<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        OwnedSegment::try_from(self)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl Serialize for TimeUnit<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        OwnedTimeUnit::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn serde_round_trip() {
+        let names = ["am", "pm"];
+        let formatter = TimeFormatter::new(
+            (1, 1),
+            [
+                Segment::Value((12, "hour", 3_600_000, 12, 1).into()),
+                Segment::UnitName(TimeUnit::new("hour", 1, 1, 0).with_abbreviation("h"), true),
+                Segment::Literal(" "),
+                Segment::Name(("half", 43_200_000, 2).into(), &names),
+            ],
+        );
+
+        let serialized = toml::to_string(&formatter).unwrap();
+        let owned: OwnedTimeFormatter = toml::from_str(&serialized).unwrap();
+        check!(Ok(&owned) == OwnedTimeFormatter::try_from(&formatter).as_ref());
+        check!(owned.formatter(&owned.names()).render(49_029_000) == "1h pm");
+        check!(toml::to_string(&owned).unwrap() == serialized);
+
+        let unit = || TimeUnit::new("hour", 3_600_000, 24, 0);
+        let conditional = Segment::nonzero(unit(), [Segment::Value(unit())]);
+        check!(OwnedSegment::try_from(&conditional) == Err(UnsupportedSegment));
+        check!(toml::to_string(&conditional).is_err());
+    }
+}
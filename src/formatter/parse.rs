@@ -0,0 +1,339 @@
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{error::Error, fmt};
+
+use super::{Segment, TimeUnit};
+
+/// Error produced when a string cannot be parsed by a formatter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The literal text of a segment was missing.
+    ExpectedLiteral(String),
+    /// A unit had no digits.
+    ExpectedValue(String),
+    /// None of the names of a unit were found.
+    ExpectedName(String),
+    /// A unit's value was at least its limit.
+    OutOfRange(String),
+    /// The time is too large to be represented.
+    Overflow,
+    /// A segment rendered by a function can't be parsed.
+    Dynamic,
+    /// Text was left over after the last segment.
+    TrailingInput(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExpectedLiteral(literal) => write!(f, "expected `{}`", literal),
+            Self::ExpectedValue(unit) => write!(f, "expected a value for the {}", unit),
+            Self::ExpectedName(unit) => write!(f, "expected a name for the {}", unit),
+            Self::OutOfRange(unit) => write!(f, "the {} is out of range", unit),
+            Self::Overflow => write!(f, "the time is too large"),
+            Self::Dynamic => write!(f, "dynamic segments can't be parsed"),
+            Self::TrailingInput(rest) => write!(f, "unexpected `{}` after the time", rest),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse a string rendered from `segments`, returning the total number of base
+/// units. Literals must match exactly. Values may have any number of digits in
+/// the unit's radix, except that a value directly followed by another unit
+/// takes exactly as many digits as its padding width. Names match the longest
+/// name of the unit that the string starts with, and unit names may be singular
+/// or plural. Conditional groups are parsed if present, and count for nothing
+/// if left out. Lists may name their units in any order, and may separate
+/// them with just a space.
+pub fn parse(segments: &[Segment], s: &str) -> Result<u64, ParseError> {
+    let (total, rest) = parse_prefix(segments, s)?;
+    if !rest.is_empty() {
+        return Err(ParseError::TrailingInput(rest.to_string()));
+    }
+    Ok(total)
+}
+
+/// Parse the start of a string rendered from `segments`, returning the total
+/// number of base units and the rest of the string.
+fn parse_prefix<'s>(segments: &[Segment], s: &'s str) -> Result<(u64, &'s str), ParseError> {
+    let mut rest = s;
+    let mut total: u64 = 0;
+    for (i, segment) in segments.iter().enumerate() {
+        let (count, unit) = match segment {
+            Segment::Literal(literal) => {
+                rest = rest
+                    .strip_prefix(literal)
+                    .ok_or_else(|| ParseError::ExpectedLiteral(literal.to_string()))?;
+                continue;
+            }
+            Segment::Value(unit) => {
+                let adjacent = matches!(
+                    segments.get(i + 1),
+                    Some(Segment::Value(_) | Segment::Name(..))
+                );
+                let (count, remaining) = parse_value(unit, rest, adjacent)?;
+                rest = remaining;
+                (count, unit)
+            }
+            Segment::Name(unit, names) => {
+                let (index, name) = names
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, name)| !name.is_empty() && rest.starts_with(*name))
+                    .max_by_key(|(_, name)| name.len())
+                    .ok_or_else(|| ParseError::ExpectedName(unit.name().to_string()))?;
+                rest = &rest[name.len()..];
+                (index as u64, unit)
+            }
+            Segment::UnitName(unit, abbreviated) => {
+                let names = match abbreviated {
+                    true => vec![Cow::Borrowed(unit.abbreviation())],
+                    false => vec![Cow::Borrowed(unit.name()), unit.plural()],
+                };
+                let (_, name) = longest_name(&[unit, unit], &names, rest)
+                    .ok_or_else(|| ParseError::ExpectedName(unit.name().to_string()))?;
+                rest = &rest[name.len()..];
+                continue;
+            }
+            Segment::List(units) => {
+                let (count, remaining) = parse_list(units, rest)?;
+                total = total.checked_add(count).ok_or(ParseError::Overflow)?;
+                rest = remaining;
+                continue;
+            }
+            Segment::Dynamic(_) => return Err(ParseError::Dynamic),
+            Segment::Styled(_, group) => {
+                let (count, remaining) = parse_prefix(group, rest)?;
+                total = total.checked_add(count).ok_or(ParseError::Overflow)?;
+                rest = remaining;
+                continue;
+            }
+            Segment::Conditional(_, _, group) => {
+                if let Ok((count, remaining)) = parse_prefix(group, rest) {
+                    total = total.checked_add(count).ok_or(ParseError::Overflow)?;
+                    rest = remaining;
+                }
+                continue;
+            }
+        };
+        if !unit.fits(count) {
+            return Err(ParseError::OutOfRange(unit.name().to_string()));
+        }
+        total = count
+            .checked_mul(unit.value as u64)
+            .and_then(|value| total.checked_add(value))
+            .ok_or(ParseError::Overflow)?;
+    }
+    Ok((total, rest))
+}
+
+/// Parse a list rendered from `units`, returning the total number of base units
+/// and the rest of the string. Items may also be separated by a single space,
+/// as in `2 lapses 15 moments`, as long as another item follows it.
+fn parse_list<'s>(units: &[TimeUnit], s: &'s str) -> Result<(u64, &'s str), ParseError> {
+    // lists write whole values, without fraction digits
+    let units: Vec<_> = units
+        .iter()
+        .map(|unit| {
+            let mut unit = unit.clone();
+            unit.set_fraction_digits(0);
+            unit
+        })
+        .collect();
+    let last = units.last().map(TimeUnit::name).unwrap_or_default();
+    let names: Vec<_> = units
+        .iter()
+        .flat_map(|unit| [Cow::Borrowed(unit.name()), unit.plural()])
+        .collect();
+    // each unit's singular and plural are next to each other
+    let owners: Vec<_> = units.iter().flat_map(|unit| [unit, unit]).collect();
+
+    // parse the value and name of one unit at the start of `s`
+    let item = |s: &'s str| -> Result<(u64, &'s str), ParseError> {
+        let (digits, after) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::ExpectedValue(last.to_string()))?;
+        let (unit, name) = longest_name(&owners, &names, after)
+            .ok_or_else(|| ParseError::ExpectedName(last.to_string()))?;
+        let count = match parse_value(unit, digits, false)? {
+            (count, "") => count,
+            _ => return Err(ParseError::ExpectedValue(unit.name().to_string())),
+        };
+        if !unit.fits(count) {
+            return Err(ParseError::OutOfRange(unit.name().to_string()));
+        }
+        let value = count
+            .checked_mul(unit.value as u64)
+            .ok_or(ParseError::Overflow)?;
+        Ok((value, &after[name.len()..]))
+    };
+
+    let (mut total, mut rest) = item(s)?;
+    loop {
+        let (value, after) = match rest
+            .strip_prefix(", ")
+            .or_else(|| rest.strip_prefix(" and "))
+        {
+            Some(next) => item(next)?,
+            None => match rest.strip_prefix(' ').map(item) {
+                Some(Ok(next)) => next,
+                _ => return Ok((total, rest)),
+            },
+        };
+        total = total.checked_add(value).ok_or(ParseError::Overflow)?;
+        rest = after;
+    }
+}
+
+/// Find the longest of `names` that `s` starts with, along with the unit at the
+/// same index of `units`.
+fn longest_name<'n, 'u, 'f>(
+    units: &[&'u TimeUnit<'f>],
+    names: &'n [Cow<str>],
+    s: &str,
+) -> Option<(&'u TimeUnit<'f>, &'n str)> {
+    units
+        .iter()
+        .zip(names)
+        .filter(|(_, name)| !name.is_empty() && s.starts_with(name.as_ref()))
+        .max_by_key(|(_, name)| name.len())
+        .map(|(unit, name)| (*unit, name.as_ref()))
+}
+
+/// Parse the digits of a value at the start of `s`, after any padding,
+/// returning the value and the rest of the string. With `fixed`, exactly as
+/// many characters as the unit's padding width are read. Any fraction digits,
+/// after the unit's point if it has one, are read and dropped, since base
+/// units are whole.
+fn parse_value<'s>(unit: &TimeUnit, s: &'s str, fixed: bool) -> Result<(u64, &'s str), ParseError> {
+    let pad = unit.pad();
+    let padding = match unit.digit_value(pad) {
+        Some(_) => 0,
+        None => s
+            .chars()
+            .take(unit.width().saturating_sub(1))
+            .take_while(|&c| c == pad)
+            .count(),
+    };
+    let s = &s[padding * pad.len_utf8()..];
+    let width = unit.width().saturating_sub(padding);
+    // without a point, the fraction digits run on from the value's
+    let (inline, pointed) = match unit.point() {
+        Some(point) if unit.fraction_digits() > 0 => (0, Some(point)),
+        _ => (unit.fraction_digits(), None),
+    };
+
+    let digits: Vec<_> = s
+        .chars()
+        .map_while(|c| Some((c, unit.digit_value(c)?)))
+        .take(if fixed { width + inline } else { usize::MAX })
+        .collect();
+    if digits.len() <= inline || (fixed && digits.len() != width + inline) {
+        return Err(ParseError::ExpectedValue(unit.name().to_string()));
+    }
+
+    let length = digits.iter().map(|(c, _)| c.len_utf8()).sum();
+    let whole = &digits[..digits.len() - inline];
+    let value = whole.iter().try_fold(0u64, |value, &(_, digit)| {
+        value
+            .checked_mul(unit.radix() as u64)?
+            .checked_add(digit as u64)
+    });
+    let mut rest = &s[length..];
+    if let Some(point) = pointed {
+        rest = rest
+            .strip_prefix(point)
+            .ok_or_else(|| ParseError::ExpectedLiteral(point.to_string()))?;
+        let fraction: Vec<_> = rest
+            .chars()
+            .take(unit.fraction_digits())
+            .take_while(|&c| unit.digit_value(c).is_some())
+            .collect();
+        if fraction.len() != unit.fraction_digits() {
+            return Err(ParseError::ExpectedValue(unit.name().to_string()));
+        }
+        rest = &rest[fraction.iter().map(|c| c.len_utf8()).sum::<usize>()..];
+    }
+    Ok((value.ok_or(ParseError::Overflow)?, rest))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn parse_segments() {
+        let segments = [
+            Segment::Value((6, "lapse", 7776, 36).into()),
+            Segment::Literal(":"),
+            Segment::Value((6, "lull", 216, 36).into()),
+            Segment::Literal(":"),
+            Segment::Value((6, "moment", 6, 36).into()),
+            Segment::Literal("."),
+            Segment::Value((6, "snap", 1, 6, 0).into()),
+        ];
+
+        check!(parse(&segments, "31:44:45.4") == Ok(153970));
+        check!(parse(&segments, "1:2:3.4") == Ok(((36 + 2) * 36 + 3) * 6 + 4));
+        check!(parse(&segments, "31:44:45") == Err(ParseError::ExpectedLiteral(".".into())));
+        check!(parse(&segments, "31:44:45.") == Err(ParseError::ExpectedValue("snap".into())));
+        check!(parse(&segments, "31:64:45.4") == Err(ParseError::ExpectedValue("lull".into())));
+        check!(parse(&segments, "31:100:45.4") == Err(ParseError::OutOfRange("lull".into())));
+        check!(parse(&segments, "31:44:45.4 ") == Err(ParseError::TrailingInput(" ".into())));
+    }
+
+    #[test]
+    fn parse_adjacent_segments() {
+        let names = ["zero", "one", "once"];
+        let segments = [
+            Segment::Value((36, "lapse", 216, 36, 1).into()),
+            Segment::Value((6, "moment", 6, 36).into()),
+            Segment::Name(("snap", 1, 3).into(), &names),
+        ];
+
+        check!(parse(&segments, "z01one") == Ok(35 * 216 + 6 + 1));
+        check!(parse(&segments, "z01once") == Ok(35 * 216 + 6 + 2));
+        check!(parse(&segments, "z1once") == Err(ParseError::ExpectedValue("moment".into())));
+        check!(parse(&segments, "z01two") == Err(ParseError::ExpectedName("snap".into())));
+    }
+
+    #[test]
+    fn parse_padded_segments() {
+        let unit = |name, value, limit, pad| {
+            let mut unit = TimeUnit::new(name, value, limit, 3);
+            unit.set_pad(pad);
+            unit
+        };
+        let segments = [
+            Segment::Value(unit("hour", 1000, 24, '\u{2007}')),
+            Segment::Value(unit("minute", 1, 1000, '_')),
+        ];
+
+        check!(parse(&segments, "\u{2007}\u{2007}7_42") == Ok(7042));
+        check!(parse(&segments, "\u{2007}23123") == Ok(23123));
+        check!(parse(&segments, "\u{2007}7_42") == Err(ParseError::ExpectedValue("hour".into())));
+    }
+
+    #[test]
+    fn parse_conditional_segments() {
+        let lull = || TimeUnit::new("lull", 6, 36, 0);
+        let snap = || TimeUnit::new("snap", 1, 6, 0);
+        let segments = [
+            Segment::nonzero(lull(), [Segment::Value(lull()), Segment::Literal(" lulls")]),
+            Segment::nonzero(snap(), [Segment::Literal(" "), Segment::Value(snap())]),
+        ];
+
+        check!(parse(&segments, "3 lulls 4") == Ok(3 * 6 + 4));
+        check!(parse(&segments, " 4") == Ok(4));
+        check!(parse(&segments, "") == Ok(0));
+        check!(parse(&segments, "3 lull") == Err(ParseError::TrailingInput("3 lull".into())));
+    }
+}
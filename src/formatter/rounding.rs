@@ -0,0 +1,106 @@
+use alloc::string::{String, ToString};
+use core::{error::Error, fmt, str::FromStr};
+
+use num::{rational::Ratio, CheckedDiv, CheckedMul, Integer};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How to handle the part of a time too small for the smallest unit to show.
+/// Rounding up carries into the larger units.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Rounding {
+    /// Drop the remainder, so the time shown is never ahead of the real time.
+    #[default]
+    Floor,
+    /// Round any remainder up, so the time shown is never behind the real time.
+    Ceil,
+    /// Round to the nearest, and to an even value of the smallest unit when
+    /// exactly halfway.
+    HalfEven,
+}
+
+/// Error returned when parsing a [`Rounding`] that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRounding(pub String);
+
+impl fmt::Display for UnknownRounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown rounding `{}`; expected `floor`, `ceil`, or `half-even`",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownRounding {}
+
+impl FromStr for Rounding {
+    type Err = UnknownRounding;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "floor" => Ok(Self::Floor),
+            "ceil" => Ok(Self::Ceil),
+            "half-even" => Ok(Self::HalfEven),
+            _ => Err(UnknownRounding(s.to_string())),
+        }
+    }
+}
+
+impl Rounding {
+    /// Round `total` to a whole number of `step`, saturating rather than
+    /// overflowing. A `step` of zero leaves `total` as it is.
+    pub(super) fn round(self, total: Ratio<u64>, step: Ratio<u64>) -> Ratio<u64> {
+        if *step.numer() == 0 {
+            return total;
+        }
+        let steps = match total.checked_div(&step) {
+            Some(steps) => steps,
+            None => return total,
+        };
+        let (floor, fract) = (steps.floor(), steps.fract());
+        let up = match self {
+            Self::Floor => false,
+            Self::Ceil => fract > Ratio::from_integer(0),
+            Self::HalfEven => {
+                let half = Ratio::new(1, 2);
+                fract > half || (fract == half && floor.to_integer().is_odd())
+            }
+        };
+        let steps = match up {
+            true => floor + 1,
+            false => floor,
+        };
+        steps
+            .checked_mul(&step)
+            .unwrap_or_else(|| Ratio::from_integer(u64::MAX))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn round_to_step() {
+        let r = |n, d| Ratio::new(n, d);
+        let step = Ratio::from_integer;
+        check!(Rounding::Floor.round(r(59, 10), step(2)) == r(4, 1));
+        check!(Rounding::Ceil.round(r(59, 10), step(2)) == r(6, 1));
+        check!(Rounding::Ceil.round(r(6, 1), step(2)) == r(6, 1));
+        check!(Rounding::HalfEven.round(r(3, 1), step(2)) == r(4, 1));
+        check!(Rounding::HalfEven.round(r(5, 1), step(2)) == r(4, 1));
+        check!(Rounding::HalfEven.round(r(51, 10), step(2)) == r(6, 1));
+        check!(Rounding::Ceil.round(r(u64::MAX - 1, 1), step(1 << 63)) == r(u64::MAX, 1));
+        check!(Rounding::Ceil.round(r(59, 10), r(1, 6)) == r(6, 1));
+        check!(Rounding::Ceil.round(r(58, 10), r(1, 6)) == r(35, 6));
+        check!(Rounding::Floor.round(r(58, 10), step(0)) == r(58, 10));
+        check!("half-even".parse() == Ok(Rounding::HalfEven));
+        check!("up".parse::<Rounding>() == Err(UnknownRounding("up".into())));
+    }
+}
@@ -1,35 +1,231 @@
-use std::fmt;
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use core::fmt::{self, Write};
 
 use num::rational::Ratio;
 
-use super::TimeUnit;
+use super::{Style, Theme, TimeUnit};
+
+/// A function that renders a segment from the number of base units since the
+/// start of the day. Implemented for every such closure.
+pub trait RenderFn: Fn(Ratio<u64>) -> String {}
+
+impl<F: Fn(Ratio<u64>) -> String> RenderFn for F {}
 
 /// A segment to render.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub enum Segment<'s> {
     /// A literal string.
     Literal(&'s str),
     /// A dynamic segment formatted using a TimeUnit.
     Value(TimeUnit<'s>),
+    /// A dynamic segment that displays the name at the index given by the
+    /// value of a TimeUnit, rather than the value itself.
+    Name(TimeUnit<'s>, &'s [&'s str]),
+    /// The name of a TimeUnit, abbreviated if the flag is set. Unabbreviated
+    /// names are plural unless the value of the unit is one.
+    UnitName(TimeUnit<'s>, bool),
+    /// The value and name of each TimeUnit whose value isn't zero, in a list
+    /// such as `2 lapses, 3 lulls and 5 snaps`. If every value is zero, the
+    /// last unit is shown.
+    List(Vec<TimeUnit<'s>>),
+    /// A group of segments that is only displayed when the value of a TimeUnit
+    /// satisfies a predicate.
+    Conditional(TimeUnit<'s>, fn(u64) -> bool, Vec<Segment<'s>>),
+    /// A segment rendered by a function of the number of base units since the
+    /// start of the day. The function is shared, so that formatters can be
+    /// sent between threads.
+    Dynamic(Arc<dyn RenderFn + Send + Sync + 's>),
+    /// A group of segments displayed in a style, when styles are shown.
+    Styled(Style, Vec<Segment<'s>>),
 }
 
-impl Segment<'_> {
+impl fmt::Debug for Segment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(s) => f.debug_tuple("Literal").field(s).finish(),
+            Self::Value(u) => f.debug_tuple("Value").field(u).finish(),
+            Self::Name(u, names) => f.debug_tuple("Name").field(u).field(names).finish(),
+            Self::UnitName(u, abbreviated) => f
+                .debug_tuple("UnitName")
+                .field(u)
+                .field(abbreviated)
+                .finish(),
+            Self::List(units) => f.debug_tuple("List").field(units).finish(),
+            Self::Conditional(u, predicate, segments) => f
+                .debug_tuple("Conditional")
+                .field(u)
+                .field(predicate)
+                .field(segments)
+                .finish(),
+            Self::Dynamic(_) => f.debug_tuple("Dynamic").finish_non_exhaustive(),
+            Self::Styled(style, segments) => f
+                .debug_tuple("Styled")
+                .field(style)
+                .field(segments)
+                .finish(),
+        }
+    }
+}
+
+impl<'s> Segment<'s> {
+    /// Construct a group of segments that is only displayed when the value of
+    /// `unit` isn't zero, e.g. `unit` followed by a literal naming it.
+    pub fn nonzero<I>(unit: TimeUnit<'s>, segments: I) -> Self
+    where
+        I: IntoIterator<Item = Segment<'s>>,
+    {
+        Self::Conditional(unit, |value| value != 0, segments.into_iter().collect())
+    }
+
+    /// Construct a segment rendered by `render`, which is passed the number of
+    /// base units since the start of the day.
+    pub fn dynamic<F: RenderFn + Send + Sync + 's>(render: F) -> Self {
+        Self::Dynamic(Arc::new(render))
+    }
+
     /// Render this segment with the given number of ms since the start of the
     /// day.
     pub fn render(&self, total: Ratio<u64>) -> String {
         match self {
             Self::Literal(s) => s.to_string(),
-            Self::Value(u) => u.render(total / u.value as u64 % u.limit as u64),
+            Self::Value(u) => u.render(u.count(total)),
+            Self::Name(u, names) => Self::name(u, names, total).to_string(),
+            Self::UnitName(u, abbreviated) => Self::unit_name(u, *abbreviated, total).into(),
+            Self::List(units) => {
+                let mut out = String::new();
+                Self::list(&mut out, units, total).expect("writing to a string can't fail");
+                out
+            }
+            Self::Conditional(u, predicate, segments) if predicate(Self::count(u, total)) => {
+                segments.iter().map(|s| s.render(total)).collect()
+            }
+            Self::Conditional(..) => String::new(),
+            Self::Dynamic(render) => render(total),
+            Self::Styled(_, segments) => segments.iter().map(|s| s.render(total)).collect(),
         }
     }
 
     /// Render this segment with the given number of ms since the start of the
-    /// day.
+    /// day. If `f` is alternate (`{:#}`), the styles of units and styled groups
+    /// are written as ANSI escape sequences. Each style ends with a reset, so
+    /// styles don't nest.
     pub fn render_fmt(&self, f: &mut fmt::Formatter, total: Ratio<u64>) -> fmt::Result {
+        let style = match self {
+            Self::Value(u) | Self::Name(u, _) | Self::UnitName(u, _) => u.style(),
+            Self::Styled(style, _) => *style,
+            _ => Style::default(),
+        };
+        if f.alternate() {
+            style.start(f)?;
+        }
+        self.render_unstyled_fmt(f, total)?;
+        if f.alternate() {
+            style.end(f)?;
+        }
+        Ok(())
+    }
+
+    /// Render this segment to a formatter, without its own style.
+    fn render_unstyled_fmt(&self, f: &mut fmt::Formatter, total: Ratio<u64>) -> fmt::Result {
         match self {
             Self::Literal(s) => write!(f, "{}", s),
-            Self::Value(u) => u.render_fmt(f, total / u.value as u64 % u.limit as u64),
+            Self::Value(u) => u.render_fmt(f, u.count(total)),
+            Self::Name(u, names) => write!(f, "{}", Self::name(u, names, total)),
+            Self::UnitName(u, abbreviated) => {
+                write!(f, "{}", Self::unit_name(u, *abbreviated, total))
+            }
+            Self::List(units) => Self::list(f, units, total),
+            Self::Conditional(u, predicate, segments) if predicate(Self::count(u, total)) => {
+                segments.iter().try_for_each(|s| s.render_fmt(f, total))
+            }
+            Self::Conditional(..) => Ok(()),
+            Self::Dynamic(render) => f.write_str(&render(total)),
+            Self::Styled(_, segments) => segments.iter().try_for_each(|s| s.render_fmt(f, total)),
+        }
+    }
+
+    /// Whether this segment is only literal text, perhaps styled.
+    pub(super) fn is_literal(&self) -> bool {
+        match self {
+            Self::Literal(_) => true,
+            Self::Styled(_, segments) => segments.iter().all(Self::is_literal),
+            _ => false,
+        }
+    }
+
+    /// Style the units in this segment as `theme` gives, and put any literal
+    /// text in the theme's style for separators.
+    pub(super) fn apply_theme(&mut self, theme: &Theme) {
+        let style = |unit: &mut TimeUnit| {
+            if let Some(style) = theme.unit(unit.name()) {
+                unit.set_style(style);
+            }
+        };
+        match self {
+            Self::Literal(literal) if !theme.separators.is_plain() => {
+                *self = Self::Styled(theme.separators, vec![Self::Literal(literal)]);
+            }
+            Self::Literal(_) | Self::Dynamic(_) => {}
+            Self::Value(u) | Self::Name(u, _) | Self::UnitName(u, _) => style(u),
+            Self::List(units) => units.iter_mut().for_each(style),
+            Self::Conditional(_, _, segments) | Self::Styled(_, segments) => {
+                segments.iter_mut().for_each(|s| s.apply_theme(theme))
+            }
+        }
+    }
+
+    /// Look up the name for the value of the passed unit. Values without a
+    /// name are displayed as nothing.
+    fn name<'n>(unit: &TimeUnit, names: &[&'n str], total: Ratio<u64>) -> &'n str {
+        names
+            .get(Self::count(unit, total) as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The name of the passed unit for its value in `total`, or its
+    /// abbreviation.
+    fn unit_name<'n>(unit: &TimeUnit<'n>, abbreviated: bool, total: Ratio<u64>) -> Cow<'n, str> {
+        if abbreviated {
+            Cow::Borrowed(unit.abbreviation())
+        } else {
+            unit.name_for(Self::count(unit, total))
+        }
+    }
+
+    /// Write the value and name of each of `units` that isn't zero, separated
+    /// by commas and a final "and".
+    fn list<W: Write>(out: &mut W, units: &[TimeUnit], total: Ratio<u64>) -> fmt::Result {
+        let mut items: Vec<_> = units
+            .iter()
+            .map(|unit| (unit, Self::count(unit, total)))
+            .filter(|&(_, count)| count != 0)
+            .collect();
+        if items.is_empty() {
+            items.extend(units.last().map(|unit| (unit, 0)));
         }
+
+        for (i, (unit, count)) in items.iter().enumerate() {
+            if i + 1 == items.len() && i > 0 {
+                out.write_str(" and ")?;
+            } else if i > 0 {
+                out.write_str(", ")?;
+            }
+            let value = unit.display(Ratio::from_integer(*count)).unpadded().whole();
+            write!(out, "{} {}", value, unit.name_for(*count))?;
+        }
+        Ok(())
+    }
+
+    /// The whole number of the passed unit in `total`, less any larger units.
+    fn count(unit: &TimeUnit, total: Ratio<u64>) -> u64 {
+        unit.count(total).to_integer()
     }
 }
 
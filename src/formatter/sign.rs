@@ -0,0 +1,16 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Where the sign of a negative time is written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum SignPlacement {
+    /// Before everything else, e.g. `-@123`.
+    #[default]
+    Leading,
+    /// Before the first segment that isn't literal text, e.g. `@-123`.
+    BeforeUnits,
+    /// After everything else, e.g. `@123-`.
+    Trailing,
+}
@@ -0,0 +1,120 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// One of the eight standard terminal colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Color {
+    /// Black.
+    Black,
+    /// Red.
+    Red,
+    /// Green.
+    Green,
+    /// Yellow.
+    Yellow,
+    /// Blue.
+    Blue,
+    /// Magenta.
+    Magenta,
+    /// Cyan.
+    Cyan,
+    /// White.
+    White,
+}
+
+/// How to style text in a terminal. The default style is plain.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Style {
+    /// The foreground color, if not the terminal's default.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub color: Option<Color>,
+    /// Whether the text is bold.
+    pub bold: bool,
+    /// Whether the text is dimmed.
+    pub dim: bool,
+}
+
+impl Style {
+    /// A plain style.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// This style in `color`.
+    pub fn color(self, color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+
+    /// This style in bold.
+    pub fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// This style dimmed.
+    pub fn dim(self) -> Self {
+        Self { dim: true, ..self }
+    }
+
+    /// Whether this style leaves text as it is.
+    pub fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Write the ANSI escape sequence that starts this style.
+    pub(super) fn start<W: Write>(&self, out: &mut W) -> fmt::Result {
+        if self.is_plain() {
+            return Ok(());
+        }
+        let codes = [
+            self.bold.then_some(1),
+            self.dim.then_some(2),
+            self.color.map(|color| 30 + color as u8),
+        ];
+        out.write_str("\x1b[")?;
+        for (i, code) in codes.iter().flatten().enumerate() {
+            if i > 0 {
+                out.write_char(';')?;
+            }
+            write!(out, "{}", code)?;
+        }
+        out.write_char('m')
+    }
+
+    /// Write the ANSI escape sequence that ends this style.
+    pub(super) fn end<W: Write>(&self, out: &mut W) -> fmt::Result {
+        match self.is_plain() {
+            true => Ok(()),
+            false => out.write_str("\x1b[0m"),
+        }
+    }
+}
+
+/// Styles for the parts of a formatter, applied with
+/// [`TimeFormatter::apply_theme`](super::TimeFormatter::apply_theme).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Theme<'t> {
+    /// The style of each unit, by name.
+    pub units: Vec<(&'t str, Style)>,
+    /// The style of the literal text between units.
+    pub separators: Style,
+}
+
+impl<'t> Theme<'t> {
+    /// The style of the unit `name`, if the theme gives it one.
+    pub fn unit(&self, name: &str) -> Option<Style> {
+        self.units
+            .iter()
+            .find(|(unit, _)| *unit == name)
+            .map(|&(_, style)| style)
+    }
+}
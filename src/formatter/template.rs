@@ -0,0 +1,154 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{error::Error, fmt};
+
+use super::{Segment, TimeUnit};
+
+/// Error produced when a template cannot be compiled into segments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A placeholder names a unit that isn't available.
+    UnknownUnit(String),
+    /// A `{` was not closed by a matching `}`.
+    Unclosed,
+    /// A `}` appeared without a matching `{`.
+    Unmatched,
+    /// A `%` was followed by a character that isn't a format code.
+    UnknownCode(char),
+    /// The template ended with an unescaped `%`.
+    IncompleteCode,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownUnit(name) => write!(f, "unknown unit `{}` in template", name),
+            Self::Unclosed => write!(f, "unclosed `{{` in template"),
+            Self::Unmatched => write!(f, "unmatched `}}` in template"),
+            Self::UnknownCode(c) => write!(f, "unknown format code `%{}` in template", c),
+            Self::IncompleteCode => write!(f, "incomplete format code at end of template"),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+/// Compile a template into segments. A unit name in braces (e.g. `{lapse}`) is
+/// replaced by the value of the unit with that name in `units`. A `%` followed
+/// by one of the characters in `codes` is shorthand for the unit named by that
+/// code. Everything else is copied literally. Use `{{`, `}}`, and `%%` for
+/// literal braces and percent signs.
+pub fn compile<'a>(
+    template: &'a str,
+    units: &[TimeUnit<'a>],
+    codes: &[(char, &str)],
+) -> Result<Vec<Segment<'a>>, TemplateError> {
+    let unit = |name: &str| {
+        units
+            .iter()
+            .find(|u| u.name() == name)
+            .map(|u| Segment::Value(u.clone()))
+            .ok_or_else(|| TemplateError::UnknownUnit(name.to_string()))
+    };
+    let mut segments = Vec::new();
+    let push_literal = |segments: &mut Vec<Segment<'a>>, s: &'a str| {
+        if !s.is_empty() {
+            segments.push(Segment::Literal(s));
+        }
+    };
+
+    // the start of the literal text not yet pushed
+    let mut start = 0;
+    let mut chars = template.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' | '}' | '%' if matches!(chars.peek(), Some(&(_, next)) if next == c) => {
+                // keep one of the two characters as part of the literal
+                push_literal(&mut segments, &template[start..=i]);
+                chars.next();
+                start = i + 2;
+            }
+            '{' => {
+                push_literal(&mut segments, &template[start..i]);
+                let end = i + template[i..].find('}').ok_or(TemplateError::Unclosed)?;
+                segments.push(unit(&template[i + 1..end])?);
+                while chars.next_if(|&(j, _)| j <= end).is_some() {}
+                start = end + 1;
+            }
+            '}' => return Err(TemplateError::Unmatched),
+            '%' => {
+                push_literal(&mut segments, &template[start..i]);
+                let (j, code) = chars.next().ok_or(TemplateError::IncompleteCode)?;
+                let (_, name) = codes
+                    .iter()
+                    .find(|(c, _)| *c == code)
+                    .ok_or(TemplateError::UnknownCode(code))?;
+                segments.push(unit(name)?);
+                start = j + code.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    push_literal(&mut segments, &template[start..]);
+
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    fn units() -> Vec<TimeUnit<'static>> {
+        vec![(10, "hour", 60, 24).into(), (10, "minute", 1, 60).into()]
+    }
+
+    #[test]
+    fn compile_placeholders() {
+        let segments = compile("{hour}h {minute}m", &units(), &[]).unwrap();
+        check!(segments.len() == 4);
+        check!(matches!(&segments[0], Segment::Value(u) if u.name() == "hour"));
+        check!(matches!(segments[1], Segment::Literal("h ")));
+        check!(matches!(&segments[2], Segment::Value(u) if u.name() == "minute"));
+        check!(matches!(segments[3], Segment::Literal("m")));
+    }
+
+    #[test]
+    fn compile_escaped_braces() {
+        let segments = compile("{{{hour}}}", &units(), &[]).unwrap();
+        check!(segments.len() == 3);
+        check!(matches!(segments[0], Segment::Literal("{")));
+        check!(matches!(segments[2], Segment::Literal("}")));
+    }
+
+    #[test]
+    fn compile_codes() {
+        let codes = [('H', "hour"), ('M', "minute"), ('S', "second")];
+        let segments = compile("%H:%M 100%%", &units(), &codes).unwrap();
+        check!(segments.len() == 4);
+        check!(matches!(&segments[0], Segment::Value(u) if u.name() == "hour"));
+        check!(matches!(segments[1], Segment::Literal(":")));
+        check!(matches!(&segments[2], Segment::Value(u) if u.name() == "minute"));
+        check!(matches!(segments[3], Segment::Literal(" 100%")));
+
+        check!(compile("%X", &units(), &codes).unwrap_err() == TemplateError::UnknownCode('X'));
+        check!(compile("%H%", &units(), &codes).unwrap_err() == TemplateError::IncompleteCode);
+        check!(
+            compile("%S", &units(), &codes).unwrap_err()
+                == TemplateError::UnknownUnit("second".into())
+        );
+    }
+
+    #[test]
+    fn compile_errors() {
+        check!(
+            compile("{second}", &units(), &[]).unwrap_err()
+                == TemplateError::UnknownUnit("second".into())
+        );
+        check!(compile("{hour", &units(), &[]).unwrap_err() == TemplateError::Unclosed);
+        check!(compile("hour}", &units(), &[]).unwrap_err() == TemplateError::Unmatched);
+    }
+}
@@ -1,9 +1,21 @@
-use std::fmt;
+use alloc::{borrow::Cow, format, string::String};
+use core::{
+    convert::TryFrom,
+    fmt::{self, Write},
+};
 
 use num::rational::Ratio;
 
+use super::Style;
+
 /// Default padding width.
-const DEFAULT_WIDTH: usize = 2;
+pub(super) const DEFAULT_WIDTH: usize = 2;
+/// Default padding character.
+pub(super) const DEFAULT_PAD: char = '0';
+/// The digits used by units that don't have their own, in order of value.
+const DEFAULT_DIGITS: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+/// The usual digits, with uppercase letters.
+const UPPERCASE_DIGITS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
 /// A time unit to display. It only makes sense when taken in conjunction with
 /// a reference unit, such as the attribute `prototype` on [`TimeFormatter`].
@@ -12,68 +24,366 @@ pub struct TimeUnit<'u> {
     /// The preferred radix of this unit's unit system.
     radix: u8,
     /// The name of this time unit.
-    _name: &'u str,
+    name: &'u str,
+    /// A short form of the name of this time unit, if it has one.
+    pub(super) abbreviation: Option<&'u str>,
+    /// The plural of the name of this time unit, if it isn't regular.
+    pub(super) plural: Option<&'u str>,
     /// The value of this time unit as a multiple of the reference unit.
     pub(super) value: u32,
     /// The maximum number of these time units permitted.
     pub(super) limit: u32,
+    /// Whether this unit has no limit, and counts every one of it in the time.
+    unbounded: bool,
     /// How wide to pad this unit.
     width: usize,
+    /// The character to pad this unit with.
+    pad: char,
+    /// The glyph for each digit, in order of value, if not the usual ones.
+    pub(super) digits: Option<&'u str>,
+    /// Whether the usual digits above 9 are uppercase letters.
+    uppercase: bool,
+    /// How many digits of the part of this unit below one to show after it.
+    fraction_digits: usize,
+    /// The radix point written before the fraction digits, if any.
+    pub(super) point: Option<&'u str>,
+    /// How to style this unit when styles are shown.
+    style: Style,
 }
 
 impl<'u> TimeUnit<'u> {
+    /// Construct a new decimal time unit. `value` is the unit's size in
+    /// reference units, `limit` is how many of it fit in the next larger unit,
+    /// and `width` is how many digits to zero-pad it to.
     pub fn new(name: &'u str, value: u32, limit: u32, width: usize) -> Self {
         Self::with_radix(10, name, value, limit, width)
     }
 
+    /// Construct a new time unit displayed in the passed radix.
     pub fn with_radix(radix: u8, name: &'u str, value: u32, limit: u32, width: usize) -> Self {
         Self {
             radix,
-            _name: name,
+            name,
+            abbreviation: None,
+            plural: None,
             value,
             limit,
+            unbounded: false,
             width,
+            pad: DEFAULT_PAD,
+            digits: None,
+            uppercase: false,
+            fraction_digits: 0,
+            point: None,
+            style: Style::default(),
+        }
+    }
+
+    /// Construct a new decimal time unit with no limit, such as a count of
+    /// days. It should be the largest unit of its formatter.
+    pub fn unbounded(name: &'u str, value: u32, width: usize) -> Self {
+        let mut unit = Self::new(name, value, u32::MAX, width);
+        unit.unbounded = true;
+        unit
+    }
+
+    /// The name of this time unit.
+    pub fn name(&self) -> &'u str {
+        self.name
+    }
+
+    /// The short form of the name of this time unit, or the whole name if it
+    /// has no short form.
+    pub fn abbreviation(&self) -> &'u str {
+        self.abbreviation.unwrap_or(self.name)
+    }
+
+    /// Give this time unit a short form of its name.
+    pub fn with_abbreviation(mut self, abbreviation: &'u str) -> Self {
+        self.abbreviation = Some(abbreviation);
+        self
+    }
+
+    /// The name of this time unit in the plural. Unless it has been given, the
+    /// plural is the name followed by "s", or by "es" if the name ends in "s".
+    pub fn plural(&self) -> Cow<'u, str> {
+        match self.plural {
+            Some(plural) => Cow::Borrowed(plural),
+            None if self.name.ends_with('s') => Cow::Owned(format!("{}es", self.name)),
+            None => Cow::Owned(format!("{}s", self.name)),
         }
     }
 
+    /// Give this time unit an irregular plural.
+    pub fn with_plural(mut self, plural: &'u str) -> Self {
+        self.plural = Some(plural);
+        self
+    }
+
+    /// The name of this time unit for `count` of it: singular for one and
+    /// plural otherwise.
+    pub fn name_for(&self, count: u64) -> Cow<'u, str> {
+        if count == 1 {
+            Cow::Borrowed(self.name)
+        } else {
+            self.plural()
+        }
+    }
+
+    /// Whether this unit has no limit.
+    pub fn is_unbounded(&self) -> bool {
+        self.unbounded
+    }
+
+    /// Set whether this unit has no limit. An unbounded unit counts every one
+    /// of it in the time instead of wrapping around at its limit.
+    pub fn set_unbounded(&mut self, unbounded: bool) {
+        self.unbounded = unbounded;
+    }
+
+    /// The number of this unit in `total` base units, less any larger units
+    /// unless this unit is unbounded.
+    pub(super) fn count(&self, total: Ratio<u64>) -> Ratio<u64> {
+        match self.unbounded {
+            true => total / self.value as u64,
+            false => total / self.value as u64 % self.limit as u64,
+        }
+    }
+
+    /// Whether `count` of this unit can be shown without reaching its limit.
+    pub(super) fn fits(&self, count: u64) -> bool {
+        self.unbounded || count < self.limit as u64
+    }
+
+    /// The radix that this unit is displayed in.
+    pub fn radix(&self) -> u8 {
+        self.radix
+    }
+
+    /// How wide this unit is padded.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Set how wide to pad this unit. A width of zero disables padding.
+    pub fn set_width(&mut self, width: usize) {
+        self.width = width;
+    }
+
+    /// The character that this unit is padded with.
+    pub fn pad(&self) -> char {
+        self.pad
+    }
+
+    /// Set the character to pad this unit with, such as `' '` or `'\u{2007}'`
+    /// (figure space). The width counts characters, however many bytes each
+    /// takes.
+    pub fn set_pad(&mut self, pad: char) {
+        self.pad = pad;
+    }
+
+    /// The glyph for each digit of this unit, in order of value: `0-9` then
+    /// `a-z` (or `A-Z` if uppercase), unless it has been given its own.
+    pub fn digits(&self) -> &'u str {
+        match self.uppercase {
+            true => self.digits.unwrap_or(UPPERCASE_DIGITS),
+            false => self.digits.unwrap_or(DEFAULT_DIGITS),
+        }
+    }
+
+    /// Give this time unit its own glyph for each digit, in order of value,
+    /// such as `"0123456789XE"` for dozenal. There should be at least as many
+    /// as the radix.
+    pub fn with_digits(mut self, digits: &'u str) -> Self {
+        self.digits = Some(digits);
+        self
+    }
+
+    /// Whether this unit's digits above 9 are uppercase letters.
+    pub fn uppercase(&self) -> bool {
+        self.uppercase
+    }
+
+    /// Set whether to write this unit's digits above 9 as uppercase letters.
+    /// Units with their own digits are unaffected. Either case is parsed.
+    pub fn set_uppercase(&mut self, uppercase: bool) {
+        self.uppercase = uppercase;
+    }
+
+    /// How many digits of the part of this unit below one are written straight
+    /// after its value.
+    pub fn fraction_digits(&self) -> usize {
+        self.fraction_digits
+    }
+
+    /// Set how many digits of the part of this unit below one, such as the
+    /// time within a snap, to write straight after its value in its radix.
+    /// Gives the smallest unit of a formatter more precision than it has.
+    pub fn set_fraction_digits(&mut self, digits: usize) {
+        self.fraction_digits = digits;
+    }
+
+    /// The radix point written between this unit's value and its fraction
+    /// digits, if any.
+    pub fn point(&self) -> Option<&'u str> {
+        self.point
+    }
+
+    /// Write `point` between this unit's value and its fraction digits, so
+    /// that the remainder below the unit reads as a radix-point expansion of
+    /// it, such as `50.40` lulls.
+    pub fn set_point(&mut self, point: &'u str) {
+        self.point = Some(point);
+    }
+
+    /// The smallest part of this unit that is shown, in base units, or zero if
+    /// the fraction digits are too fine to represent.
+    pub(super) fn step(&self) -> Ratio<u64> {
+        let places = u32::try_from(self.fraction_digits).ok();
+        match places.and_then(|places| (self.radix.max(2) as u64).checked_pow(places)) {
+            Some(denom) => Ratio::new(self.value as u64, denom),
+            None => Ratio::from_integer(0),
+        }
+    }
+
+    /// The value of the digit `c` in this unit, if it is one.
+    pub fn digit_value(&self, c: char) -> Option<u32> {
+        let radix = self.radix as usize;
+        match self.digits {
+            Some(digits) => digits.chars().take(radix).position(|d| d == c),
+            None => c.to_digit(radix as u32).map(|d| d as usize),
+        }
+        .map(|d| d as u32)
+    }
+
+    /// How this unit is styled when styles are shown.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Set how to style this unit when styles are shown.
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
     /// Render the passed value to a string using this unit.
     pub fn render(&self, value: Ratio<u64>) -> String {
-        // TODO: make padding width and character configurable
-        format!(
-            "{:0width$}",
-            ValueDisplay(self.radix, value),
-            width = self.width
-        )
+        let mut out = String::with_capacity(self.width);
+        write!(out, "{}", self.display(value)).expect("writing to a string can't fail");
+        out
     }
 
     /// Render the passed value to a formatter using this unit.
     pub fn render_fmt(&self, f: &mut fmt::Formatter, value: Ratio<u64>) -> fmt::Result {
-        // TODO: make padding width and character configurable
-        write!(
-            f,
-            "{:0width$}",
-            ValueDisplay(self.radix, value),
-            width = self.width
-        )
+        write!(f, "{}", self.display(value))
+    }
+
+    /// Display the passed value using this unit.
+    pub(super) fn display(&self, value: Ratio<u64>) -> ValueDisplay<'u> {
+        ValueDisplay {
+            radix: self.radix,
+            digits: self.digits(),
+            value: value.to_integer(),
+            width: self.width,
+            pad: self.pad,
+            fraction: value.fract(),
+            fraction_digits: self.fraction_digits,
+            point: self.point.unwrap_or_default(),
+        }
     }
 }
 
-/// A hack for padding the radix-converted number correctly.
-/// ```rust
-/// let radix = 8;
-/// let value = 39;
-/// assert_eq!(format!("{0:3}", ValueDisplay(radix, value)), "047");
+/// A value converted to a radix and padded to a width, followed by as many
+/// digits of its fraction as asked for. Units display their values with it:
+///
 /// ```
-struct ValueDisplay(u8, Ratio<u64>);
+/// use rn::formatter::{Segment, TimeFormatter, TimeUnit};
+///
+/// let mut unit = TimeUnit::with_radix(8, "unit", 1, 64, 3);
+/// unit.set_pad('_');
+/// unit.set_point(".");
+/// unit.set_fraction_digits(2);
+/// // 39¼ units, which is 47.2 in octal
+/// let formatter = TimeFormatter::new((1, 4), [Segment::Value(unit)]);
+/// assert_eq!(formatter.render(157), "_47.20");
+/// ```
+pub(super) struct ValueDisplay<'d> {
+    /// The radix to display the value in.
+    radix: u8,
+    /// The glyph for each digit, in order of value.
+    digits: &'d str,
+    /// The value to display.
+    value: u64,
+    /// How many characters to pad the value to.
+    width: usize,
+    /// The character to pad the value with.
+    pad: char,
+    /// The part of the value below one.
+    fraction: Ratio<u64>,
+    /// How many digits of the fraction to display.
+    fraction_digits: usize,
+    /// The radix point written before the digits of the fraction.
+    point: &'d str,
+}
+
+impl ValueDisplay<'_> {
+    /// Display the value without padding.
+    pub(super) fn unpadded(self) -> Self {
+        Self { width: 0, ..self }
+    }
 
-impl fmt::Display for ValueDisplay {
+    /// Display the value without the digits of its fraction.
+    pub(super) fn whole(self) -> Self {
+        Self {
+            fraction_digits: 0,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ValueDisplay<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let r = radix_fmt::radix(
-            (*self.1.numer() as u64).saturating_div(*self.1.denom() as u64),
-            self.0,
-        )
-        .to_string();
-        f.pad_integral(true, "", &r)
+        // the value of each place, least significant first; a u64 has at most
+        // 64 places, in binary
+        let mut places = [0u8; 64];
+        let mut count = 0;
+        let mut value = self.value;
+        // a radix below two can't be displayed; validation rejects it
+        let radix = self.radix.max(2) as u64;
+        loop {
+            places[count] = (value % radix) as u8;
+            count += 1;
+            value /= radix;
+            if value == 0 {
+                break;
+            }
+        }
+
+        let glyph = |place: u8| {
+            let digit = self.digits.chars().nth(place as usize);
+            // fall back to the usual digit if there are too few glyphs
+            let digit = digit.or_else(|| DEFAULT_DIGITS.chars().nth(place as usize));
+            digit.unwrap_or('?')
+        };
+
+        for _ in count..self.width {
+            f.write_char(self.pad)?;
+        }
+        for &place in places[..count].iter().rev() {
+            f.write_char(glyph(place))?;
+        }
+        if self.fraction_digits > 0 {
+            f.write_str(self.point)?;
+        }
+        // the numerator stays below the denominator, so it can't overflow
+        let mut numer = *self.fraction.numer() as u128;
+        let denom = *self.fraction.denom() as u128;
+        for _ in 0..self.fraction_digits {
+            numer *= radix as u128;
+            f.write_char(glyph((numer / denom) as u8))?;
+            numer %= denom;
+        }
+        Ok(())
     }
 }
 
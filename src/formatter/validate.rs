@@ -0,0 +1,261 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{error::Error, fmt};
+
+use num::{rational::Ratio, Zero};
+
+use super::{Segment, TimeUnit};
+
+/// Error produced when a formatter's specification doesn't make sense.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatterError {
+    /// The base is zero.
+    ZeroBase,
+    /// There are no units to display.
+    NoUnits,
+    /// A unit's radix is less than 2, or more than it has digits for.
+    InvalidRadix(String, u8),
+    /// A unit has the same glyph for more than one digit.
+    DuplicateDigit(String, char),
+    /// A unit's value is zero.
+    ZeroValue(String),
+    /// A unit's limit is zero.
+    ZeroLimit(String),
+    /// A unit rolls over before reaching the next larger unit, so some times
+    /// can't be told apart.
+    Gap(String, String),
+    /// A unit rolls over after passing the next larger unit, so the same time
+    /// is counted twice.
+    Overlap(String, String),
+}
+
+impl fmt::Display for FormatterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroBase => write!(f, "the base must not be zero"),
+            Self::NoUnits => write!(f, "at least one segment must be a unit"),
+            Self::InvalidRadix(unit, radix) => write!(
+                f,
+                "the {} has radix {}, but the radix must be at least 2 and no more \
+                 than the number of digits",
+                unit, radix
+            ),
+            Self::DuplicateDigit(unit, digit) => {
+                write!(f, "the {} uses `{}` for more than one digit", unit, digit)
+            }
+            Self::ZeroValue(unit) => write!(f, "the {} must have a nonzero value", unit),
+            Self::ZeroLimit(unit) => write!(f, "the {} must have a nonzero limit", unit),
+            Self::Gap(small, large) => {
+                write!(f, "the {} rolls over before reaching a {}", small, large)
+            }
+            Self::Overlap(small, large) => {
+                write!(f, "the {} rolls over after passing a {}", small, large)
+            }
+        }
+    }
+}
+
+impl Error for FormatterError {}
+
+/// Check that `segments` describe a usable formatter for a day of `day` ms:
+/// every unit has a valid radix and distinct digits, a nonzero value and
+/// limit, and each unit's limit makes it fill exactly one of a larger unit or
+/// the whole day. Only the largest unit may be unbounded. A unit may be
+/// displayed more than once, and a time may be displayed in more than one set
+/// of units, so long as each set tiles the day.
+pub fn validate(
+    base: Ratio<u64>,
+    day: Ratio<u64>,
+    segments: &[Segment],
+) -> Result<(), FormatterError> {
+    if base.is_zero() {
+        return Err(FormatterError::ZeroBase);
+    }
+
+    let mut units: Vec<&TimeUnit> = Vec::new();
+    collect_units(segments, &mut units)?;
+    if units.is_empty() {
+        return Err(FormatterError::NoUnits);
+    }
+
+    // the length of the day in base units, wide enough not to overflow
+    let day = Ratio::new(
+        *base.numer() as u128 * *day.numer() as u128,
+        *base.denom() as u128 * *day.denom() as u128,
+    );
+    units.sort_by_key(|unit| core::cmp::Reverse(unit.value));
+    for (i, small) in units.iter().enumerate() {
+        let larger = units[..i].iter().rev().find(|u| u.value > small.value);
+        if small.is_unbounded() {
+            // only the largest unit can count past the next larger unit
+            if let Some(large) = larger {
+                return Err(FormatterError::Overlap(
+                    small.name().to_string(),
+                    large.name().to_string(),
+                ));
+            }
+            continue;
+        }
+        // each unit should fill either a larger unit or the whole day
+        let span = small.value as u64 * small.limit as u64;
+        if Ratio::from_integer(span as u128) == day
+            || units
+                .iter()
+                .any(|u| u.value > small.value && u.value as u64 == span)
+        {
+            continue;
+        }
+        let (large, limit) = match larger {
+            Some(large) => (large.name(), Ratio::from_integer(large.value as u128)),
+            None => ("day", day),
+        };
+        return Err(if Ratio::from_integer(span as u128) < limit {
+            FormatterError::Gap(small.name().to_string(), large.to_string())
+        } else {
+            FormatterError::Overlap(small.name().to_string(), large.to_string())
+        });
+    }
+    Ok(())
+}
+
+/// Check each unit in `segments`, including those in conditional and styled
+/// groups, and
+/// add those not already seen to `units`.
+fn collect_units<'s, 'f>(
+    segments: &'s [Segment<'f>],
+    units: &mut Vec<&'s TimeUnit<'f>>,
+) -> Result<(), FormatterError> {
+    for segment in segments {
+        match segment {
+            Segment::Literal(_) | Segment::UnitName(..) | Segment::Dynamic(_) => {}
+            Segment::Value(unit) | Segment::Name(unit, _) => collect_unit(unit, units)?,
+            Segment::List(list) => {
+                for unit in list {
+                    collect_unit(unit, units)?;
+                }
+            }
+            Segment::Styled(_, group) => collect_units(group, units)?,
+            Segment::Conditional(unit, _, group) => {
+                collect_units(group, units)?;
+                collect_unit(unit, units)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check `unit`, and add it to `units` if it hasn't already been seen.
+fn collect_unit<'s, 'f>(
+    unit: &'s TimeUnit<'f>,
+    units: &mut Vec<&'s TimeUnit<'f>>,
+) -> Result<(), FormatterError> {
+    let digits: Vec<_> = unit.digits().chars().collect();
+    if unit.radix() < 2 || unit.radix() as usize > digits.len() {
+        return Err(FormatterError::InvalidRadix(
+            unit.name().to_string(),
+            unit.radix(),
+        ));
+    }
+    let digits = &digits[..unit.radix() as usize];
+    if let Some(&digit) = digits
+        .iter()
+        .enumerate()
+        .find_map(|(i, digit)| digits[..i].contains(digit).then_some(digit))
+    {
+        return Err(FormatterError::DuplicateDigit(
+            unit.name().to_string(),
+            digit,
+        ));
+    }
+    if unit.value == 0 {
+        return Err(FormatterError::ZeroValue(unit.name().to_string()));
+    }
+    if unit.limit == 0 && !unit.is_unbounded() {
+        return Err(FormatterError::ZeroLimit(unit.name().to_string()));
+    }
+    if !units
+        .iter()
+        .any(|u| (u.value, u.limit) == (unit.value, unit.limit))
+    {
+        units.push(unit);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn validate_segments() {
+        let base = Ratio::from_integer(1);
+        let day = Ratio::from_integer(86_400_000);
+        let hour = || Segment::Value(("hour", 3_600_000, 24).into());
+        let minute = |limit| Segment::Value(("minute", 60_000, limit).into());
+
+        check!(validate(base, day, &[hour(), Segment::Literal(":"), minute(60)]) == Ok(()));
+        check!(validate(base, day, &[hour(), minute(60), hour()]) == Ok(()));
+        let days = Segment::Value(TimeUnit::unbounded("day", 86_400_000, 0));
+        check!(validate(base, day, &[days, hour()]) == Ok(()));
+        let minutes = Segment::Value(TimeUnit::unbounded("minute", 60_000, 0));
+        check!(
+            validate(base, day, &[hour(), minutes])
+                == Err(FormatterError::Overlap("minute".into(), "hour".into()))
+        );
+        check!(validate(Ratio::from_integer(0), day, &[hour()]) == Err(FormatterError::ZeroBase));
+        check!(validate(base, day, &[Segment::Literal(":")]) == Err(FormatterError::NoUnits));
+        check!(
+            validate(base, day, &[hour(), minute(30)])
+                == Err(FormatterError::Gap("minute".into(), "hour".into()))
+        );
+        check!(
+            validate(base, day, &[hour(), minute(90)])
+                == Err(FormatterError::Overlap("minute".into(), "hour".into()))
+        );
+        let hours = |limit| Segment::Value(("hour", 3_600_000, limit).into());
+        check!(
+            validate(base, day, &[hours(12), minute(60)])
+                == Err(FormatterError::Gap("hour".into(), "day".into()))
+        );
+        check!(
+            validate(base, day, &[hours(25), minute(60)])
+                == Err(FormatterError::Overlap("hour".into(), "day".into()))
+        );
+        // a second set of units is fine, so long as it also tiles the day
+        let watch = Segment::Value(("watch", 14_400_000, 6).into());
+        let bell = Segment::Value(("bell", 1_800_000, 8).into());
+        check!(validate(base, day, &[hour(), minute(60), watch, bell]) == Ok(()));
+        let sol = |day| validate(base, day, &[Segment::Value(("hour", 3_700_000, 24).into())]);
+        check!(sol(Ratio::from_integer(88_800_000)) == Ok(()));
+        check!(sol(day) == Err(FormatterError::Overlap("hour".into(), "day".into())));
+        check!(
+            validate(base, day, &[Segment::Value((1, "x", 1, 1).into())])
+                == Err(FormatterError::InvalidRadix("x".into(), 1))
+        );
+        check!(
+            validate(base, day, &[Segment::Value((40, "x", 1, 1).into())])
+                == Err(FormatterError::InvalidRadix("x".into(), 40))
+        );
+        check!(
+            validate(
+                base,
+                day,
+                &[Segment::Value(
+                    TimeUnit::with_radix(40, "x", 1, 1, 0).with_digits(&"⠁".repeat(40))
+                )]
+            ) == Err(FormatterError::DuplicateDigit("x".into(), '⠁'))
+        );
+        check!(
+            validate(base, day, &[Segment::Value(("x", 0, 1).into())])
+                == Err(FormatterError::ZeroValue("x".into()))
+        );
+        check!(
+            validate(base, day, &[Segment::Value(("x", 1, 0).into())])
+                == Err(FormatterError::ZeroLimit("x".into()))
+        );
+    }
+}
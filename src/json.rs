@@ -0,0 +1,127 @@
+//! Structured output for other programs.
+
+use num::rational::Ratio;
+use serde::Serialize;
+
+use rn::{
+    formatter::TimeFormatter,
+    systems::{civil_time_formatter, mk_snap_time_formatter, mk_span_time_formatter, System},
+};
+
+use crate::{render, time_of_day, SelectedZone};
+
+/// A reading of the time, with each of its parts, so that other programs
+/// don't have to parse the rendered text.
+#[derive(Debug, Serialize)]
+pub struct Reading<'a> {
+    /// The name of the system the time is shown in.
+    pub system: &'a str,
+    /// The time as rendered in the system.
+    pub text: &'a str,
+    /// The value of each unit of the system, in display order.
+    pub units: Vec<UnitValue<'a>>,
+    /// The seximal time of day in basic form.
+    pub basic: &'a str,
+    /// The seximal time of day in span form.
+    pub span: &'a str,
+    /// The civil time of day the reading was taken at.
+    pub civil: &'a str,
+}
+
+/// The value of a unit in a [`Reading`].
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct UnitValue<'a> {
+    /// The name of the unit.
+    pub name: &'a str,
+    /// The number of the unit, less any larger units.
+    pub value: u64,
+    /// The number as rendered by the unit, in its radix.
+    pub text: String,
+}
+
+impl Reading<'_> {
+    /// Serialize this as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("serializing a reading can't fail")
+    }
+}
+
+/// Describe the time `millis` milliseconds since the epoch of `system`, read
+/// at the instant `unix_millis` milliseconds after the Unix epoch, as a line of
+/// JSON. The seximal and civil times of day are in the time zone `tz`.
+pub fn reading(
+    formatter: &TimeFormatter,
+    system: &System,
+    millis: i64,
+    unix_millis: i64,
+    tz: SelectedZone,
+) -> String {
+    let nanos = millis.unsigned_abs() as u128 * 1_000_000;
+    let units = formatter
+        .unit_values(nanos)
+        .into_iter()
+        .map(|(unit, value)| UnitValue {
+            name: unit.name(),
+            value,
+            text: unit.render(Ratio::from_integer(value)),
+        })
+        .collect();
+    let day = time_of_day(unix_millis, tz);
+    Reading {
+        system: system.name,
+        text: &render(formatter, millis, false),
+        units,
+        basic: &mk_snap_time_formatter().render(day),
+        span: &mk_span_time_formatter().render(day),
+        civil: &civil_time_formatter().render(day),
+    }
+    .to_json()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    use rn::systems::Registry;
+
+    #[test]
+    fn reading_json() {
+        let reading = Reading {
+            system: "span",
+            text: "314",
+            units: vec![UnitValue {
+                name: "span",
+                value: 118,
+                text: "314".to_string(),
+            }],
+            basic: "3144454",
+            span: "314",
+            civil: "13:12:01.888",
+        };
+        check!(
+            reading.to_json()
+                == r#"{"system":"span","text":"314","units":[{"name":"span","value":118,"text":"314"}],"#
+                    .to_string()
+                    + r#""basic":"3144454","span":"314","civil":"13:12:01.888"}"#
+        );
+    }
+
+    #[test]
+    fn readings() {
+        let registry = Registry::builtin();
+        let extended = registry.get("extended").unwrap();
+        // 2023-11-14T22:13:20Z
+        let json = reading(
+            &extended.formatter,
+            extended,
+            80_000_000,
+            1_700_000_000_000,
+            SelectedZone::Utc,
+        );
+        check!(json.starts_with(r#"{"system":"extended","text":"53:20:00.0","units":["#));
+        check!(json.contains(r#"{"name":"lapse","value":33,"text":"53"}"#));
+        check!(json.ends_with(r#""basic":"5320000","span":"532","civil":"22:13:20.000"}"#));
+    }
+}
@@ -0,0 +1,87 @@
+//! The leap seconds inserted into UTC, and the resulting offset of UTC from
+//! International Atomic Time (TAI).
+
+use crate::mk;
+
+/// The number of seconds by which GPS time is behind TAI.
+pub const TAI_MINUS_GPS: i64 = 19;
+
+/// The midnights (in seconds since the Unix epoch) from which TAI − UTC took
+/// each value (in seconds). Every change after the first follows a leap second
+/// at the end of the previous UTC day.
+pub const LEAP_SECONDS: [(i64, i64); 28] = [
+    (63_072_000, 10),    // 1972-01-01
+    (78_796_800, 11),    // 1972-07-01
+    (94_694_400, 12),    // 1973-01-01
+    (126_230_400, 13),   // 1974-01-01
+    (157_766_400, 14),   // 1975-01-01
+    (189_302_400, 15),   // 1976-01-01
+    (220_924_800, 16),   // 1977-01-01
+    (252_460_800, 17),   // 1978-01-01
+    (283_996_800, 18),   // 1979-01-01
+    (315_532_800, 19),   // 1980-01-01
+    (362_793_600, 20),   // 1981-07-01
+    (394_329_600, 21),   // 1982-07-01
+    (425_865_600, 22),   // 1983-07-01
+    (489_024_000, 23),   // 1985-07-01
+    (567_993_600, 24),   // 1988-01-01
+    (631_152_000, 25),   // 1990-01-01
+    (662_688_000, 26),   // 1991-01-01
+    (709_948_800, 27),   // 1992-07-01
+    (741_484_800, 28),   // 1993-07-01
+    (773_020_800, 29),   // 1994-07-01
+    (820_454_400, 30),   // 1996-01-01
+    (867_715_200, 31),   // 1997-07-01
+    (915_148_800, 32),   // 1999-01-01
+    (1_136_073_600, 33), // 2006-01-01
+    (1_230_768_000, 34), // 2009-01-01
+    (1_341_100_800, 35), // 2012-07-01
+    (1_435_708_800, 36), // 2015-07-01
+    (1_483_228_800, 37), // 2017-01-01
+];
+
+/// Get TAI − UTC, in seconds, at the instant `unix_millis` milliseconds after
+/// the Unix epoch, or `None` before leap seconds began in 1972.
+pub fn tai_minus_utc(unix_millis: i64) -> Option<i64> {
+    let seconds = unix_millis.div_euclid(1000);
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|&&(start, _)| start <= seconds)
+        .map(|&(_, offset)| offset)
+}
+
+/// Check whether the UTC day containing the instant `unix_millis` milliseconds
+/// after the Unix epoch ends with a leap second.
+pub fn ends_with_leap_second(unix_millis: i64) -> bool {
+    let day = mk::MILLIS_PER_DAY as i64;
+    let next_midnight = (unix_millis.div_euclid(day) + 1) * day / 1000;
+    LEAP_SECONDS[1..]
+        .iter()
+        .any(|&(start, _)| start == next_midnight)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn offsets_from_tai() {
+        check!(tai_minus_utc(0).is_none());
+        check!(tai_minus_utc(63_072_000_000) == Some(10));
+        check!(tai_minus_utc(1_483_228_799_999) == Some(36));
+        check!(tai_minus_utc(1_483_228_800_000) == Some(37));
+        check!(tai_minus_utc(1_700_000_000_000) == Some(37));
+    }
+
+    #[test]
+    fn days_ending_with_leap_seconds() {
+        check!(ends_with_leap_second(1_483_142_400_000));
+        check!(ends_with_leap_second(1_483_228_799_999));
+        check!(!ends_with_leap_second(1_483_228_800_000));
+        check!(!ends_with_leap_second(63_071_999_999));
+        check!(!ends_with_leap_second(1_700_000_000_000));
+    }
+}
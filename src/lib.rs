@@ -0,0 +1,44 @@
+//! Display times using the Misalian Seximal Units with Kunimunean Extensions,
+//! or any other system of units.
+//!
+//! A [`TimeFormatter`](formatter::TimeFormatter) renders a number of
+//! milliseconds as a string of [`Segment`](formatter::Segment)s: literal text
+//! and the values of [`TimeUnit`](formatter::TimeUnit)s. The [`systems`]
+//! module provides formatters for the built-in unit systems, and a
+//! [`Registry`](systems::Registry) to look them up by name.
+//!
+//! ```
+//! use rn::systems::misalian_kunimunean_time_formatter;
+//!
+//! let formatter = misalian_kunimunean_time_formatter();
+//! assert_eq!(formatter.render(47_521_888), "31:44:45.4");
+//! ```
+//!
+//! Without the default `std` feature, the crate is `no_std` and needs only
+//! `alloc`, so that the formatters can be embedded in clocks running on
+//! microcontrollers. Specification files, `SystemTime` rendering and sidereal
+//! time need `std`. The optional `serde` feature serializes and deserializes
+//! formatters, through owned variants of them; specification files also need
+//! it.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![warn(missing_docs)]
+
+extern crate alloc;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod formatter;
+pub mod leap;
+pub mod mars;
+pub mod mk;
+#[cfg(feature = "python")]
+mod python;
+pub mod sidereal;
+#[cfg(all(feature = "std", feature = "serde"))]
+pub mod spec;
+#[cfg(feature = "chrono")]
+pub mod stardate;
+pub mod systems;
+#[cfg(feature = "wasm")]
+pub mod wasm;
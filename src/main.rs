@@ -1,68 +1,464 @@
-#![feature(fn_traits)]
-#![feature(trait_alias)]
+use std::{
+    convert::TryFrom,
+    env,
+    error::Error,
+    ffi::{OsStr, OsString},
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
 
-use std::{error::Error, time::Duration};
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Timelike,
+    Utc,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use num::rational::Ratio;
 
-use chrono::{DateTime, Local, NaiveTime, ParseResult, Utc};
-use clap::Parser;
+use rn::{
+    formatter::{ParseError, Rounding, Segment, TimeFormatter},
+    leap, mars,
+    mk::{self, MkTime, ParseMkError, ParseMkErrorKind},
+    sidereal,
+    spec::{self, FormatterSpec},
+    stardate,
+    systems::{
+        self, civil_time_formatter, misalian_kunimunean_time_formatter,
+        mk_delimited_time_formatter, mk_snap_time_formatter, mk_units, Epoch, Registry, System,
+        Zone,
+    },
+};
 
-mod formatter;
+mod add;
+mod alarm;
+mod at;
+mod bar;
+mod batch;
+mod big;
+mod clock;
+mod config;
+mod csv;
+mod daemon;
+#[cfg(feature = "dbus")]
+mod dbus;
+mod diff;
+mod face;
+mod json;
+mod parse_error;
+mod percent;
+mod progress;
+#[cfg(feature = "png")]
+mod raster;
+mod relative;
+mod shell;
+mod sleep;
+mod stat;
+mod stopwatch;
+mod systemd;
+mod timer;
+mod until;
+mod wait;
+mod watch;
+mod words;
 
-use crate::formatter::{Segment, TimeFormatter};
+use crate::{big::Font, config::Config, parse_error::ParseTimeError};
 
-/// Return a time formatter for Misalian–Kunimunean Seximal Units.
-pub fn misalian_kunimunean_time_formatter() -> TimeFormatter<'static> {
-    TimeFormatter::new(
-        (36 * 36 * 36 * 6, 86_400_000),
-        [
-            Segment::Value((6, "lapse", 7776, 36).into()),
-            Segment::Literal(":"),
-            Segment::Value((6, "lull", 216, 36).into()),
-            Segment::Literal(":"),
-            Segment::Value((6, "moment", 6, 36).into()),
-            Segment::Literal("."),
-            Segment::Value((6, "snap", 1, 6, 0).into()),
-        ],
-    )
+/// Get the first instant of `date` in the time zone `tz`. When a daylight
+/// saving transition skips midnight, the day starts when the clocks go
+/// forward; when midnight happens twice, the day starts at the first.
+fn start_of_day<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> Option<DateTime<Tz>> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    // transitions happen on the quarter hour
+    (0..24 * 4).find_map(|quarter| {
+        let time = midnight + chrono::Duration::minutes(15 * quarter);
+        tz.from_local_datetime(&time).earliest()
+    })
 }
 
-/// Return a time formatter for Misalian–Kunimunean spans.
-pub fn mk_span_time_formatter() -> TimeFormatter<'static> {
-    TimeFormatter::new(
-        (36 * 36 * 36 * 6, 86_400_000),
-        [Segment::Value((6, "span", 1296, 1296, 3).into())],
-    )
+/// Get the duration that has elapsed between the start of the day and `now`,
+/// in the time zone of `now`.
+fn time_since_start_of_day<Tz: TimeZone>(now: DateTime<Tz>) -> Result<Duration, Box<dyn Error>> {
+    let start = start_of_day(&now.timezone(), now.date_naive())
+        .ok_or("the current day has no start in the time zone")?;
+    now.signed_duration_since(start)
+        .to_std()
+        .map_err(|_| "the current time is before the start of the day".into())
 }
 
-/// Return a time formatter for Misalian–Kunimunean snaps.
-pub fn mk_snap_time_formatter() -> TimeFormatter<'static> {
-    TimeFormatter::new(
-        (36 * 36 * 36 * 6, 86_400_000),
-        [Segment::Value((6, "snap", 1, 36 * 36 * 36 * 6, 7).into())],
-    )
+/// The time zone selected by the user, that times of day are read and shown
+/// in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum SelectedZone {
+    /// Coordinated Universal Time.
+    #[default]
+    Utc,
+    /// The system time zone, with `--local`.
+    System,
+    /// A zone from the IANA time zone database, with `--zone`.
+    Named(chrono_tz::Tz),
+    /// A fixed offset from UTC, with `--offset`.
+    Fixed(FixedOffset),
 }
 
-/// Get the duration that has elapsed since midnight today.
-fn time_since_local_midnight() -> Duration {
-    let now: DateTime<Local> = Local::now();
-    let midnight: DateTime<Local> = Local::today().and_hms(0, 0, 0);
+impl SelectedZone {
+    /// Get the offset from UTC, in milliseconds, at the instant `unix_millis`
+    /// milliseconds after the Unix epoch.
+    fn offset_millis(self, unix_millis: i64) -> i64 {
+        let utc = DateTime::from_timestamp_millis(unix_millis).unwrap_or_default();
+        let seconds = match self {
+            Self::Utc => 0,
+            Self::System => utc.with_timezone(&Local).offset().fix().local_minus_utc(),
+            Self::Named(tz) => utc.with_timezone(&tz).offset().fix().local_minus_utc(),
+            Self::Fixed(offset) => offset.local_minus_utc(),
+        };
+        seconds as i64 * 1000
+    }
+
+    /// Get the civil date and time at the instant `unix_millis` milliseconds
+    /// after the Unix epoch.
+    fn civil(self, unix_millis: i64) -> NaiveDateTime {
+        let utc = DateTime::from_timestamp_millis(unix_millis).unwrap_or_default();
+        utc.naive_utc() + chrono::Duration::milliseconds(self.offset_millis(unix_millis))
+    }
+}
 
-    now.signed_duration_since(midnight).to_std().unwrap()
+/// Get the duration that has elapsed since midnight today in the time zone
+/// `tz`.
+fn time_since_midnight(tz: SelectedZone) -> Result<Duration, Box<dyn Error>> {
+    match tz {
+        SelectedZone::Utc => time_since_start_of_day(Utc::now()),
+        SelectedZone::System => time_since_start_of_day(Local::now()),
+        SelectedZone::Named(tz) => time_since_start_of_day(Utc::now().with_timezone(&tz)),
+        SelectedZone::Fixed(offset) => time_since_start_of_day(Utc::now().with_timezone(&offset)),
+    }
 }
 
-/// Get the duration that has elapsed since midnight today.
-fn time_since_utc_midnight() -> Duration {
-    let now: DateTime<Utc> = Utc::now();
-    let midnight: DateTime<Utc> = Utc::today().and_hms(0, 0, 0);
+/// Get the offset from UTC, in milliseconds, of the time zone that the time is
+/// displayed in, at the instant `unix_millis` milliseconds after the Unix
+/// epoch.
+fn utc_offset_millis(zone: Zone, tz: SelectedZone, unix_millis: i64) -> i64 {
+    match zone {
+        Zone::Fixed(offset) => offset as i64 * 1000,
+        Zone::Shifted(offset) => tz.offset_millis(unix_millis) + offset as i64 * 1000,
+        Zone::Selected => tz.offset_millis(unix_millis),
+    }
+}
+
+/// Shift a number of milliseconds since midnight by `offset` milliseconds,
+/// wrapping around at the end of the day.
+fn shift_millis(millis: u32, offset: i64) -> u32 {
+    (millis as i64 + offset).rem_euclid(mk::MILLIS_PER_DAY as i64) as u32
+}
+
+/// Where the time is observed from: the options that affect which time is
+/// displayed, rather than how it is displayed.
+#[derive(Debug, Default, Clone, Copy)]
+struct Observer {
+    /// The time zone to use.
+    tz: SelectedZone,
+    /// Degrees east of Greenwich, for local sidereal time.
+    longitude: f64,
+    /// How local days that aren't 24 hours long are shown.
+    dst: Dst,
+    /// How leap seconds are shown.
+    leap: LeapSeconds,
+    /// The time scale that the time is read in.
+    scale: Scale,
+}
 
-    now.signed_duration_since(midnight).to_std().unwrap()
+/// A time scale, defining how long each day is.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Scale {
+    /// Coordinated Universal Time, kept within a second of the rotation of
+    /// the Earth by leap seconds.
+    #[default]
+    Utc,
+    /// International Atomic Time, without leap seconds.
+    Tai,
+    /// GPS time, a constant 19 seconds behind TAI.
+    Gps,
 }
 
-/// Parse a user-provided time. Attempts various formats before giving up and
-/// erroring out.
-fn attempt_parse_time_since_midnight(when: &str) -> ParseResult<NaiveTime> {
+/// Convert the instant `unix_millis` milliseconds after the Unix epoch to the
+/// number of milliseconds since 1970-01-01 00:00:00 in the time scale `scale`.
+/// The atomic time scales are only available from 1972, when leap seconds
+/// began.
+fn scale_millis(unix_millis: i64, scale: Scale) -> Result<i64, Box<dyn Error>> {
+    let tai = || {
+        leap::tai_minus_utc(unix_millis)
+            .map(|offset| unix_millis + offset * 1000)
+            .ok_or("atomic time scales are only available from 1972")
+    };
+    match scale {
+        Scale::Utc => Ok(unix_millis),
+        Scale::Tai => Ok(tai()?),
+        Scale::Gps => Ok(tai()? - leap::TAI_MINUS_GPS * 1000),
+    }
+}
+
+/// How to treat the leap seconds at the end of some UTC days.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LeapSeconds {
+    /// Hold the time at the end of the second before, as POSIX clocks do.
+    #[default]
+    Ignore,
+    /// Slow the clock down over the day before, to make room for the leap
+    /// second.
+    Smear,
+    /// Reject times within a leap second.
+    Strict,
+}
+
+/// Get the number of milliseconds since UTC midnight at the instant
+/// `unix_millis` milliseconds after the Unix epoch. When smearing leap
+/// seconds, the days ending with one are scaled to fit a standard day.
+fn utc_time_of_day(unix_millis: i64, leap: LeapSeconds) -> u32 {
+    let millis = unix_millis.rem_euclid(mk::MILLIS_PER_DAY as i64) as u32;
+    match leap == LeapSeconds::Smear && leap::ends_with_leap_second(unix_millis) {
+        true => stretch_millis(millis as i64, mk::MILLIS_PER_DAY as i64 + 1000),
+        false => millis,
+    }
+}
+
+/// Treat a time of day given as `millis` milliseconds since midnight, which is
+/// within a leap second if it is a whole day or more, according to `leap`.
+/// When smearing, the time is read as on a day ending with a leap second, the
+/// only days that have one, and the whole day is scaled to fit a standard day
+/// so that later times never come out earlier.
+fn leap_time_of_day(millis: u32, leap: LeapSeconds) -> Result<u32, Box<dyn Error>> {
+    match leap {
+        LeapSeconds::Smear => Ok(stretch_millis(
+            millis as i64,
+            mk::MILLIS_PER_DAY as i64 + 1000,
+        )),
+        _ if millis < mk::MILLIS_PER_DAY => Ok(millis),
+        LeapSeconds::Ignore => Ok(mk::MILLIS_PER_DAY - 1),
+        LeapSeconds::Strict => Err("the time is within a leap second".into()),
+    }
+}
+
+/// How to show the time of day in the system time zone when daylight saving
+/// time makes the day shorter or longer than 24 hours.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Dst {
+    /// Follow the wall clock, jumping when the clocks change.
+    #[default]
+    Wall,
+    /// Scale the day to its real length, so that the time is continuous.
+    Stretch,
+}
+
+/// Get the number of milliseconds since the start of the day in `tz` at the
+/// instant `unix_millis` milliseconds after the Unix epoch, scaled so that
+/// the day lasts [`mk::MILLIS_PER_DAY`] however long it really is.
+fn stretched_time_of_day<Tz: TimeZone>(tz: &Tz, unix_millis: i64) -> Option<u32> {
+    let now = tz.timestamp_millis_opt(unix_millis).single()?;
+    let start = start_of_day(tz, now.date_naive())?;
+    let end = start_of_day(tz, now.date_naive().succ_opt()?)?;
+    Some(stretch_millis(
+        (now - start.clone()).num_milliseconds(),
+        (end - start).num_milliseconds(),
+    ))
+}
+
+/// Scale `elapsed` milliseconds of a day `length` milliseconds long to the
+/// same fraction of a 24-hour day.
+fn stretch_millis(elapsed: i64, length: i64) -> u32 {
+    (elapsed as i128 * mk::MILLIS_PER_DAY as i128 / length as i128) as u32
+}
+
+/// Get the number of milliseconds between the passed system's epoch and the
+/// instant `unix_millis` milliseconds after the Unix epoch. Negative for
+/// instants before the epoch.
+fn instant_since_epoch(
+    system: &System,
+    unix_millis: i64,
+    observer: &Observer,
+) -> Result<i64, Box<dyn Error>> {
+    match system.epoch {
+        Epoch::Midnight if observer.scale != Scale::Utc => Ok(shift_millis(
+            scale_millis(unix_millis, observer.scale)?.rem_euclid(mk::MILLIS_PER_DAY as i64) as u32,
+            utc_offset_millis(system.zone, observer.tz, unix_millis),
+        ) as i64),
+        Epoch::Midnight
+            if observer.dst == Dst::Stretch
+                && observer.tz != SelectedZone::Utc
+                && system.zone == Zone::Selected =>
+        {
+            let stretched = match observer.tz {
+                SelectedZone::Named(tz) => stretched_time_of_day(&tz, unix_millis),
+                SelectedZone::Fixed(offset) => stretched_time_of_day(&offset, unix_millis),
+                _ => stretched_time_of_day(&Local, unix_millis),
+            };
+            stretched
+                .map(i64::from)
+                .ok_or_else(|| "the current day has no start in the time zone".into())
+        }
+        Epoch::Midnight => Ok(shift_millis(
+            utc_time_of_day(unix_millis, observer.leap),
+            utc_offset_millis(system.zone, observer.tz, unix_millis),
+        ) as i64),
+        Epoch::Unix => scale_millis(unix_millis, observer.scale),
+        Epoch::MarsMidnight => Ok(mars::millis_since_midnight(unix_millis) as i64),
+        Epoch::SiderealMidnight => {
+            Ok(sidereal::millis_since_midnight(unix_millis, observer.longitude) as i64)
+        }
+        Epoch::JulianDate => {
+            Ok(scale_millis(unix_millis, observer.scale)? + systems::JULIAN_DATE_OF_UNIX_EPOCH)
+        }
+        Epoch::Stardate => stardate::hundredths(unix_millis)
+            .ok_or_else(|| "the time is too far from the present for a stardate".into()),
+    }
+}
+
+/// Render a number of milliseconds since the epoch, which may be negative. If
+/// `color` is set, the styles of the formatter are shown.
+fn render(formatter: &TimeFormatter, millis: i64, color: bool) -> String {
+    render_nanos(formatter, millis as i128 * 1_000_000, color)
+}
+
+/// Render a number of nanoseconds since the epoch, which may be negative. If
+/// `color` is set, the styles of the formatter are shown.
+fn render_nanos(formatter: &TimeFormatter, nanos: i128, color: bool) -> String {
+    let display = formatter.display_signed_nanos(nanos);
+    match color {
+        true => format!("{:#}", display),
+        false => display.to_string(),
+    }
+}
+
+/// Render the civil time of day at the instant `unix_millis` milliseconds
+/// after the Unix epoch, to the second, in the time zone `tz`.
+fn civil_time(unix_millis: i64, tz: SelectedZone) -> Result<String, Box<dyn Error>> {
+    let millis = unix_millis.rem_euclid(mk::MILLIS_PER_DAY as i64) as u32;
+    let millis = shift_millis(millis, tz.offset_millis(unix_millis));
+    let formatter = civil_time_formatter().with_template("{hour}:{minute}:{second}")?;
+    Ok(formatter.render(millis as u64))
+}
+
+/// Get the number of milliseconds since midnight at the instant `unix_millis`
+/// milliseconds after the Unix epoch, in the time zone `tz`.
+fn time_of_day(unix_millis: i64, tz: SelectedZone) -> u64 {
+    let millis = unix_millis.rem_euclid(mk::MILLIS_PER_DAY as i64) as u32;
+    shift_millis(millis, tz.offset_millis(unix_millis)) as u64
+}
+
+/// Get how long to wait after the time `millis` milliseconds since the epoch
+/// before the display may need updating: until the end of the current
+/// `interval` of nanoseconds, if given, or else until the rendered time next
+/// changes. Times before the epoch count down rather than up, so they're polled
+/// instead.
+fn until_next_update(formatter: &TimeFormatter, millis: i64, interval: Option<u128>) -> Duration {
+    let nanos = match u128::try_from(millis) {
+        Ok(millis) => millis * 1_000_000,
+        Err(_) => return watch::POLL_INTERVAL,
+    };
+    let wait = match interval {
+        Some(interval) => Some(interval - nanos % interval),
+        None => formatter.until_next_change(nanos),
+    };
+    wait.map_or(watch::POLL_INTERVAL, |nanos| {
+        Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    })
+}
+
+/// Get the number of milliseconds that have elapsed since the passed system's
+/// epoch.
+fn millis_since_epoch(system: &System, observer: &Observer) -> Result<i64, Box<dyn Error>> {
+    instant_since_epoch(system, Utc::now().timestamp_millis(), observer)
+}
+
+/// Parse a user-provided time into the number of milliseconds since the
+/// passed system's epoch. Times of day are read in the selected time zone, and
+/// dates and times with an offset (`2001-07-08T00:34:60+09:30`) or seconds
+/// since the Unix epoch (`@1700000000`) are converted into it; systems
+/// counting from other epochs need one of those. Times relative to now are
+/// parsed by [`relative::parse_millis`].
+fn parse_millis_since_epoch(
+    when: &str,
+    system: &System,
+    observer: &Observer,
+) -> Result<i64, Box<dyn Error>> {
+    if let Some(seconds) = when.trim().strip_prefix('@') {
+        return instant_since_epoch(system, parse_unix_seconds(seconds)?, observer);
+    }
+    if let Some(offset) = relative::parse_millis(when) {
+        let unix_millis = Utc::now().timestamp_millis() + offset;
+        return instant_since_epoch(system, unix_millis, observer);
+    }
+    match system.epoch {
+        Epoch::Midnight => {
+            if let Ok(time) = DateTime::parse_from_str(when.trim(), "%+") {
+                return instant_since_epoch(system, unix_millis_of(time, observer.leap)?, observer);
+            }
+            let now = Utc::now().timestamp_millis();
+            let offset = utc_offset_millis(system.zone, observer.tz, now)
+                - utc_offset_millis(Zone::Selected, observer.tz, now);
+            let millis = leap_time_of_day(parse_millis_since_midnight(when)?, observer.leap)?;
+            Ok(shift_millis(millis, offset) as i64)
+        }
+        _ => {
+            let time = DateTime::parse_from_rfc3339(when.trim())?;
+            instant_since_epoch(system, unix_millis_of(time, observer.leap)?, observer)
+        }
+    }
+}
+
+/// Parse a number of seconds since the Unix epoch, with up to millisecond
+/// precision, into milliseconds. Further digits of the fraction are dropped.
+fn parse_unix_seconds(seconds: &str) -> Result<i64, Box<dyn Error>> {
+    let invalid = || {
+        format!(
+            "invalid number of seconds since the Unix epoch: {}",
+            seconds
+        )
+    };
+    let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    let (sign, digits) = match whole.strip_prefix('-') {
+        Some(digits) => (-1, digits),
+        None => (1, whole),
+    };
+    let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if digits.is_empty() || !all_digits(digits) || !all_digits(fraction) {
+        return Err(invalid().into());
+    }
+    let millis: String = fraction.chars().chain("000".chars()).take(3).collect();
+    digits
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| seconds.checked_mul(1000))
+        .and_then(|millis_part| millis_part.checked_add(millis.parse().ok()?))
+        .map(|millis| sign * millis)
+        .ok_or_else(|| invalid().into())
+}
+
+/// Get the number of milliseconds since the Unix epoch at `time`. A time
+/// within a leap second is treated according to `leap`, as POSIX time has no
+/// leap seconds.
+fn unix_millis_of(time: DateTime<FixedOffset>, leap: LeapSeconds) -> Result<i64, Box<dyn Error>> {
+    match time.nanosecond() >= 1_000_000_000 {
+        true if leap == LeapSeconds::Strict => Err("the time is within a leap second".into()),
+        true => Ok(time.timestamp() * 1000 + 999),
+        false => Ok(time.timestamp_millis()),
+    }
+}
+
+/// Parse a seximal time of day in extended, basic, or span form, or in
+/// extended form without the snap (`43:00:00`).
+fn parse_seximal_target(target: &str) -> Result<MkTime, ParseMkError> {
+    mk::parse(target).or_else(|err| {
+        mk::parse_moment(target.trim())
+            .and_then(MkTime::from_snaps)
+            .ok_or(err)
+    })
+}
+
+/// Parse a user-provided time. Attempts various formats, then times written in
+/// words (`quarter past three`), before giving up.
+fn attempt_parse_time_since_midnight(when: &str) -> Option<NaiveTime> {
     // Formats to try before giving up.
-    const FORMATS: [&str; 12] = [
+    const FORMATS: [&str; 13] = [
         "%T",          // 00:34:60
         "%R",          // 00:35
         "%r",          // 12:34:60 AM
@@ -70,34 +466,70 @@ fn attempt_parse_time_since_midnight(when: &str) -> ParseResult<NaiveTime> {
         "%Hh %Mm %Ss", // 12h 34m 60s
         "%Hh %Mm",     // 12h 35m
         "%Hh",         // 12h
-        "%I%M %p",     // 1235am
+        "%I%M %p",     // 1235 am
+        "%I%M%p",      // 1235am
         "%I %p",       // 12am
         "%H%M",        // 1235
         "%+",          // 2001-07-08T00:34:60.026490+09:30
         "%c",          // Sun Jul 8 00:34:60 2001
     ];
 
-    let mut t = None;
-    for fmt in FORMATS {
-        match NaiveTime::parse_from_str(when, fmt) {
-            Ok(t) => return Ok(t),
-            Err(err) => t = Some(err),
-        }
+    FORMATS
+        .iter()
+        .find_map(|fmt| NaiveTime::parse_from_str(when, fmt).ok())
+        .or_else(|| words::parse(when))
+}
+
+/// Parse a user-provided time into the number of milliseconds since midnight,
+/// or `None` if it isn't in any accepted form.
+fn try_parse_millis_since_midnight(when: &str) -> Option<u32> {
+    if let Ok(time) = when.parse::<MkTime>() {
+        return Some(time.millis());
+    }
+
+    attempt_parse_time_since_midnight(when).map(|time| {
+        time.signed_duration_since(NaiveTime::MIN)
+            .num_milliseconds() as u32
+    })
+}
+
+/// Parse a user-provided time into the number of milliseconds since midnight.
+/// Seximal times in extended or basic form are recognized in addition to the
+/// standard formats accepted by [`attempt_parse_time_since_midnight`]. Times in
+/// none of these forms are reported with a nearly matching input, if any.
+fn parse_millis_since_midnight(when: &str) -> Result<u32, Box<dyn Error>> {
+    match when.parse::<MkTime>() {
+        Ok(time) => return Ok(time.millis()),
+        // seven digits can only be basic form, so say why they aren't valid
+        Err(err) if err.kind() == ParseMkErrorKind::BasicDigit => return Err(err.into()),
+        Err(_) => {}
     }
 
-    // because the length of the loop above is guaranteed to be greater than
-    // zero, this is perfectly safe.
-    Err(t.unwrap())
+    try_parse_millis_since_midnight(when).ok_or_else(|| {
+        ParseTimeError::new(when, |s| try_parse_millis_since_midnight(s).is_some()).into()
+    })
 }
 
 #[derive(Debug, Parser)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
     /// What time to display. Defaults to the current time.
     ///
-    /// Several input formats are supported, including ISO-8601 extended date/time
-    /// format and `ctime` format. In these formats, the date is ignored. AM and
-    /// PM may be upper- or lowercased. Examples of supported times include `00:34:60`, `12:34:60 AM`, `4pm`, `6h 45m`, and `8h24m36s`.
+    /// Several input formats are supported, including ISO-8601 extended
+    /// date/time format and `ctime` format. In `ctime` format, the date is
+    /// ignored. AM and PM may be upper- or lowercased. Examples of supported
+    /// times include `00:34:60`, `12:34:60 AM`, `4pm`, `6h 45m`, and
+    /// `8h24m36s`. Times may also be written in words, as in `noon`,
+    /// `midnight`, `quarter past three`, or `half past 17`. Seximal times in
+    /// extended (`31:44:45.4`) or basic (`3144454`) form are also accepted.
+    /// A date and time with an offset, or a number of seconds since the Unix
+    /// epoch (`@1700000000`), is converted into the selected time zone.
+    /// Systems that don't count from midnight on Earth, such as `unix`,
+    /// `julian`, `mars`, `sidereal`, and `stardate`, need a full RFC 3339 date
+    /// and time (`2023-11-14T22:13:20Z`) or Unix time. Times relative to now,
+    /// such as `+30m`, `-2h15m`, or `now+3s`, work with any system.
     when: Option<String>,
     /// Display the current snap.
     ///
@@ -106,45 +538,1128 @@ struct Args {
     /// the same as the default extended form, but without delimiters; e.g.,
     /// extended form `20:34:05.0` is equivalent to basic form `2034050`. Zero
     /// padded to fill seven digits. Ranges from `0000000` to `5555555`.
+    /// Equivalent to `--system basic`.
     #[clap(short, long)]
     basic: bool,
     /// Use system time zone instead of UTC.
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     local: bool,
+    /// Use UTC even if the configuration file enables `local`.
+    #[clap(short, long, global = true, conflicts_with = "local")]
+    utc: bool,
+    /// Use the named time zone from the IANA time zone database, such as
+    /// `Asia/Tokyo`, instead of UTC.
+    #[clap(long, global = true, value_name = "NAME", conflicts_with_all = &["local", "utc"])]
+    zone: Option<chrono_tz::Tz>,
+    /// Use a fixed offset from UTC, such as `+09:30` or `-05:00`, instead of
+    /// UTC.
+    #[clap(
+        long,
+        global = true,
+        value_name = "OFFSET",
+        allow_hyphen_values = true,
+        conflicts_with_all = &["local", "utc", "zone"]
+    )]
+    offset: Option<FixedOffset>,
+    /// How to show the time of day on days when daylight saving time starts
+    /// or ends, with `--local`.
+    ///
+    /// `wall` follows the wall clock, skipping or repeating an hour of the
+    /// seximal day. `stretch` squeezes or stretches the seximal day to the
+    /// real length of the local day, so that it never jumps.
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        value_name = "MODE",
+        default_value = "wall"
+    )]
+    dst: Dst,
+    /// How to treat leap seconds when finding the time of day.
+    ///
+    /// `ignore` holds the time at the end of 23:59:59 during a leap second.
+    /// `smear` spreads the leap second over the whole UTC day before it, so
+    /// that the seximal day still ends at midnight. `strict` refuses to show a
+    /// time within a leap second.
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        value_name = "MODE",
+        default_value = "ignore"
+    )]
+    leap_seconds: LeapSeconds,
+    /// Read the time in this time scale.
+    ///
+    /// `tai` (International Atomic Time) and `gps` have no leap seconds, so
+    /// they run ahead of UTC by the leap seconds inserted since 1972, less 19
+    /// seconds for `gps`. `--dst` and `--leap-seconds` only apply to `utc`.
+    #[clap(long, value_enum, value_name = "SCALE", default_value = "utc")]
+    scale: Scale,
+    /// Read default options from this file.
+    ///
+    /// Defaults to `$XDG_CONFIG_HOME/rn/config.toml` (usually
+    /// `~/.config/rn/config.toml`), which is ignored if it doesn't exist.
+    #[clap(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// Draw large digits with the FIGlet font (`.flf`) at this path.
+    ///
+    /// Used by `--big`, `--png`, and `rn clock`. Glyphs are set at their full
+    /// width, without smushing. Defaults to the bundled block digits.
+    #[clap(long, global = true, value_name = "PATH")]
+    font: Option<PathBuf>,
+    /// Display the time in extended form, even if the configuration file
+    /// selects a different system. Equivalent to `--system extended`.
+    #[clap(short, long)]
+    extended: bool,
+    /// Display the time in niftimal shorthand.
+    ///
+    /// Each lapse, lull, and moment is written as a single base-36 digit,
+    /// followed by the snap; e.g., extended form `31:44:45.4` is `jst4`.
+    /// Equivalent to `--system niftimal`; use `--system niftimal-short` to
+    /// leave off the snap.
+    #[clap(short, long)]
+    niftimal: bool,
+    /// Display Coordinated Mars Time.
+    ///
+    /// Shows the mean solar time at the Martian prime meridian, in standard
+    /// units and in seximal units of the sol. Equivalent to `--system mars`.
+    #[clap(long)]
+    mars: bool,
+    /// Show sidereal time as seen from this many degrees east of Greenwich.
+    ///
+    /// Only used by the `sidereal` system. Defaults to 0 (Greenwich mean
+    /// sidereal time); western longitudes are negative.
+    #[clap(long, value_name = "DEGREES", allow_hyphen_values = true)]
+    longitude: Option<f64>,
+    /// Display the time using the named unit system.
+    ///
+    /// Use `--list-systems` to see the available systems. Systems defined in
+    /// TOML files in `~/.config/rn/systems/` are available under the name of
+    /// the file, without the extension. Also available as `--to`, e.g.
+    /// `rn 2034050 --to standard`.
+    #[clap(long, alias = "to", value_name = "NAME")]
+    system: Option<String>,
+    /// List the available unit systems and exit.
+    #[clap(long)]
+    list_systems: bool,
+    /// Display the time using a unit system defined in a TOML file.
+    ///
+    /// The file gives the `base` ratio of base units to milliseconds and a list
+    /// of `segments`, each either a `literal` or a `unit` with a `value` (in
+    /// base units), a `limit`, and optionally a `radix`, `width`, `pad`
+    /// character, `digits` glyphs, and whether the digits are `uppercase`. With
+    /// `--format`, placeholders name the units defined in the file.
+    #[clap(long, value_name = "PATH")]
+    system_file: Option<PathBuf>,
     /// Alias of `--basic`.
     #[clap(long)]
     snap: bool,
+    /// Display the time using a custom template.
+    ///
+    /// Unit names in braces are replaced by the value of that unit; all other
+    /// text is printed as-is. In the Misalian–Kunimunean systems, the
+    /// available units are `lapse`, `lull`, `moment`, `snap`, and `span`. For
+    /// example, `{lapse}l {lull}:{moment}`.
+    ///
+    /// The format codes `%L` (lapse), `%U` (lull), `%M` (moment), `%S` (snap),
+    /// and `%P` (span) can be used as shorthand, e.g. `%L:%U`. Use `{{`, `}}`,
+    /// and `%%` for literal braces and percent signs.
+    #[clap(short, long)]
+    format: Option<String>,
+    /// Display the time in words.
+    ///
+    /// Each unit of the selected system that isn't zero is written out with
+    /// its name, e.g. `2 lapses, 3 lulls, 4 moments and 5 snaps`.
+    #[clap(long, conflicts_with = "format")]
+    long: bool,
+    /// Pad values with this character instead of `0`.
+    ///
+    /// For example, `' '`, `_`, or a figure space (U+2007), which is as wide
+    /// as a digit in most fonts.
+    #[clap(long, value_name = "CHAR")]
+    pad: Option<char>,
+    /// Write digits above 9 as uppercase letters.
+    ///
+    /// For example, niftimal form `jst4` becomes `JST4`. Units with their own
+    /// digits are unaffected.
+    #[clap(long)]
+    uppercase: bool,
+    /// How to round the time to the smallest unit shown.
+    ///
+    /// `floor` (the default) never shows a time ahead of the real time, `ceil`
+    /// never shows one behind it, and `half-even` shows the nearest.
+    #[clap(long, value_name = "MODE")]
+    round: Option<Rounding>,
+    /// Show this many senary digits after the point.
+    ///
+    /// The snap is the first digit after the point in extended form, so
+    /// `--precision 2` adds a digit for the time within the snap, e.g.
+    /// `20:34:05.03`. Other systems show as many more digits of their
+    /// smallest unit. Defaults to 1; at most 12.
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u8).range(1..=12))]
+    precision: Option<u8>,
     /// Display the current span.
     ///
     /// Outputs the number of spans that have elapsed since midnight.
     /// Zero-padded to fill three digits. Ranges from `000` to `555`.
+    /// Equivalent to `--system span`.
     #[clap(short, long)]
     span: bool,
+    /// Keep displaying the current time, updating it in place.
+    ///
+    /// The line is redrawn as soon as the displayed value changes, sleeping
+    /// until then. Press Ctrl-C to exit.
+    #[clap(short, long, conflicts_with = "when")]
+    watch: bool,
+    /// With `--watch`, also write the time into the terminal title.
+    #[clap(long, requires = "watch")]
+    title: bool,
+    /// With `--watch`, write the time only into the terminal title, leaving
+    /// the line alone.
+    #[clap(long, requires = "watch")]
+    title_only: bool,
+    /// Display the time this many nanoseconds after the epoch of the selected
+    /// system, instead of a wall-clock time.
+    ///
+    /// For most systems the epoch is midnight, so the offset is the time of
+    /// day; `unix` counts from the Unix epoch. No precision is lost to whole
+    /// milliseconds, and the offset may be negative.
+    #[clap(
+        long,
+        value_name = "N",
+        allow_hyphen_values = true,
+        conflicts_with_all = &["when", "watch", "micros"]
+    )]
+    nanos: Option<i128>,
+    /// Display the time this many microseconds after the epoch of the
+    /// selected system, instead of a wall-clock time. See `--nanos`.
+    #[clap(
+        long,
+        value_name = "N",
+        allow_hyphen_values = true,
+        conflicts_with_all = &["when", "watch"]
+    )]
+    micros: Option<i128>,
+    /// Display the instant this many milliseconds after the Unix epoch,
+    /// instead of the current time.
+    ///
+    /// Useful for timestamps taken from logs. The instant is shown in the
+    /// selected system like any other time, e.g. as a time of day for systems
+    /// that count from midnight.
+    #[clap(
+        long,
+        value_name = "N",
+        allow_hyphen_values = true,
+        conflicts_with_all = &["when", "watch", "nanos", "micros", "epoch-secs"]
+    )]
+    epoch_ms: Option<i64>,
+    /// Display the instant this many seconds after the Unix epoch, instead of
+    /// the current time. See `--epoch-ms`.
+    #[clap(
+        long,
+        value_name = "N",
+        allow_hyphen_values = true,
+        conflicts_with_all = &["when", "watch", "nanos", "micros"]
+    )]
+    epoch_secs: Option<i64>,
+    /// Keep printing the current time, on a new line whenever it changes.
+    ///
+    /// Unlike `--watch`, the cursor isn't moved, for status bars that read
+    /// the output of a script line by line, such as polybar with
+    /// `tail = true` or xmobar's `CommandReader`.
+    #[clap(
+        long,
+        conflicts_with_all = &["when", "watch", "nanos", "micros", "epoch-ms", "epoch-secs"]
+    )]
+    follow: bool,
+    /// With `--follow`, print the time at the start of each seximal duration,
+    /// instead of whenever it changes.
+    ///
+    /// For example, `1 moment` or `0:1:0.0`; the duration is read like the
+    /// `sleep` subcommand reads it.
+    #[clap(long, value_name = "DURATION", requires = "follow")]
+    interval: Option<String>,
+    /// Convert each line of this file, or of standard input if `-`, instead
+    /// of displaying the current time.
+    ///
+    /// Each line is read like `WHEN`, and written on a line of its own. Blank
+    /// lines, and lines that can't be read, are written as empty lines (or
+    /// rows of empty fields in CSV); lines that can't be read are reported,
+    /// exiting with an error at the end.
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = &[
+            "when", "watch", "follow", "nanos", "micros", "epoch-ms", "epoch-secs", "prompt"
+        ]
+    )]
+    from: Option<PathBuf>,
+    /// Convert each line of standard input, instead of displaying the current
+    /// time. The same as `--from -`.
+    #[clap(
+        long,
+        conflicts_with_all = &[
+            "from", "when", "watch", "follow", "nanos", "micros", "epoch-ms", "epoch-secs", "prompt"
+        ]
+    )]
+    stdin: bool,
+    /// Publish the time on the D-Bus session bus instead of printing it.
+    ///
+    /// The `io.github.ethwu.rn.Clock` interface at `/io/github/ethwu/rn` has a
+    /// `Now()` method returning the current time, and a `Changed` signal
+    /// emitted with the time at the start of each moment. Press Ctrl-C to
+    /// exit.
+    #[cfg(feature = "dbus")]
+    #[clap(
+        long,
+        conflicts_with_all = &[
+            "when", "watch", "follow", "nanos", "micros", "epoch-ms", "epoch-secs", "prompt",
+            "from", "stdin"
+        ]
+    )]
+    dbus: bool,
+    /// How to write the time.
+    ///
+    /// `waybar` writes a line of JSON for a Waybar custom module with
+    /// `"return-type": "json"`, with the civil time to the second as the
+    /// tooltip and the name of the system as the class. Combine with
+    /// `--watch` to write a line whenever the time changes.
+    ///
+    /// `i3bar` keeps running and speaks the i3bar protocol, for use as the
+    /// `status_command` of i3bar or swaybar. The status line is updated
+    /// whenever the time changes.
+    ///
+    /// `json` writes a JSON object with the value of each unit, the seximal
+    /// time of day in basic and span forms, and the civil time it was read
+    /// at. Combine with `--watch` to write a line whenever the time changes.
+    ///
+    /// `sh` writes a shell variable assignment for the value of each unit and
+    /// for the current span, as in `eval "$(rn --output sh)"`.
+    ///
+    /// `csv` writes a header row and a row with the civil time and the
+    /// seximal time of day in extended, basic, and span forms. Combine with
+    /// `--from` to write a row for each time converted.
+    ///
+    /// `tmux` writes the time for a tmux status line, with the colors of the
+    /// units as style markup unless colors are turned off, as in
+    /// `set -g status-right '#(rn --output tmux)'`.
+    #[clap(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value = "text",
+        conflicts_with_all = &["when", "nanos", "micros", "epoch-ms", "epoch-secs"]
+    )]
+    output: Output,
+    /// When to color the output.
+    ///
+    /// With `auto`, the output is colored only if it is a terminal and the
+    /// `NO_COLOR` environment variable isn't set.
+    #[clap(long, value_enum, value_name = "WHEN", default_value = "auto")]
+    color: ColorChoice,
+    /// Write the time for a prompt of this shell: without a trailing newline,
+    /// and with the color codes escaped so the shell doesn't count them
+    /// towards the width of the prompt.
+    ///
+    /// Colors are shown as if writing to a terminal, e.g.
+    /// `PS1='$(rn --prompt bash) \$ '`.
+    #[clap(
+        long,
+        value_enum,
+        value_name = "SHELL",
+        conflicts_with_all = &["watch", "follow", "output"]
+    )]
+    prompt: Option<Shell>,
+    /// Display how far through the day the time is, instead of the time.
+    ///
+    /// By default, as a decimal percentage, e.g. `55.002%`. With `=senary`,
+    /// as a senary fraction of the day, e.g. `0.3144454`, which has the same
+    /// digits as the basic form.
+    #[clap(
+        long,
+        value_enum,
+        value_name = "DIGITS",
+        min_values = 0,
+        max_values = 1,
+        require_equals = true,
+        default_missing_value = "decimal",
+        conflicts_with_all = &["watch", "follow", "output", "prompt", "from", "stdin", "nanos", "micros"]
+    )]
+    percent: Option<percent::Digits>,
+    /// Draw a bar this many cells wide, filled as far as the day has gone,
+    /// with the time written over it.
+    ///
+    /// Defaults to 36 cells, each two lulls long; set the width with
+    /// `--bar=WIDTH`. Combine with `--watch` or `--follow` to keep the bar up
+    /// to date.
+    #[clap(
+        long,
+        value_name = "WIDTH",
+        min_values = 0,
+        max_values = 1,
+        require_equals = true,
+        default_missing_value = "36",
+        conflicts_with_all = &["output", "prompt", "from", "stdin", "nanos", "micros", "percent"]
+    )]
+    bar: Option<usize>,
+    /// Display the time in large digits, five rows tall.
+    ///
+    /// Digits above 9 are drawn as letters, and separators are narrower than
+    /// digits. For a clock that keeps the large digits up to date, see
+    /// `rn clock`.
+    #[clap(
+        long,
+        conflicts_with_all = &[
+            "watch", "follow", "output", "prompt", "from", "stdin", "percent", "bar"
+        ]
+    )]
+    big: bool,
+    /// Write the time in large digits to a PNG image at this path, instead
+    /// of printing it.
+    ///
+    /// The digits are dark on a light background, as large as fit the
+    /// `--size` of the image, for wallpapers and e-ink displays.
+    #[cfg(feature = "png")]
+    #[clap(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = &[
+            "watch", "follow", "output", "prompt", "from", "stdin", "percent", "bar", "big"
+        ]
+    )]
+    png: Option<PathBuf>,
+    /// The width and height of the `--png` image, in pixels.
+    #[cfg(feature = "png")]
+    #[clap(long, value_name = "WxH", default_value = "800x400")]
+    size: raster::Size,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
-
-    let millis = if let Some(when) = args.when {
-        attempt_parse_time_since_midnight(&when)?
-            .signed_duration_since(NaiveTime::from_hms(0, 0, 0))
-            .to_std()
-            .unwrap()
-    } else if args.local {
-        time_since_local_midnight()
+/// Get the number of milliseconds since the selected system's epoch at the
+/// instant given by `--epoch-ms` or `--epoch-secs`, or at the time given as
+/// `WHEN`, or else now.
+fn requested_millis(
+    args: &Args,
+    system: &System,
+    observer: &Observer,
+) -> Result<i64, Box<dyn Error>> {
+    let epoch_secs = match args.epoch_secs {
+        Some(secs) => Some(
+            secs.checked_mul(1_000)
+                .ok_or("`--epoch-secs` is out of range")?,
+        ),
+        None => None,
+    };
+    if let Some(unix_millis) = args.epoch_ms.or(epoch_secs) {
+        instant_since_epoch(system, unix_millis, observer)
+    } else if let Some(when) = &args.when {
+        parse_millis_since_epoch(when, system, observer)
     } else {
-        time_since_utc_midnight()
+        millis_since_epoch(system, observer)
     }
-    .as_millis() as u32;
+}
 
-    let formatter = if args.span {
-        mk_span_time_formatter()
-    } else if args.basic || args.snap {
-        mk_snap_time_formatter()
-    } else {
-        misalian_kunimunean_time_formatter()
+/// Get the number of milliseconds since midnight in the selected time zone at
+/// the requested time, read as it would be for the time itself.
+fn day_millis(args: &Args, observer: &Observer) -> Result<u32, Box<dyn Error>> {
+    let day = System::new("day", "", mk_snap_time_formatter());
+    Ok(requested_millis(args, &day, observer)? as u32)
+}
+
+/// A shell whose prompt the time is written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Shell {
+    /// Bash, whose readline wraps escapes in `\x01` and `\x02`.
+    Bash,
+    /// Zsh, which wraps escapes in `%{` and `%}`.
+    Zsh,
+}
+
+/// Prepare text rendered with ANSI styles for the prompt of `shell`: the
+/// escape sequences are wrapped so that they aren't counted towards the width
+/// of the prompt. Bash doesn't decode `\[` and `\]` in the output of a
+/// command substitution, so escapes are wrapped in readline's own markers and
+/// nothing else is escaped. Zsh expands `%` in the output, so it is doubled.
+fn prompt(shell: Shell, styled: &str) -> String {
+    let (open, close, special) = match shell {
+        Shell::Bash => ("\x01", "\x02", None),
+        Shell::Zsh => ("%{", "%}", Some('%')),
     };
-    println!("{}", formatter.render(millis));
+    let mut out = String::with_capacity(styled.len());
+    let mut in_escape = false;
+    for c in styled.chars() {
+        match c {
+            '\x1b' => {
+                out.push_str(open);
+                out.push(c);
+                in_escape = true;
+            }
+            // the styles are SGR sequences, which all end with `m`
+            'm' if in_escape => {
+                out.push(c);
+                out.push_str(close);
+                in_escape = false;
+            }
+            c if Some(c) == special && !in_escape => {
+                out.push(c);
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// How to write the time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    /// Plain text.
+    Text,
+    /// JSON for a Waybar custom module.
+    Waybar,
+    /// The i3bar protocol, for i3bar and swaybar.
+    I3bar,
+    /// Markup for a tmux status line.
+    Tmux,
+    /// A JSON object with each part of the time.
+    Json,
+    /// Shell variable assignments.
+    Sh,
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+/// When to color the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// Color the output if it is a terminal, unless `NO_COLOR` is set.
+    Auto,
+    /// Always color the output.
+    Always,
+    /// Never color the output.
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color the output, given whether it is a `terminal` and the
+    /// value of `NO_COLOR`, which disables colors if set to anything but an
+    /// empty string.
+    fn enabled(self, terminal: bool, no_color: Option<&OsStr>) -> bool {
+        match self {
+            Self::Auto => terminal && no_color.is_none_or(OsStr::is_empty),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Show a full-screen clock in large digits.
+    ///
+    /// The basic form and the span are shown underneath. Press `q` to exit.
+    Clock,
+    /// Show a full-screen analog clock face.
+    ///
+    /// The face has 36 tick marks, and hands for the lapse, the lull, and
+    /// the moment, drawn in braille. Press `q` to exit.
+    Face,
+    /// Show the time elapsed since starting, in extended form.
+    ///
+    /// Press Enter to mark a lap, and `q` to stop. The time of each lap and
+    /// the total are printed on exit.
+    Stopwatch,
+    /// Convert a seximal time back into standard time.
+    ///
+    /// The seximal time is read as UTC and printed as `HH:MM:SS.mmm`, or in
+    /// the system time zone with `--local`.
+    Convert {
+        /// The seximal time, in extended (`31:44:45.4`), basic (`3144454`),
+        /// or span (`314`) form.
+        mk_time: String,
+    },
+    /// Show how long it is until a time of day.
+    ///
+    /// The time remaining is printed in extended form. If the time has
+    /// already passed today, the time until it tomorrow is shown.
+    Until {
+        /// The time to count down to, in any format accepted for `WHEN`,
+        /// e.g. `16:30` or `40:00:00.0`. Read in UTC, or in the system time
+        /// zone with `--local`.
+        target: String,
+    },
+    /// Show the difference between two times of day.
+    ///
+    /// The time from `A` to `B` is printed in extended form, with a `-` if
+    /// `B` is earlier in the day than `A`.
+    Diff {
+        /// The time to measure from, in any format accepted for `WHEN`.
+        a: String,
+        /// The time to measure to, in any format accepted for `WHEN`.
+        b: String,
+    },
+    /// Run a command at a seximal time of day.
+    ///
+    /// Waits until the next time it is `TIME`, today or tomorrow, and then
+    /// runs the command in place of `rn`. For example,
+    /// `rn at 43:00:00 -- notify-send lunch`. Interrupting the wait with
+    /// Ctrl-C exits without running the command.
+    At {
+        /// When to run the command, in extended (`43:00:00.0` or `43:00:00`),
+        /// basic, or span form. Read in UTC, or in the system time zone with
+        /// `--local`.
+        time: String,
+        /// The command to run and its arguments, after `--`.
+        #[clap(last = true, required = true)]
+        command: Vec<OsString>,
+    },
+    /// Wait for a seximal duration.
+    ///
+    /// For pacing shell scripts in seximal units, e.g. `rn sleep 3 moments`
+    /// or `rn sleep 0:0:3.0`.
+    Sleep {
+        /// How long to wait, in extended form or as a list of seximal values
+        /// and units, such as `1 lull and 3 snaps` or `4 spans`.
+        #[clap(required = true)]
+        duration: Vec<String>,
+    },
+    /// Count down from a seximal duration.
+    ///
+    /// The time left is shown in extended form, updating in place, e.g.
+    /// `rn timer 2 lulls`. Exits with an error if interrupted with Ctrl-C
+    /// before the time is up.
+    Timer {
+        /// How long to count down from, as for `sleep`.
+        #[clap(required = true)]
+        duration: Vec<String>,
+        /// Ring the terminal bell when the time is up.
+        #[clap(long)]
+        bell: bool,
+    },
+    /// Run commands at seximal times of day, every day.
+    ///
+    /// Reads a schedule file of times and commands, and runs each command at
+    /// its time until interrupted with Ctrl-C, like `cron` with seximal
+    /// times. Each entry of the file gives a `time`, in any form accepted by
+    /// `at`, and a `command`, as a list of the program and its arguments.
+    Schedule {
+        /// The schedule file. Defaults to `schedule.toml` next to the
+        /// configuration file.
+        path: Option<PathBuf>,
+    },
+    /// Generate systemd units that run a command at a seximal time of day.
+    ///
+    /// Prints a `.timer` unit, which starts a `.service` unit running the
+    /// command at the time every day, and the `.service` unit itself. For
+    /// example, `rn systemd-gen 43:00:00 -- notify-send lunch`.
+    SystemdGen {
+        /// When to run the command, as for `at`. Read in UTC, or in the
+        /// system time zone with `--local`.
+        time: String,
+        /// The name of the units. Defaults to `rn-` followed by the time in
+        /// basic form.
+        #[clap(long)]
+        name: Option<String>,
+        /// Write the units into this directory instead of printing them, e.g.
+        /// `~/.config/systemd/user`.
+        #[clap(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+        /// The command to run and its arguments, after `--`.
+        #[clap(last = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Sound an alarm at a seximal time of day.
+    ///
+    /// Waits until the next time it is `TIME`, today or tomorrow, and then
+    /// prints the message, shows it in a desktop notification, and rings the
+    /// terminal bell if asked to. For example,
+    /// `rn alarm 43:00:00 --message "stand up"`.
+    Alarm {
+        /// When to sound the alarm, as for `at`.
+        time: String,
+        /// The message to show.
+        #[clap(long, short)]
+        message: Option<String>,
+        /// Sound the alarm again at the same time each day, until
+        /// interrupted with Ctrl-C.
+        #[clap(long, value_enum, value_name = "WHEN")]
+        repeat: Option<alarm::Repeat>,
+        /// Ring the terminal bell.
+        #[clap(long)]
+        bell: bool,
+        /// Don't show a desktop notification.
+        #[clap(long)]
+        no_notification: bool,
+    },
+    /// Add a seximal duration to a time of day.
+    ///
+    /// The resulting time is printed in standard time and in extended form,
+    /// wrapping around past midnight. For example, `rn add 14:30 + 2 lapses
+    /// 15 moments`.
+    Add {
+        /// The time to add to, in any format accepted for `WHEN`.
+        time: String,
+        /// The duration to add, as a list of seximal values and units, such
+        /// as `2 lapses 15 moments` or `1 lull and 3 snaps`. May start with
+        /// `+`.
+        #[clap(required = true, allow_hyphen_values = true)]
+        duration: Vec<String>,
+    },
+    /// Subtract a seximal duration from a time of day.
+    ///
+    /// Like `add`, but wrapping around before midnight. For example,
+    /// `rn sub 14:30 - 2 lapses`.
+    Sub {
+        /// The time to subtract from, in any format accepted for `WHEN`.
+        time: String,
+        /// The duration to subtract, as for `add`. May start with `-`.
+        #[clap(required = true, allow_hyphen_values = true)]
+        duration: Vec<String>,
+    },
+    /// Show when files were last modified, accessed, and changed.
+    ///
+    /// Each time is shown as the date followed by the seximal time of day, in
+    /// UTC or in the system time zone with `--local`.
+    Stat {
+        /// The files to show.
+        #[clap(required = true)]
+        paths: Vec<PathBuf>,
+    },
+}
+
+/// Print the standard time equivalent to the passed seximal time.
+fn convert(mk_time: &str, tz: SelectedZone) -> Result<(), Box<dyn Error>> {
+    let millis = mk::parse(mk_time)?.millis();
+    let millis = shift_millis(millis, tz.offset_millis(Utc::now().timestamp_millis()));
+    println!("{}", civil_time_formatter().render(millis as u64));
+    Ok(())
+}
+
+/// Parse a seximal duration, either in extended form (`0:0:3.0`) or as a list
+/// of values and any of the Misalian–Kunimunean units (`2 lapses 3 moments`,
+/// `4 spans`), into an exact number of milliseconds.
+fn parse_mk_duration(duration: &str) -> Result<Ratio<u64>, ParseError> {
+    let formatter = misalian_kunimunean_time_formatter();
+    let long_form = TimeFormatter::new(formatter.base(), [Segment::List(mk_units())]);
+    let units = formatter
+        .parse_base_units(duration)
+        .or_else(|_| long_form.parse_base_units(duration))?;
+    Ok(Ratio::from_integer(units) / formatter.base())
+}
+
+impl Args {
+    /// The name of the system selected on the command line, if any.
+    fn system_name(&self) -> Result<Option<&str>, Box<dyn Error>> {
+        let selected: Vec<_> = [
+            (self.span, "span"),
+            (self.basic || self.snap, "basic"),
+            (self.extended, "extended"),
+            (self.niftimal, "niftimal"),
+            (self.mars, "mars"),
+        ]
+        .iter()
+        .filter(|(flag, _)| *flag)
+        .map(|(_, name)| *name)
+        .chain(self.system.as_deref())
+        .chain(self.system_file.as_deref().map(system_file_name))
+        .collect();
+
+        match selected[..] {
+            [] => Ok(None),
+            [name] => Ok(Some(name)),
+            _ => Err(
+                "only one of `--extended`, `--basic`, `--span`, `--niftimal`, \
+                      `--mars`, `--system`, and `--system-file` may be given"
+                    .into(),
+            ),
+        }
+    }
+}
+
+/// The name that the system defined in the file at `path` is registered as.
+fn system_file_name(path: &Path) -> &str {
+    path.file_stem().and_then(OsStr::to_str).unwrap_or_default()
+}
+
+/// Load the user-defined systems: those in the systems directory next to the
+/// configuration file, and the one passed with `--system-file`.
+fn load_specs(args: &Args) -> Result<Vec<(String, FormatterSpec)>, Box<dyn Error>> {
+    let mut specs = match Config::dir() {
+        Some(dir) => spec::load_dir(&dir.join("systems"))?,
+        None => Vec::new(),
+    };
+    if let Some(path) = &args.system_file {
+        specs.push((
+            system_file_name(path).to_string(),
+            FormatterSpec::load(path)?,
+        ));
+    }
+    Ok(specs)
+}
+
+/// Select the system and the formatter to display the time with. Options on
+/// the command line take precedence over the configuration file.
+fn formatter<'a, 'r>(
+    args: &'a Args,
+    config: &'a Config,
+    registry: &'r Registry<'a>,
+) -> Result<(TimeFormatter<'a>, &'r System<'a>), Box<dyn Error>> {
+    let selected = args.system_name()?;
+    let name = selected
+        .or(config.system.as_deref())
+        .unwrap_or(systems::DEFAULT_SYSTEM);
+    let system = registry.get(name)?;
+
+    // a template from the configuration file is only a default for when no
+    // system is selected on the command line
+    let template = match selected {
+        Some(_) => args.format.as_ref(),
+        None => args.format.as_ref().or(config.format.as_ref()),
+    };
+    let mut formatter = match (template, &config.delimiters) {
+        (Some(template), _) => system.template(template)?,
+        (None, Some([lapse, lull, moment])) if name == "extended" => {
+            mk_delimited_time_formatter([lapse, lull, moment])
+        }
+        (None, _) => system.formatter.clone(),
+    };
+    if let Some(false) = config.padding {
+        formatter.units_mut().for_each(|unit| unit.set_width(0));
+    }
+    if let Some(pad) = args.pad {
+        formatter.units_mut().for_each(|unit| unit.set_pad(pad));
+    }
+    if args.uppercase {
+        formatter
+            .units_mut()
+            .for_each(|unit| unit.set_uppercase(true));
+    }
+    if let Some(rounding) = args.round {
+        formatter.set_rounding(rounding);
+    }
+    if let (Some(precision), Some(unit)) = (args.precision, formatter.smallest_unit_mut()) {
+        unit.set_fraction_digits(precision as usize - 1);
+    }
+    if args.long {
+        formatter = formatter.long_form();
+    }
+    Ok((formatter, system))
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
+
+/// Run the command selected by the command-line arguments.
+fn run() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse_from(relative::rewrite_args(env::args_os()));
+    let config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::load_default()?,
+    };
+    let tz = match (args.zone, args.offset) {
+        (Some(zone), _) => SelectedZone::Named(zone),
+        (_, Some(offset)) => SelectedZone::Fixed(offset),
+        _ if args.local || (config.local && !args.utc) => SelectedZone::System,
+        _ => SelectedZone::Utc,
+    };
+    let font = || match &args.font {
+        Some(path) => Font::load(path),
+        None => Ok(Font::default()),
+    };
+    let observer = Observer {
+        tz,
+        longitude: args.longitude.unwrap_or_default(),
+        dst: args.dst,
+        leap: args.leap_seconds,
+        scale: args.scale,
+    };
+
+    match args.command {
+        Some(Command::Clock) => return clock::run(tz, &font()?),
+        Some(Command::Face) => return face::run(tz),
+        Some(Command::Stopwatch) => return stopwatch::run(),
+        Some(Command::Convert { ref mk_time }) => return convert(mk_time, tz),
+        Some(Command::Until { ref target }) => return until::run(target, tz),
+        Some(Command::Diff { ref a, ref b }) => return diff::run(a, b),
+        Some(Command::At {
+            ref time,
+            ref command,
+        }) => return at::run(time, command, tz),
+        Some(Command::Sleep { ref duration }) => return sleep::run(duration),
+        Some(Command::Timer { ref duration, bell }) => return timer::run(duration, bell),
+        Some(Command::Alarm {
+            ref time,
+            ref message,
+            repeat,
+            bell,
+            no_notification,
+        }) => return alarm::run(time, message.as_deref(), repeat, bell, !no_notification, tz),
+        Some(Command::Schedule { ref path }) => {
+            let path = match path {
+                Some(path) => path.clone(),
+                None => Config::dir()
+                    .ok_or("no schedule file given, and no configuration directory")?
+                    .join("schedule.toml"),
+            };
+            return daemon::run(&daemon::Schedule::load(&path)?, tz);
+        }
+        Some(Command::SystemdGen {
+            ref time,
+            ref name,
+            ref dir,
+            ref command,
+        }) => return systemd::run(time, name.as_deref(), dir.as_deref(), command, tz),
+        Some(Command::Add {
+            ref time,
+            ref duration,
+        }) => return add::run(time, duration, false),
+        Some(Command::Sub {
+            ref time,
+            ref duration,
+        }) => return add::run(time, duration, true),
+        Some(Command::Stat { ref paths }) => return stat::run(paths, tz),
+        None => {}
+    }
+
+    let specs = load_specs(&args)?;
+    let mut registry = Registry::builtin();
+    for (name, spec) in &specs {
+        let description = spec.description.as_deref().unwrap_or_default();
+        registry.register(System::new(name, description, spec.formatter()));
+    }
+
+    if args.list_systems {
+        let width = registry.iter().map(|s| s.name.len()).max().unwrap_or(0);
+        for system in registry.iter() {
+            println!(
+                "{:width$}  {}",
+                system.name,
+                system.description,
+                width = width
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(digits) = args.percent {
+        println!("{}", percent::of_day(day_millis(&args, &observer)?, digits));
+        return Ok(());
+    }
+
+    let (mut formatter, system) = formatter(&args, &config, &registry)?;
+    // tmux and shells read the styles, wherever the output goes
+    let color = args.color.enabled(
+        io::stdout().is_terminal() || args.output == Output::Tmux || args.prompt.is_some(),
+        env::var_os("NO_COLOR").as_deref(),
+    );
+    if color {
+        formatter.apply_theme(&systems::mk_theme());
+    }
+
+    let interval = match &args.interval {
+        Some(interval) => {
+            let nanos = (parse_mk_duration(interval)? * 1_000_000)
+                .ceil()
+                .to_integer() as u128;
+            Some(nanos)
+                .filter(|&nanos| nanos > 0)
+                .ok_or("the interval must be longer than zero")?;
+            Some(nanos)
+        }
+        None => None,
+    };
+
+    #[cfg(feature = "dbus")]
+    if args.dbus {
+        return dbus::serve(|| {
+            let unix_millis = Utc::now().timestamp_millis();
+            let millis = instant_since_epoch(system, unix_millis, &observer)?;
+            let day = MkTime::from_millis(time_of_day(unix_millis, tz) as u32);
+            Ok(dbus::Reading {
+                text: render(&formatter, millis, false),
+                moment: day.snaps() / 6,
+                wait: until_next_update(&formatter, millis, None),
+            })
+        });
+    }
+
+    let from = match args.stdin {
+        true => Some(Path::new("-")),
+        false => args.from.as_deref(),
+    };
+    if let Some(path) = from {
+        return match args.output {
+            Output::Text => batch::convert_lines(path, None, "", |when| {
+                let millis = parse_millis_since_epoch(when, system, &observer)?;
+                Ok(render(&formatter, millis, color))
+            }),
+            Output::Csv => {
+                batch::convert_lines(path, Some(csv::HEADER), &csv::blank_row(), |when| {
+                    Ok(csv::row(parse_millis_since_midnight(when)? as u64))
+                })
+            }
+            _ => Err("`--from` can only write text or CSV".into()),
+        };
+    }
+
+    if args.output == Output::Csv {
+        if args.watch || args.follow {
+            return Err("`--output csv` writes the time only once".into());
+        }
+        let day = time_of_day(Utc::now().timestamp_millis(), tz);
+        println!("{}\n{}", csv::HEADER, csv::row(day));
+        return Ok(());
+    }
+
+    if args.output == Output::I3bar {
+        return bar::i3bar(system.name, || {
+            let millis = millis_since_epoch(system, &observer)?;
+            let wait = until_next_update(&formatter, millis, interval);
+            Ok((render(&formatter, millis, false), wait))
+        });
+    }
+
+    if args.output == Output::Waybar {
+        let line = || {
+            let unix_millis = Utc::now().timestamp_millis();
+            let millis = instant_since_epoch(system, unix_millis, &observer)?;
+            let waybar = bar::Waybar {
+                text: &render(&formatter, millis, false),
+                tooltip: &civil_time(unix_millis, tz)?,
+                class: system.name,
+            };
+            // the tooltip changes every second, whatever the system
+            let wait = match interval {
+                Some(_) => until_next_update(&formatter, millis, interval),
+                None => watch::POLL_INTERVAL,
+            };
+            Ok((waybar.to_json(), wait))
+        };
+        return match args.watch || args.follow {
+            true => watch::follow(line),
+            false => line().map(|(line, _)| println!("{}", line)),
+        };
+    }
+
+    if args.output == Output::Json {
+        let line = || {
+            let unix_millis = Utc::now().timestamp_millis();
+            let millis = instant_since_epoch(system, unix_millis, &observer)?;
+            let wait = until_next_update(&formatter, millis, interval);
+            Ok((
+                json::reading(&formatter, system, millis, unix_millis, tz),
+                wait,
+            ))
+        };
+        return match args.watch || args.follow {
+            true => watch::follow(line),
+            false => line().map(|(line, _)| println!("{}", line)),
+        };
+    }
+
+    if args.output == Output::Sh {
+        if args.watch || args.follow {
+            return Err("`--output sh` writes the time only once".into());
+        }
+        let unix_millis = Utc::now().timestamp_millis();
+        let millis = instant_since_epoch(system, unix_millis, &observer)?;
+        let span = systems::mk_span_time_formatter().render(time_of_day(unix_millis, tz));
+        for line in shell::variables(&formatter, millis, &span) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    if args.output == Output::Tmux {
+        if args.watch {
+            return Err("`--watch` moves the cursor, which tmux doesn't understand".into());
+        }
+        let line = || {
+            let millis = millis_since_epoch(system, &observer)?;
+            let wait = until_next_update(&formatter, millis, interval);
+            Ok((bar::tmux(&render(&formatter, millis, color)), wait))
+        };
+        return match args.follow {
+            true => watch::follow(line),
+            false => line().map(|(line, _)| println!("{}", line)),
+        };
+    }
+
+    if args.follow {
+        return watch::follow(|| {
+            let unix_millis = Utc::now().timestamp_millis();
+            let millis = instant_since_epoch(system, unix_millis, &observer)?;
+            let wait = until_next_update(&formatter, millis, interval);
+            let rendered = match args.bar {
+                Some(width) => progress::bar(
+                    &render(&formatter, millis, false),
+                    time_of_day(unix_millis, tz) as u32,
+                    width,
+                    color,
+                ),
+                None => render(&formatter, millis, color),
+            };
+            Ok((rendered, wait))
+        });
+    }
+
+    if args.watch {
+        let target = match (args.title, args.title_only) {
+            (_, true) => watch::Target::Title,
+            (true, false) => watch::Target::Both,
+            (false, false) => watch::Target::Line,
+        };
+        return watch::watch(
+            || {
+                let unix_millis = Utc::now().timestamp_millis();
+                let millis = instant_since_epoch(system, unix_millis, &observer)?;
+                let plain = render(&formatter, millis, false);
+                let line = match args.bar {
+                    Some(width) => {
+                        progress::bar(&plain, time_of_day(unix_millis, tz) as u32, width, color)
+                    }
+                    None => render(&formatter, millis, color),
+                };
+                Ok((line, plain, until_next_update(&formatter, millis, interval)))
+            },
+            target,
+        );
+    }
+
+    let nanos = match (args.nanos, args.micros) {
+        (Some(nanos), _) => nanos,
+        (None, Some(micros)) => micros
+            .checked_mul(1_000)
+            .ok_or("`--micros` is out of range")?,
+        (None, None) => requested_millis(&args, system, &observer)? as i128 * 1_000_000,
+    };
+
+    #[cfg(feature = "png")]
+    if let Some(path) = &args.png {
+        let text = render_nanos(&formatter, nanos, false);
+        return raster::write(path, &font()?, &text, args.size);
+    }
+
+    let rendered = match args.bar {
+        Some(width) => progress::bar(
+            &render_nanos(&formatter, nanos, false),
+            day_millis(&args, &observer)?,
+            width,
+            color,
+        ),
+        None if args.big => font()?
+            .render(&render_nanos(&formatter, nanos, false))
+            .join("\n"),
+        None => render_nanos(&formatter, nanos, color),
+    };
+    match args.prompt {
+        Some(shell) => print!("{}", prompt(shell, &rendered)),
+        None => println!("{}", rendered),
+    }
 
     Ok(())
 }
@@ -199,29 +1714,413 @@ mod test {
         check!(senary_time_a(47521888) == "31:44:45.4");
         check!(senary_time_a(130967197) == "130:32:30.1");
 
-        let millis = time_since_utc_midnight().as_millis();
+        let millis = time_since_midnight(SelectedZone::Utc).unwrap().as_millis();
         check!(senary_time_a(millis) == senary_time_b(millis));
     }
 
     #[test]
-    fn senary_formatter() {
+    fn parse_seximal_input() {
         let mkt = misalian_kunimunean_time_formatter();
+        let basic = mk_snap_time_formatter();
 
-        check!(mkt.render(0) == "00:00:00.0");
-        check!(mkt.render(47521888) == "31:44:45.4");
-        check!(mkt.render(81218884) == "53:50:14.1");
-        check!(mkt.render(81246133) == "53:50:40.0");
-        check!(mkt.render(130967197) == "130:32:30.1");
+        let millis = parse_millis_since_midnight("31:44:45.4").unwrap();
+        check!(mkt.render(millis as u64) == "31:44:45.4");
+        check!(basic.render(millis as u64) == "3144454");
+        check!(parse_millis_since_midnight("3144454").unwrap() == millis);
+        check!(parse_millis_since_midnight("08:24:36").unwrap() == 30_276_000);
+        check!(parse_millis_since_midnight("2034060").is_err());
+        let args = Args::parse_from(["rn", "2034050", "--to", "standard"]);
+        check!(args.system.as_deref() == Some("standard"));
     }
 
     #[test]
-    fn basic_formatter() {
-        let basic = mk_snap_time_formatter();
+    fn parse_errors() {
+        for example in parse_error::EXAMPLES {
+            check!(parse_millis_since_midnight(example).is_ok(), "{}", example);
+        }
+        check!(parse_millis_since_midnight("4 pm").unwrap() == 57_600_000);
+        check!(parse_millis_since_midnight("4pm").unwrap() == 57_600_000);
+        check!(parse_millis_since_midnight("1235am").unwrap() == 2_100_000);
+
+        let err = parse_millis_since_midnight("4 pmm")
+            .unwrap_err()
+            .to_string();
+        check!(err.starts_with("`4 pmm` is not a recognized time; did you mean `4 pm`?"));
+        let err = parse_millis_since_midnight("teatime")
+            .unwrap_err()
+            .to_string();
+        check!(err.contains("accepted forms include `00:34:59`"));
+    }
+
+    #[test]
+    fn shift_across_midnight() {
+        check!(shift_millis(0, 3_600_000) == 3_600_000);
+        check!(shift_millis(82_800_000, 3_600_000) == 0);
+        check!(shift_millis(0, -3_600_000) == 82_800_000);
+        check!(utc_offset_millis(Zone::Fixed(3_600), SelectedZone::System, 0) == 3_600_000);
+        check!(utc_offset_millis(Zone::Selected, SelectedZone::Utc, 0) == 0);
+        check!(utc_offset_millis(Zone::Shifted(21_600), SelectedZone::Utc, 0) == 21_600_000);
+    }
+
+    #[test]
+    fn time_since_day_start() {
+        let date = NaiveDate::from_ymd_opt(2023, 11, 14).unwrap();
+        let midnight = start_of_day(&Utc, date).unwrap();
+        check!(midnight.timestamp() == 1_699_920_000);
+
+        let zone = chrono::FixedOffset::east_opt(-5 * 3600).unwrap();
+        let now = zone.with_ymd_and_hms(2023, 11, 14, 13, 12, 1).unwrap();
+        check!(time_since_start_of_day(now).unwrap() == Duration::from_secs(47_521));
+        check!(time_since_midnight(SelectedZone::Utc).unwrap() < Duration::from_secs(86_400));
+
+        // clocks in São Paulo went forward at midnight on 2018-11-04, so the
+        // day started at 01:00 local time
+        let sao_paulo = chrono_tz::America::Sao_Paulo;
+        let date = NaiveDate::from_ymd_opt(2018, 11, 4).unwrap();
+        let start = start_of_day(&sao_paulo, date).unwrap();
+        check!(start.timestamp() == 1_541_300_400);
+        check!(start.format("%H:%M %z").to_string() == "01:00 -0200");
+        // clocks in Havana went back from 01:00 to midnight on 2023-11-05, so
+        // the day started at the first of its two midnights
+        let havana = chrono_tz::America::Havana;
+        let date = NaiveDate::from_ymd_opt(2023, 11, 5).unwrap();
+        let start = start_of_day(&havana, date).unwrap();
+        check!(start.timestamp() == 1_699_156_800);
+        check!(start.format("%H:%M %z").to_string() == "00:00 -0400");
+    }
+
+    #[test]
+    fn stretch_local_days() {
+        let hour = 3_600_000;
+        check!(stretch_millis(11 * hour + hour / 2, 23 * hour) == 43_200_000);
+        check!(stretch_millis(25 * hour - 25, 25 * hour) == 86_399_976);
+        check!(stretch_millis(0, 25 * hour) == 0);
+        check!(stretched_time_of_day(&Utc, 1_700_000_000_000) == Some(80_000_000));
+    }
+
+    #[test]
+    fn named_zones() {
+        let tokyo = SelectedZone::Named(chrono_tz::Asia::Tokyo);
+        check!(tokyo.offset_millis(0) == 9 * 3_600_000);
+        check!(civil_time(1_700_000_000_999, tokyo).unwrap() == "07:13:20");
+
+        let new_york = chrono_tz::America::New_York;
+        check!(SelectedZone::Named(new_york).offset_millis(1_700_000_000_000) == -5 * 3_600_000);
+        check!(SelectedZone::Named(new_york).offset_millis(1_690_000_000_000) == -4 * 3_600_000);
+
+        // daylight saving time starts on 2024-03-10, which is 23 hours long
+        let date = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        check!(start_of_day(&new_york, date).unwrap().timestamp() == 1_710_046_800);
+        let noon = 1_710_086_400_000;
+        check!(stretched_time_of_day(&new_york, noon) == Some(41_321_739));
+
+        // the mode applies to subcommands too
+        let args = Args::try_parse_from(["rn", "until", "12:00", "--dst", "stretch"]).unwrap();
+        check!(args.dst == Dst::Stretch);
+    }
+
+    #[test]
+    fn fixed_offsets() {
+        let args = Args::try_parse_from(["rn", "--offset", "+09:30"]).unwrap();
+        let offset = SelectedZone::Fixed(args.offset.unwrap());
+        check!(offset.offset_millis(0) == 34_200_000);
+        check!(civil_time(1_700_000_000_999, offset).unwrap() == "07:43:20");
+        let args = Args::try_parse_from(["rn", "--offset", "-05:00"]).unwrap();
+        check!(args.offset.unwrap().local_minus_utc() == -18_000);
+        check!(Args::try_parse_from(["rn", "--offset", "+25:00"]).is_err());
+        check!(Args::try_parse_from(["rn", "--offset", "+01:00", "--local"]).is_err());
+
+        let registry = Registry::builtin();
+        let extended = registry.get("extended").unwrap();
+        let observer = Observer {
+            tz: offset,
+            ..Observer::default()
+        };
+        let millis = parse_millis_since_epoch("13:12:01", extended, &observer).unwrap();
+        check!(millis == 47_521_000);
+    }
+
+    #[test]
+    fn convert_dated_inputs() {
+        let registry = Registry::builtin();
+        let extended = registry.get("extended").unwrap();
+        let observer = Observer::default();
+        let parse = |when: &str, observer: &Observer| {
+            parse_millis_since_epoch(when, extended, observer).unwrap()
+        };
+
+        // 2001-07-07T15:04:59.999Z, holding the leap second
+        check!(parse("2001-07-08T00:34:60+09:30", &observer) == 54_299_999);
+        check!(parse("2023-11-14T22:13:20.5+00:00", &observer) == 80_000_500);
+        let tokyo = Observer {
+            tz: SelectedZone::Named(chrono_tz::Asia::Tokyo),
+            ..observer
+        };
+        check!(parse("2023-11-14T22:13:20+00:00", &tokyo) == 26_000_000);
+        check!(parse("22:13:20", &tokyo) == 80_000_000);
+    }
+
+    #[test]
+    fn unix_time_inputs() {
+        check!(parse_unix_seconds("1700000000").unwrap() == 1_700_000_000_000);
+        check!(parse_unix_seconds("1700000000.123456").unwrap() == 1_700_000_000_123);
+        check!(parse_unix_seconds("1700000000.5").unwrap() == 1_700_000_000_500);
+        check!(parse_unix_seconds("-1.5").unwrap() == -1_500);
+        check!(parse_unix_seconds("").is_err());
+        check!(parse_unix_seconds("12a").is_err());
+        check!(parse_unix_seconds("1.-5").is_err());
+
+        let registry = Registry::builtin();
+        let extended = registry.get("extended").unwrap();
+        let tokyo = Observer {
+            tz: SelectedZone::Named(chrono_tz::Asia::Tokyo),
+            ..Observer::default()
+        };
+        let millis = parse_millis_since_epoch("@1700000000", extended, &tokyo).unwrap();
+        check!(millis == 26_000_000);
+        let unix = registry.get("unix").unwrap();
+        let millis = parse_millis_since_epoch(" @1700000000.25", unix, &tokyo).unwrap();
+        check!(millis == 1_700_000_000_250);
+    }
+
+    #[test]
+    fn leap_second_modes() {
+        // 2016-12-31T23:59:59Z, just before a leap second
+        let before = 1_483_228_799_000;
+        check!(utc_time_of_day(before, LeapSeconds::Ignore) == 86_399_000);
+        check!(utc_time_of_day(before, LeapSeconds::Smear) == 86_398_000);
+        check!(utc_time_of_day(1_700_000_000_000, LeapSeconds::Smear) == 80_000_000);
+
+        let leap = parse_millis_since_midnight("23:59:60").unwrap();
+        check!(leap == 86_400_000);
+        check!(leap_time_of_day(leap + 500, LeapSeconds::Ignore).unwrap() == 86_399_999);
+        check!(leap_time_of_day(leap + 500, LeapSeconds::Smear).unwrap() == 86_399_500);
+        check!(leap_time_of_day(leap, LeapSeconds::Strict).is_err());
+        check!(leap_time_of_day(1000, LeapSeconds::Strict).unwrap() == 1000);
+        // smeared times never go backwards across the leap second
+        let smeared: Vec<u32> = (leap - 2000..leap + 1000)
+            .map(|millis| leap_time_of_day(millis, LeapSeconds::Smear).unwrap())
+            .collect();
+        check!(smeared.windows(2).all(|pair| pair[0] <= pair[1]));
+        check!(smeared.last() < Some(&mk::MILLIS_PER_DAY));
+
+        let registry = Registry::builtin();
+        let unix = registry.get("unix").unwrap();
+        let observer = Observer {
+            leap: LeapSeconds::Strict,
+            ..Observer::default()
+        };
+        check!(parse_millis_since_epoch("2016-12-31T23:59:60Z", unix, &observer).is_err());
+        let observer = Observer::default();
+        let millis = parse_millis_since_epoch("2016-12-31T23:59:60.5Z", unix, &observer).unwrap();
+        check!(millis == 1_483_228_799_999);
+
+        // the mode applies to subcommands too
+        let args = ["rn", "until", "12:00", "--leap-seconds", "smear"];
+        check!(Args::try_parse_from(args).unwrap().leap_seconds == LeapSeconds::Smear);
+    }
+
+    #[test]
+    fn atomic_time_scales() {
+        let instant = 1_700_000_000_000;
+        check!(scale_millis(instant, Scale::Utc).unwrap() == instant);
+        check!(scale_millis(instant, Scale::Tai).unwrap() == instant + 37_000);
+        check!(scale_millis(instant, Scale::Gps).unwrap() == instant + 18_000);
+        check!(scale_millis(0, Scale::Tai).is_err());
+
+        let registry = Registry::builtin();
+        let extended = registry.get("extended").unwrap();
+        let observer = Observer {
+            scale: Scale::Tai,
+            ..Observer::default()
+        };
+        // 22:13:57 TAI
+        let millis = instant_since_epoch(extended, instant, &observer).unwrap();
+        check!(millis == 80_037_000);
+    }
+
+    #[test]
+    fn parse_durations() {
+        let moment = Ratio::new(86_400_000, 36 * 36 * 36);
+        check!(parse_mk_duration("3 moments") == Ok(moment * 3));
+        check!(parse_mk_duration("0:0:3.0") == Ok(moment * 3));
+        check!(parse_mk_duration("00:00:03.0") == Ok(moment * 3));
+        check!(parse_mk_duration("1 lapse") == Ok(Ratio::from_integer(2_400_000)));
+        check!(parse_mk_duration("3 minutes").is_err());
+        check!(parse_mk_duration("2 lapses 3 moments") == parse_mk_duration("2:0:3.0"));
+        check!(parse_mk_duration("4 spans") == parse_mk_duration("0:40:0.0"));
+        check!(parse_mk_duration("1 span and 1 snap") == parse_mk_duration("0:10:0.1"));
+    }
+
+    #[test]
+    fn civil_tooltip() {
+        check!(civil_time(1_700_000_000_999, SelectedZone::Utc).unwrap() == "22:13:20");
+        check!(civil_time(-1, SelectedZone::Utc).unwrap() == "23:59:59");
+        let args = Args::parse_from(["rn", "--output", "waybar", "-w"]);
+        check!(args.output == Output::Waybar);
+        check!(Args::try_parse_from(["rn", "--output", "waybar", "12:00"]).is_err());
+        check!(Args::parse_from(["rn", "--output", "i3bar"]).output == Output::I3bar);
+    }
+
+    #[test]
+    fn follow_updates() {
+        let registry = Registry::builtin();
+        let mkt = &registry.get("extended").unwrap().formatter;
+        let moment = Duration::from_nanos(1_851_851_852);
+        check!(until_next_update(mkt, 0, None) == Duration::from_nanos(308_641_976));
+        check!(
+            until_next_update(mkt, 1, Some(moment.as_nanos())) == moment - Duration::from_millis(1)
+        );
+        check!(until_next_update(mkt, -1, None) == watch::POLL_INTERVAL);
+
+        let args = Args::parse_from(["rn", "--follow", "--interval", "1 moment"]);
+        check!(args.follow && args.interval.as_deref() == Some("1 moment"));
+        check!(Args::try_parse_from(["rn", "--interval", "1 moment"]).is_err());
+        check!(Args::try_parse_from(["rn", "--follow", "--watch"]).is_err());
+    }
+
+    #[test]
+    fn extra_precision() {
+        let registry = Registry::builtin();
+        let config = Config::default();
+        let args = Args::parse_from(["rn", "--precision", "2"]);
+        let (extended, _) = formatter(&args, &config, &registry).unwrap();
+        check!(extended.render(47_521_888) == "31:44:45.45");
+
+        let args = Args::parse_from(["rn", "--precision", "3", "--system", "basic"]);
+        let (basic, _) = formatter(&args, &config, &registry).unwrap();
+        check!(basic.render(47_521_888) == "314445453");
+        check!(Args::try_parse_from(["rn", "--precision", "0"]).is_err());
+        check!(Args::try_parse_from(["rn", "--precision", "13"]).is_err());
+    }
+
+    #[test]
+    fn percent_through_the_day() {
+        check!(Args::parse_from(["rn", "--percent"]).percent == Some(percent::Digits::Decimal));
+        let args = Args::parse_from(["rn", "--percent=senary", "12:00"]);
+        check!(args.percent == Some(percent::Digits::Senary));
+        check!(args.when.as_deref() == Some("12:00"));
+        check!(Args::try_parse_from(["rn", "--percent", "--watch"]).is_err());
+
+        // the time is read as it is for the time itself
+        let observer = Observer::default();
+        let day_millis_of = |when: &str| day_millis(&Args::parse_from(["rn", when]), &observer);
+        check!(day_millis_of("12:00").unwrap() == 43_200_000);
+        check!(day_millis_of("@1700000000").unwrap() == 80_000_000);
+        check!(day_millis_of("2023-11-14T22:13:20+09:00").unwrap() == 47_600_000);
+        check!(day_millis_of("+30m").is_ok());
+        let args = Args::parse_from(["rn", "--epoch-secs", "1700000000"]);
+        check!(day_millis(&args, &observer).unwrap() == 80_000_000);
+    }
+
+    #[test]
+    fn day_progress_bar() {
+        check!(Args::parse_from(["rn", "--bar"]).bar == Some(36));
+        check!(Args::parse_from(["rn", "--watch", "--bar=12"]).bar == Some(12));
+        // the time after `--bar` isn't taken for its width
+        let args = Args::parse_from(["rn", "--bar", "12:00"]);
+        check!(args.bar == Some(36));
+        check!(args.when.as_deref() == Some("12:00"));
+        let args = Args::parse_from(["rn", "--bar=20", "+30m"]);
+        check!(args.bar == Some(20));
+        check!(args.when.as_deref() == Some("+30m"));
+        check!(Args::try_parse_from(["rn", "--bar", "--percent"]).is_err());
+    }
+
+    #[test]
+    fn big_digits() {
+        check!(Args::parse_from(["rn", "--big", "noon"]).big);
+        let args = Args::parse_from(["rn", "clock", "--font", "banner.flf"]);
+        check!(args.font.as_deref() == Some(Path::new("banner.flf")));
+        check!(Args::try_parse_from(["rn", "--big", "--watch"]).is_err());
+        check!(Args::try_parse_from(["rn", "--big", "--bar"]).is_err());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png_images() {
+        let args = Args::parse_from(["rn", "--png", "now.png", "noon"]);
+        check!(args.png.as_deref() == Some(Path::new("now.png")));
+        check!(
+            args.size
+                == raster::Size {
+                    width: 800,
+                    height: 400
+                }
+        );
+        let args = Args::parse_from(["rn", "--png", "now.png", "--size", "1600x900"]);
+        check!(
+            args.size
+                == raster::Size {
+                    width: 1600,
+                    height: 900
+                }
+        );
+        check!(Args::try_parse_from(["rn", "--png", "now.png", "--size", "big"]).is_err());
+        check!(Args::try_parse_from(["rn", "--png", "now.png", "--watch"]).is_err());
+    }
+
+    #[test]
+    fn prompt_escapes() {
+        let styled = "\x1b[1;35m31\x1b[0m:44%";
+        check!(prompt(Shell::Bash, styled) == "\x01\x1b[1;35m\x0231\x01\x1b[0m\x02:44%");
+        check!(prompt(Shell::Zsh, styled) == "%{\x1b[1;35m%}31%{\x1b[0m%}:44%%");
+        check!(prompt(Shell::Bash, "a\\b") == "a\\b");
+        check!(prompt(Shell::Zsh, "lapse") == "lapse");
+    }
+
+    #[test]
+    fn color_choice() {
+        let no_color = OsStr::new("1");
+        check!(ColorChoice::Auto.enabled(true, None));
+        check!(ColorChoice::Auto.enabled(true, Some(OsStr::new(""))));
+        check!(!ColorChoice::Auto.enabled(true, Some(no_color)));
+        check!(!ColorChoice::Auto.enabled(false, None));
+        check!(ColorChoice::Always.enabled(false, Some(no_color)));
+        check!(!ColorChoice::Never.enabled(true, None));
+    }
+
+    #[test]
+    fn parse_unix_input() {
+        let registry = Registry::builtin();
+        let observer = Observer::default();
+        let unix = registry.get("unix").unwrap();
+
+        let millis = parse_millis_since_epoch("2023-11-14T22:13:20Z", unix, &observer).unwrap();
+        check!(millis == 1_700_000_000_000);
+        check!(render(&unix.formatter, millis, false) == "440404522212");
+        check!(parse_millis_since_epoch("22:13:20", unix, &observer).is_err());
+
+        let millis = parse_millis_since_epoch("1969-12-31T23:59:20Z", unix, &observer).unwrap();
+        check!(render(&unix.formatter, millis, false) == "-104");
+
+        let julian = registry.get("julian").unwrap();
+        let millis = parse_millis_since_epoch("2023-11-14T22:13:20Z", julian, &observer).unwrap();
+        check!(render(&julian.formatter, millis, false) == "124422035.232000");
+
+        let mars = registry.get("mars").unwrap();
+        let millis = parse_millis_since_epoch("2000-01-06T00:00:00Z", mars, &observer).unwrap();
+        check!(render(&mars.formatter, millis, false) == "23:59:44 MTC (55:55:43.2)");
+
+        let sidereal = registry.get("sidereal").unwrap();
+        let west = Observer {
+            longitude: -122.4194,
+            ..observer
+        };
+        let millis = parse_millis_since_epoch("2024-01-01T00:00:00Z", sidereal, &west).unwrap();
+        check!(render(&sidereal.formatter, millis, false) == "22:30:55");
+
+        let args = Args::parse_from(["rn", "--epoch-secs", "1700000000"]);
+        check!(args.epoch_secs == Some(1_700_000_000));
+        check!(Args::try_parse_from(["rn", "--epoch-ms", "0", "--epoch-secs", "0"]).is_err());
+        let millis = instant_since_epoch(unix, 1_700_000_000_000, &observer).unwrap();
+        check!(render(&unix.formatter, millis, false) == "440404522212");
+        let extended = registry.get("extended").unwrap();
+        let millis = instant_since_epoch(extended, 1_700_000_000_000, &observer).unwrap();
+        check!(render(&extended.formatter, millis, false) == "53:20:00.0");
 
-        check!(basic.render(0) == "0000000");
-        check!(basic.render(47521888) == "3144454");
-        check!(basic.render(81218884) == "5350141");
-        check!(basic.render(81246133) == "5350400");
-        check!(basic.render(130967197) == "13032301");
+        let stardate = registry.get("stardate").unwrap();
+        let millis = parse_millis_since_epoch("2024-07-02T00:00:00Z", stardate, &observer).unwrap();
+        check!(render(&stardate.formatter, millis, false) == "-298500.00");
     }
 }
@@ -0,0 +1,33 @@
+//! Conversion of instants to Coordinated Mars Time (MTC), the mean solar time
+//! at the Martian prime meridian.
+
+/// The number of microseconds in a sol (a Martian solar day).
+pub const MICROS_PER_SOL: u64 = 88_775_244_147;
+
+/// The number of microseconds from the Mars Sol Date epoch (Julian Date
+/// 2,405,522.0028779 in Terrestrial Time) to the Unix epoch.
+const MSD_EPOCH_TO_UNIX_EPOCH: i64 = 3_029_658_951_349_440;
+/// The difference between Terrestrial Time and UTC, in microseconds.
+const TT_MINUS_UTC: i64 = 69_184_000;
+
+/// Get the number of milliseconds since midnight MTC at the instant
+/// `unix_millis` milliseconds after the Unix epoch.
+pub fn millis_since_midnight(unix_millis: i64) -> u64 {
+    let micros = unix_millis * 1000 + TT_MINUS_UTC + MSD_EPOCH_TO_UNIX_EPOCH;
+    micros.rem_euclid(MICROS_PER_SOL as i64) as u64 / 1000
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn coordinated_mars_time() {
+        // 2000-01-06T00:00:00Z is just before midnight at the end of sol 44,795
+        check!(millis_since_midnight(947_116_800_000) == 88_758_968);
+        // 2000-01-06T00:00:20Z is just after it
+        check!(millis_since_midnight(947_116_820_000) == 3_724);
+    }
+}
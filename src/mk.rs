@@ -0,0 +1,370 @@
+//! Parsing of times written in Misalian–Kunimunean Seximal Units.
+
+use alloc::string::{String, ToString};
+use core::{error::Error, fmt, str::FromStr};
+
+#[cfg(feature = "chrono")]
+use chrono::{NaiveTime, Timelike};
+
+/// The number of snaps in a day.
+pub const SNAPS_PER_DAY: u32 = 36 * 36 * 36 * 6;
+/// The number of milliseconds in a day.
+pub const MILLIS_PER_DAY: u32 = 86_400_000;
+
+/// The number of snaps in a span.
+const SNAPS_PER_SPAN: u32 = 6 * 36 * 6;
+/// The number of nanoseconds in a day.
+#[cfg(any(feature = "chrono", feature = "time"))]
+const NANOS_PER_DAY: u64 = 86_400_000_000_000;
+
+/// A time of day in Misalian–Kunimunean Seximal Units, as the number of snaps
+/// since midnight.
+///
+/// Parses from extended (`31:44:45.4`) or basic (`3144454`) form, and
+/// displays in extended form.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MkTime(u32);
+
+impl MkTime {
+    /// The start of the day.
+    pub const MIDNIGHT: Self = Self(0);
+
+    /// The time `snaps` snaps after midnight, if that is within the day.
+    pub fn from_snaps(snaps: u32) -> Option<Self> {
+        Some(Self(snaps)).filter(|_| snaps < SNAPS_PER_DAY)
+    }
+
+    /// The snap that contains the time `millis` milliseconds after midnight.
+    /// Times past the end of the day wrap around.
+    pub fn from_millis(millis: u32) -> Self {
+        let millis = millis % MILLIS_PER_DAY;
+        Self((millis as u64 * SNAPS_PER_DAY as u64 / MILLIS_PER_DAY as u64) as u32)
+    }
+
+    /// The number of snaps since midnight.
+    pub fn snaps(self) -> u32 {
+        self.0
+    }
+
+    /// The number of milliseconds since midnight, rounded up so that the
+    /// result falls within this snap. See [`snaps_to_millis`].
+    pub fn millis(self) -> u32 {
+        snaps_to_millis(self.0)
+    }
+
+    /// The number of lapses since midnight.
+    pub fn lapse(self) -> u32 {
+        self.0 / (36 * 36 * 6)
+    }
+
+    /// The number of lulls since the start of the lapse.
+    pub fn lull(self) -> u32 {
+        self.0 / (36 * 6) % 36
+    }
+
+    /// The number of moments since the start of the lull.
+    pub fn moment(self) -> u32 {
+        self.0 / 6 % 36
+    }
+
+    /// The number of snaps since the start of the moment.
+    pub fn snap(self) -> u32 {
+        self.0 % 6
+    }
+}
+
+impl FromStr for MkTime {
+    type Err = ParseMkError;
+
+    /// Parse a time in extended or basic form. Use [`parse`] to also accept
+    /// span form.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        parse_extended(s)
+            .or_else(|| parse_basic(s))
+            .map(Self)
+            .ok_or_else(|| ParseMkError::new(s, false))
+    }
+}
+
+impl fmt::Display for MkTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (lapse, lull, moment) = (self.lapse(), self.lull(), self.moment());
+        write!(
+            f,
+            "{}{}:{}{}:{}{}.{}",
+            lapse / 6,
+            lapse % 6,
+            lull / 6,
+            lull % 6,
+            moment / 6,
+            moment % 6,
+            self.snap(),
+        )
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<NaiveTime> for MkTime {
+    /// The snap that contains `time`. Leap seconds are counted as the last
+    /// instant of the second before them.
+    fn from(time: NaiveTime) -> Self {
+        let nanos = time.num_seconds_from_midnight() as u64 * 1_000_000_000
+            + time.nanosecond().min(999_999_999) as u64;
+        Self((nanos as u128 * SNAPS_PER_DAY as u128 / NANOS_PER_DAY as u128) as u32)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<MkTime> for NaiveTime {
+    /// The start of the snap, rounded up to the nanosecond so that it falls
+    /// within the snap.
+    fn from(time: MkTime) -> Self {
+        let nanos = snaps_to_nanos(time.0);
+        NaiveTime::from_num_seconds_from_midnight_opt(
+            (nanos / 1_000_000_000) as u32,
+            (nanos % 1_000_000_000) as u32,
+        )
+        .expect("a snap is within the day")
+    }
+}
+
+/// Error returned when a string is not a Misalian–Kunimunean time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMkError {
+    /// The string that couldn't be parsed.
+    input: String,
+    /// Why it couldn't be parsed.
+    kind: ParseMkErrorKind,
+}
+
+/// Why a string is not a Misalian–Kunimunean time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMkErrorKind {
+    /// The string is seven digits, so it can only be in basic form, but not
+    /// every digit is 0 to 5.
+    BasicDigit,
+    /// The string is in neither extended nor basic form.
+    Form,
+    /// The string is in none of extended, basic, or span form.
+    SpanForm,
+}
+
+impl ParseMkError {
+    /// The error for `input`, which isn't in any accepted form. Span form is
+    /// accepted if `span` is set.
+    fn new(input: &str, span: bool) -> Self {
+        // seven senary digits always fall within a day
+        let kind = match input.len() == 7 && input.bytes().all(|b| b.is_ascii_digit()) {
+            true => ParseMkErrorKind::BasicDigit,
+            false if span => ParseMkErrorKind::SpanForm,
+            false => ParseMkErrorKind::Form,
+        };
+        Self {
+            input: input.to_string(),
+            kind,
+        }
+    }
+
+    /// Why the string couldn't be parsed.
+    pub fn kind(&self) -> ParseMkErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for ParseMkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseMkErrorKind::BasicDigit => write!(
+                f,
+                "`{}` is not a seximal time in basic form: every digit must be 0 to 5",
+                self.input
+            ),
+            ParseMkErrorKind::SpanForm => write!(
+                f,
+                "`{}` is not a seximal time in extended (`lp:ll:mt.sn`), basic \
+                 (seven digits), or span (three digits) form",
+                self.input
+            ),
+            ParseMkErrorKind::Form => write!(
+                f,
+                "`{}` is not a seximal time in extended (`lp:ll:mt.sn`) or basic \
+                 (seven digits) form",
+                self.input
+            ),
+        }
+    }
+}
+
+impl Error for ParseMkError {}
+
+/// Parse a time in extended, basic, or span form. Times in span form are the
+/// first snap of the span.
+pub fn parse(s: &str) -> Result<MkTime, ParseMkError> {
+    let s = s.trim();
+    parse_extended(s)
+        .or_else(|| parse_basic(s))
+        .or_else(|| parse_span(s))
+        .map(MkTime)
+        .ok_or_else(|| ParseMkError::new(s, true))
+}
+
+/// Parse a time in extended form (`lp:ll:mt.sn`).
+pub fn parse_extended(s: &str) -> Option<u32> {
+    let (lapse, rest) = s.split_once(':')?;
+    let (lull, rest) = rest.split_once(':')?;
+    let (moment, snap) = rest.split_once('.')?;
+    if snap.len() != 1 {
+        return None;
+    }
+
+    Some(
+        ((senary(lapse, 36)? * 36 + senary(lull, 36)?) * 36 + senary(moment, 36)?) * 6
+            + senary(snap, 6)?,
+    )
+}
+
+/// Parse a time in extended form without the snap (`lp:ll:mt`). The result is
+/// the first snap of the moment.
+pub fn parse_moment(s: &str) -> Option<u32> {
+    let (lapse, rest) = s.split_once(':')?;
+    let (lull, moment) = rest.split_once(':')?;
+    Some(((senary(lapse, 36)? * 36 + senary(lull, 36)?) * 36 + senary(moment, 36)?) * 6)
+}
+
+/// Parse a time in basic form: the number of snaps since midnight as seven
+/// senary digits.
+pub fn parse_basic(s: &str) -> Option<u32> {
+    if s.len() != 7 {
+        return None;
+    }
+    senary(s, SNAPS_PER_DAY)
+}
+
+/// Parse a time in span form: the number of spans since midnight as three
+/// senary digits. The result is the first snap of the span.
+pub fn parse_span(s: &str) -> Option<u32> {
+    if s.len() != 3 {
+        return None;
+    }
+    Some(senary(s, SNAPS_PER_DAY / SNAPS_PER_SPAN)? * SNAPS_PER_SPAN)
+}
+
+/// Convert a number of snaps to milliseconds. Rounds up, so that the result
+/// falls within the same snap when it is rendered again.
+pub fn snaps_to_millis(snaps: u32) -> u32 {
+    (snaps as u64 * MILLIS_PER_DAY as u64).div_ceil(SNAPS_PER_DAY as u64) as u32
+}
+
+/// Convert a number of snaps to nanoseconds, rounded up.
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn snaps_to_nanos(snaps: u32) -> u64 {
+    (snaps as u128 * NANOS_PER_DAY as u128).div_ceil(SNAPS_PER_DAY as u128) as u64
+}
+
+/// Convert a number of snaps since midnight to the time of day at which the
+/// snap starts, to the nanosecond (rounded up). Snaps past the end of the day
+/// wrap around.
+#[cfg(feature = "time")]
+pub fn snaps_to_time(snaps: u32) -> time::Time {
+    let nanos = snaps_to_nanos(snaps % SNAPS_PER_DAY);
+    time::Time::MIDNIGHT + time::Duration::nanoseconds(nanos as i64)
+}
+
+/// Parse a non-empty string of senary digits with a value less than `limit`.
+fn senary(s: &str, limit: u32) -> Option<u32> {
+    if s.is_empty() || !s.bytes().all(|b| (b'0'..=b'5').contains(&b)) {
+        return None;
+    }
+    u32::from_str_radix(s, 6).ok().filter(|&v| v < limit)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn parse_forms() {
+        check!(parse("00:00:00.0") == Ok(MkTime::MIDNIGHT));
+        check!(parse("31:44:45.4") == Ok(MkTime(153970)));
+        check!(parse("3144454") == Ok(MkTime(153970)));
+        check!(parse("5555555") == Ok(MkTime(SNAPS_PER_DAY - 1)));
+        check!(parse("203") == Ok(MkTime(parse_basic("2030000").unwrap())));
+        check!(parse(" 1:2:3.4 ") == Ok(MkTime(((36 + 2) * 36 + 3) * 6 + 4)));
+        check!(parse_moment("43:00:00") == parse_extended("43:00:00.0"));
+        check!(parse_moment("43:00:00.0").is_none());
+    }
+
+    #[test]
+    fn reject_invalid() {
+        check!(parse("60:00:00.0").is_err());
+        check!(parse("00:00:00.6").is_err());
+        check!(parse("00:00:00.00").is_err());
+        check!(parse("00:00:00").is_err());
+        check!(parse("3144464").is_err());
+        check!(parse("314445").is_err());
+        check!(parse("+1:00:00.0").is_err());
+        let err = parse("2034060").unwrap_err();
+        check!(err.kind() == ParseMkErrorKind::BasicDigit);
+        check!(
+            err.to_string()
+                == "`2034060` is not a seximal time in basic form: every digit must be 0 to 5"
+        );
+        check!(parse("6:00").unwrap_err().kind() == ParseMkErrorKind::SpanForm);
+        check!("203".parse::<MkTime>().unwrap_err().kind() == ParseMkErrorKind::Form);
+    }
+
+    #[test]
+    fn mk_time() {
+        let time: MkTime = "31:44:45.4".parse().unwrap();
+        check!(time.snaps() == 153970);
+        check!((time.lapse(), time.lull(), time.moment(), time.snap()) == (19, 28, 29, 4));
+        check!(time.to_string() == "31:44:45.4");
+        check!(" 3144454".parse() == Ok(time));
+        check!("1:2:3.4".parse::<MkTime>().unwrap().to_string() == "01:02:03.4");
+        check!("203".parse::<MkTime>().is_err());
+        check!(MkTime::from_snaps(SNAPS_PER_DAY - 1).unwrap().to_string() == "55:55:55.5");
+        check!(MkTime::from_snaps(SNAPS_PER_DAY).is_none());
+        check!(MkTime::from_millis(47_521_888) == time);
+        check!(MkTime::from_millis(MILLIS_PER_DAY) == MkTime::MIDNIGHT);
+        check!(MkTime::from_millis(time.millis()) == time);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn naive_time_round_trip() {
+        let naive = NaiveTime::from_hms_milli_opt(13, 12, 1, 888).unwrap();
+        let time = MkTime::from(naive);
+        check!(time.to_string() == "31:44:45.4");
+        check!(
+            NaiveTime::from(time) == NaiveTime::from_hms_nano_opt(13, 12, 1, 604_938_272).unwrap()
+        );
+        check!(MkTime::from(NaiveTime::from(time)) == time);
+        let leap = NaiveTime::from_hms_milli_opt(23, 59, 59, 1_500).unwrap();
+        check!(MkTime::from(leap) == MkTime(SNAPS_PER_DAY - 1));
+        for snaps in [0, 1, SNAPS_PER_DAY - 1] {
+            check!(MkTime::from(NaiveTime::from(MkTime(snaps))) == MkTime(snaps));
+        }
+    }
+
+    #[test]
+    fn millis_round_trip() {
+        for snaps in [0, 1, 153970, SNAPS_PER_DAY - 1] {
+            let millis = snaps_to_millis(snaps) as u64;
+            check!(millis * SNAPS_PER_DAY as u64 / MILLIS_PER_DAY as u64 == snaps as u64);
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_of_snaps() {
+        check!(snaps_to_time(0) == time::Time::MIDNIGHT);
+        check!(snaps_to_time(1) == time::Time::from_hms_nano(0, 0, 0, 308_641_976).unwrap());
+        check!(snaps_to_time(153970) == time::Time::from_hms_nano(13, 12, 1, 604_938_272).unwrap());
+        check!(snaps_to_time(SNAPS_PER_DAY) == time::Time::MIDNIGHT);
+        let last = time::Time::from_hms_nano(23, 59, 59, 691_358_025).unwrap();
+        check!(snaps_to_time(SNAPS_PER_DAY - 1) == last);
+    }
+}
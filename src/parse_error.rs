@@ -0,0 +1,125 @@
+//! Errors for times of day that can't be parsed, with suggestions for inputs
+//! that are nearly right.
+
+use std::{error::Error, fmt};
+
+/// Examples of the accepted forms of a time of day, shown when none match.
+pub const EXAMPLES: [&str; 10] = [
+    "00:34:59",
+    "00:35",
+    "12:34:59 AM",
+    "4pm",
+    "6h 45m",
+    "1235",
+    "31:44:45.4",
+    "3144454",
+    "noon",
+    "quarter past three",
+];
+
+/// The characters tried when looking for a near miss.
+const ALPHABET: &str = "0123456789:. abcdefghijklmnopqrstuvwxyz";
+
+/// Inputs longer than this are too far from any time to look for near misses.
+const MAX_SUGGESTION_LEN: usize = 32;
+
+/// Error returned when a time of day is in none of the accepted forms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTimeError {
+    /// The input that couldn't be parsed.
+    input: String,
+    /// An accepted input one edit away, if there is one.
+    suggestion: Option<String>,
+}
+
+impl ParseTimeError {
+    /// Construct an error for `input`, suggesting the nearest input that
+    /// `parses` accepts.
+    pub fn new(input: &str, parses: impl Fn(&str) -> bool) -> Self {
+        Self {
+            input: input.to_string(),
+            suggestion: near_miss(input, parses),
+        }
+    }
+}
+
+impl fmt::Display for ParseTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a recognized time", self.input)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "; did you mean `{}`?", suggestion)?;
+        }
+        let (last, examples) = EXAMPLES.split_last().expect("there are examples");
+        write!(f, "\naccepted forms include ")?;
+        for example in examples {
+            write!(f, "`{}`, ", example)?;
+        }
+        write!(f, "and `{}`", last)
+    }
+}
+
+impl Error for ParseTimeError {}
+
+/// Find an input that `parses` accepts one edit away from `input`: with a
+/// character deleted, two neighbouring characters swapped, a character
+/// replaced, or a character inserted, tried in that order.
+fn near_miss(input: &str, parses: impl Fn(&str) -> bool) -> Option<String> {
+    let chars: Vec<char> = input.trim().chars().collect();
+    if chars.is_empty() || chars.len() > MAX_SUGGESTION_LEN {
+        return None;
+    }
+    let edit = |i: usize, remove: usize, insert: &[char]| -> String {
+        let mut edited = chars.clone();
+        edited.splice(i..i + remove, insert.iter().copied());
+        edited.into_iter().collect()
+    };
+
+    let deletions = (0..chars.len()).map(|i| edit(i, 1, &[]));
+    let swaps = (1..chars.len()).map(|i| edit(i - 1, 2, &[chars[i], chars[i - 1]]));
+    let replacements = (0..chars.len())
+        .flat_map(|i| ALPHABET.chars().map(move |c| (i, c)))
+        .filter(|&(i, c)| chars[i] != c)
+        .map(|(i, c)| edit(i, 1, &[c]));
+    let insertions = (0..=chars.len())
+        .flat_map(|i| ALPHABET.chars().map(move |c| (i, c)))
+        .map(|(i, c)| edit(i, 0, &[c]));
+
+    deletions
+        .chain(swaps)
+        .chain(replacements)
+        .chain(insertions)
+        .map(|candidate| candidate.trim().to_string())
+        .find(|candidate| !candidate.is_empty() && parses(candidate))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    fn is_pm(s: &str) -> bool {
+        s == "4pm"
+    }
+
+    #[test]
+    fn suggest_near_misses() {
+        check!(near_miss("4pmm", is_pm).as_deref() == Some("4pm"));
+        check!(near_miss("p4m", is_pm).as_deref() == Some("4pm"));
+        check!(near_miss("4pn", is_pm).as_deref() == Some("4pm"));
+        check!(near_miss("4p", is_pm).as_deref() == Some("4pm"));
+        check!(near_miss(" 4p ", is_pm).as_deref() == Some("4pm"));
+        check!(near_miss("teatime", is_pm).is_none());
+        check!(near_miss("", |_| true).is_none());
+    }
+
+    #[test]
+    fn describe_errors() {
+        let message = ParseTimeError::new("4pmm", is_pm).to_string();
+        check!(message.starts_with("`4pmm` is not a recognized time; did you mean `4pm`?\n"));
+        check!(message.ends_with("`noon`, and `quarter past three`"));
+        check!(!ParseTimeError::new("teatime", is_pm)
+            .to_string()
+            .contains("did you mean"));
+    }
+}
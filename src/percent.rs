@@ -0,0 +1,42 @@
+//! How far through the day the time is.
+
+use clap::ValueEnum;
+
+use rn::{mk, systems::mk_snap_time_formatter};
+
+/// How to write how far through the day the time is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Digits {
+    /// A decimal percentage, to the thousandth of a percent.
+    Decimal,
+    /// A senary fraction, to the snap.
+    Senary,
+}
+
+/// Write how far through the day the time `millis` milliseconds after
+/// midnight is, rounded down, as for `--percent`.
+pub fn of_day(millis: u32, digits: Digits) -> String {
+    match digits {
+        Digits::Decimal => {
+            let thousandths = millis as u64 * 100_000 / mk::MILLIS_PER_DAY as u64;
+            format!("{}.{:03}%", thousandths / 1000, thousandths % 1000)
+        }
+        Digits::Senary => format!("0.{}", mk_snap_time_formatter().render(millis as u64)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn percent_of_day() {
+        check!(of_day(47_521_888, Digits::Decimal) == "55.002%");
+        check!(of_day(0, Digits::Decimal) == "0.000%");
+        check!(of_day(mk::MILLIS_PER_DAY - 1, Digits::Decimal) == "99.999%");
+        check!(of_day(47_521_888, Digits::Senary) == "0.3144454");
+        check!(of_day(43_200_000, Digits::Senary) == "0.3000000");
+    }
+}
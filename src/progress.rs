@@ -0,0 +1,43 @@
+//! The day drawn as a progress bar.
+
+use rn::mk;
+
+/// Draw a bar `width` cells wide, filled as far as the time `millis`
+/// milliseconds after midnight is through the day, with `text` written over
+/// its middle. The bar is widened to fit the text. If `color` is set, the
+/// filled part is shown in reverse video; otherwise it is drawn with `=`.
+pub fn bar(text: &str, millis: u32, width: usize, color: bool) -> String {
+    let text: Vec<char> = text.chars().collect();
+    let width = width.max(text.len());
+    let filled = (millis as u128 * width as u128 / mk::MILLIS_PER_DAY as u128) as usize;
+    let start = (width - text.len()) / 2;
+    let cell = |i: usize| match (i.checked_sub(start).and_then(|j| text.get(j)), i < filled) {
+        (Some(&c), _) => c,
+        (None, true) if !color => '=',
+        (None, _) => ' ',
+    };
+    let (left, right): (String, String) = (
+        (0..filled).map(cell).collect(),
+        (filled..width).map(cell).collect(),
+    );
+    match color {
+        true if filled > 0 => format!("[\x1b[7m{}\x1b[27m{}]", left, right),
+        _ => format!("[{}{}]", left, right),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn day_progress_bar() {
+        check!(bar("31:44:45.4", 47_521_888, 16, false) == "[===31:44:45.4   ]");
+        check!(bar("31:44:45.4", 0, 12, false) == "[ 31:44:45.4 ]");
+        check!(bar("31:44:45.4", 47_521_888, 4, false) == "[31:44:45.4]");
+        check!(bar("3", 43_200_000, 4, true) == "[\x1b[7m 3\x1b[27m  ]");
+        check!(bar("3", 0, 4, true) == "[ 3  ]");
+    }
+}
@@ -0,0 +1,79 @@
+//! A Python extension module, so that scripts can show times in the same
+//! systems as `rn` without running it.
+//!
+//! ```python
+//! import rn
+//!
+//! rn.render(47521888)  # "31:44:45.4"
+//! rn.TimeFormatter.preset("span").render(47521888)  # "314"
+//! ```
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    formatter::TimeFormatter,
+    systems::{misalian_kunimunean_time_formatter, Registry},
+};
+
+/// Render the time `ms` milliseconds after midnight in extended form. Times
+/// before midnight are shown with a leading `-`.
+#[pyfunction]
+fn render(ms: i64) -> String {
+    misalian_kunimunean_time_formatter().render_signed(ms)
+}
+
+/// Parse a time in extended form into milliseconds after midnight.
+#[pyfunction]
+fn parse(s: &str) -> PyResult<u64> {
+    misalian_kunimunean_time_formatter()
+        .parse(s)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// The names of the built-in systems, for `TimeFormatter.preset`.
+#[pyfunction]
+fn presets() -> Vec<&'static str> {
+    Registry::builtin()
+        .iter()
+        .map(|system| system.name)
+        .collect()
+}
+
+/// A formatter for one of the built-in systems.
+#[pyclass(name = "TimeFormatter")]
+struct PyTimeFormatter(TimeFormatter<'static>);
+
+#[pymethods]
+impl PyTimeFormatter {
+    /// Look up the built-in system `name`, as listed by `presets`.
+    #[staticmethod]
+    fn preset(name: &str) -> PyResult<Self> {
+        match Registry::builtin().get(name) {
+            Ok(system) => Ok(Self(system.formatter.clone())),
+            Err(err) => Err(PyValueError::new_err(err.to_string())),
+        }
+    }
+
+    /// Render the time `ms` milliseconds after the start of the day (or of
+    /// whichever epoch the system counts from).
+    fn render(&self, ms: i64) -> String {
+        self.0.render_signed(ms)
+    }
+
+    /// Parse a time rendered by this formatter into milliseconds.
+    fn parse(&self, s: &str) -> PyResult<u64> {
+        self.0
+            .parse(s)
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+}
+
+/// The `rn` Python module.
+#[pymodule]
+fn rn(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyTimeFormatter>()?;
+    module.add_function(wrap_pyfunction!(render, module)?)?;
+    module.add_function(wrap_pyfunction!(parse, module)?)?;
+    module.add_function(wrap_pyfunction!(presets, module)?)?;
+    Ok(())
+}
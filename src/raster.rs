@@ -0,0 +1,156 @@
+//! PNG images of the time, drawn with the large glyphs.
+
+use std::{error::Error, fs::File, io::BufWriter, path::Path, str::FromStr};
+
+use crate::big::Font;
+
+/// The most pixels an image may have, so that a mistyped size can't use up
+/// all the memory. Enough for an image 8192 pixels square.
+const MAX_PIXELS: usize = 1 << 26;
+
+/// The size of an image, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Size {
+    /// The number of pixels across.
+    pub width: u32,
+    /// The number of pixels down.
+    pub height: u32,
+}
+
+impl Size {
+    /// The number of pixels in an image of this size, or `None` if that is
+    /// more than [`MAX_PIXELS`].
+    fn pixels(self) -> Option<usize> {
+        (self.width as usize)
+            .checked_mul(self.height as usize)
+            .filter(|&pixels| pixels <= MAX_PIXELS)
+    }
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("`{}` isn't a size such as `800x400`", s);
+        let (width, height) = s.split_once(['x', 'X']).ok_or_else(invalid)?;
+        let size = Self {
+            width: width.trim().parse().map_err(|_| invalid())?,
+            height: height.trim().parse().map_err(|_| invalid())?,
+        };
+        if size.width == 0 || size.height == 0 {
+            return Err(invalid());
+        }
+        match size.pixels() {
+            Some(_) => Ok(size),
+            None => Err(format!(
+                "`{}` is larger than the largest image, of {} pixels",
+                s, MAX_PIXELS
+            )),
+        }
+    }
+}
+
+/// Draw `lines` of large glyphs as a grayscale image of `size`, dark on
+/// light, with each character that isn't a space as a block, as large as fit
+/// with a margin of one block around them. Returns a row-major byte per
+/// pixel, or an error if the image is too small for even one pixel per block
+/// or has more than [`MAX_PIXELS`].
+fn rasterize(lines: &[String], size: Size) -> Result<Vec<u8>, String> {
+    let pixels = size.pixels().ok_or_else(|| {
+        format!(
+            "{}x{} is larger than the largest image, of {} pixels",
+            size.width, size.height, MAX_PIXELS
+        )
+    })?;
+    let rows: Vec<Vec<bool>> = lines
+        .iter()
+        .map(|row| row.chars().map(|c| c != ' ').collect())
+        .collect();
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let (width, height) = (size.width as usize, size.height as usize);
+    let block = (width / (columns + 2)).min(height / (rows.len() + 2));
+    if block == 0 {
+        return Err(format!(
+            "{}x{} is too small for the time",
+            size.width, size.height
+        ));
+    }
+    let left = (width - columns * block) / 2;
+    let top = (height - rows.len() * block) / 2;
+
+    let mut pixels = vec![u8::MAX; pixels];
+    for (r, row) in rows.iter().enumerate() {
+        for (c, _) in row.iter().enumerate().filter(|(_, &filled)| filled) {
+            for y in top + r * block..top + (r + 1) * block {
+                let start = y * width + left + c * block;
+                pixels[start..start + block].fill(0);
+            }
+        }
+    }
+    Ok(pixels)
+}
+
+/// Write `text` in large glyphs of `font` to a PNG image of `size` at `path`.
+/// Every character must have a glyph, since a character drawn as itself would
+/// only show as a block.
+pub fn write(path: &Path, font: &Font, text: &str, size: Size) -> Result<(), Box<dyn Error>> {
+    if let Some(c) = text.chars().find(|&c| !font.has_glyph(c)) {
+        return Err(format!("the font has no glyph for `{}`", c).into());
+    }
+    let pixels = rasterize(&font.render(text), size)?;
+    let file = File::create(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), size.width, size.height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&pixels)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    fn size(width: u32, height: u32) -> Size {
+        Size { width, height }
+    }
+
+    #[test]
+    fn parse_sizes() {
+        check!("800x400".parse() == Ok(size(800, 400)));
+        check!("1X2".parse() == Ok(size(1, 2)));
+        check!("800".parse::<Size>().is_err());
+        check!("0x400".parse::<Size>().is_err());
+        check!("ax400".parse::<Size>().is_err());
+        check!("8192x8192".parse() == Ok(size(8192, 8192)));
+        check!("8192x8193".parse::<Size>().is_err());
+        check!("4294967295x4294967295".parse::<Size>().is_err());
+    }
+
+    #[test]
+    fn draw_blocks() {
+        // `1` is three blocks across and five down, in a frame of one block
+        let one = Font::default().render("1");
+        let pixels = rasterize(&one, size(10, 14)).unwrap();
+        check!(pixels.len() == 140);
+        let pixel = |x: usize, y: usize| pixels[y * 10 + x];
+        check!(pixel(0, 0) == u8::MAX);
+        // the top of the `1` is its middle block
+        check!(pixel(2, 2) == u8::MAX);
+        check!(pixel(4, 2) == 0);
+        check!(pixel(5, 3) == 0);
+        check!(pixel(6, 2) == u8::MAX);
+        // the foot of the `1` spans all three blocks
+        check!((2..8).all(|x| pixel(x, 11) == 0));
+        check!(rasterize(&one, size(4, 14)).is_err());
+        check!(rasterize(&one, size(u32::MAX, u32::MAX)).is_err());
+    }
+
+    #[test]
+    fn missing_glyphs() {
+        let path = Path::new("/nonexistent/time.png");
+        let err = write(path, &Font::default(), "12é3", size(100, 100)).unwrap_err();
+        check!(err.to_string() == "the font has no glyph for `é`");
+    }
+}
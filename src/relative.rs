@@ -0,0 +1,130 @@
+//! Times relative to now, such as `+30m`.
+
+use std::ffi::OsString;
+
+use clap::CommandFactory;
+
+use crate::Args;
+
+/// Parse a time relative to now, such as `+30m`, `-2h15m`, or `now+3s`, into
+/// the number of milliseconds from now. The offset is a list of whole numbers
+/// of days (`d`), hours (`h`), minutes (`m`), and seconds (`s`). Returns
+/// `None` if `when` isn't a relative time.
+pub fn parse_millis(when: &str) -> Option<i64> {
+    let when = when.trim();
+    let offset = match when.strip_prefix("now") {
+        Some("") => return Some(0),
+        Some(offset) => offset.trim_start(),
+        None => when,
+    };
+    let (sign, mut rest) = match offset.split_at_checked(1)? {
+        ("+", rest) => (1, rest.trim_start()),
+        ("-", rest) => (-1, rest.trim_start()),
+        _ => return None,
+    };
+    let mut millis: i64 = 0;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let value: i64 = rest[..digits].parse().ok()?;
+        let unit = match rest[digits..].chars().next()? {
+            'd' => 86_400_000,
+            'h' => 3_600_000,
+            'm' => 60_000,
+            's' => 1000,
+            _ => return None,
+        };
+        millis = millis.checked_add(value.checked_mul(unit)?)?;
+        rest = rest[digits + 1..].trim_start();
+    }
+    match offset.len() > 1 {
+        true => Some(sign * millis),
+        false => None,
+    }
+}
+
+/// Rewrite a time before now, such as `-2h15m`, as `now-2h15m`, so that it
+/// isn't taken for an option.
+fn to_now(arg: OsString) -> OsString {
+    match arg.to_str() {
+        Some(s) if s.starts_with('-') && parse_millis(s).is_some() => format!("now{}", s).into(),
+        _ => arg,
+    }
+}
+
+/// Rewrite the top-level `WHEN` in `args` with [`to_now`]. Option values are
+/// skipped over, and nothing after the first other positional argument, such
+/// as a subcommand, or after `--` is rewritten, so that the arguments of
+/// subcommands, such as the offset of `diff`, are left alone.
+pub fn rewrite_args(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    let command = Args::command();
+    let takes_value = |arg: &str| {
+        let arg = match (arg.strip_prefix("--"), arg.strip_prefix('-')) {
+            (Some(long), _) => command.get_arguments().find(|a| a.get_long() == Some(long)),
+            (_, Some(short)) if short.chars().count() == 1 => {
+                let short = short.chars().next();
+                command.get_arguments().find(|a| a.get_short() == short)
+            }
+            _ => None,
+        };
+        arg.is_some_and(|arg| arg.is_takes_value_set())
+    };
+
+    let mut args = args.into_iter();
+    let mut rewritten: Vec<OsString> = args.next().into_iter().collect();
+    let mut value = false;
+    for arg in args.by_ref() {
+        let s = arg.to_str().unwrap_or_default();
+        if value {
+            value = false;
+        } else if s == "--" {
+            rewritten.push(arg);
+            break;
+        } else if s.starts_with('-') && parse_millis(s).is_none() {
+            value = !s.contains('=') && takes_value(s);
+        } else {
+            rewritten.push(to_now(arg));
+            break;
+        }
+        rewritten.push(arg);
+    }
+    rewritten.extend(args);
+    rewritten
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+    use clap::Parser;
+
+    #[test]
+    fn relative_inputs() {
+        check!(parse_millis("+30m") == Some(1_800_000));
+        check!(parse_millis("-2h15m") == Some(-8_100_000));
+        check!(parse_millis("now+3s") == Some(3000));
+        check!(parse_millis("now - 1d 2s") == Some(-86_402_000));
+        check!(parse_millis("now") == Some(0));
+        check!(parse_millis("+").is_none());
+        check!(parse_millis("+3").is_none());
+        check!(parse_millis("+3x").is_none());
+        check!(parse_millis("12:00").is_none());
+        check!(parse_millis("nowhere").is_none());
+
+        let args = rewrite_args(["rn", "-b", "-2h15m"].map(OsString::from));
+        let args = Args::try_parse_from(args).unwrap();
+        check!(args.basic);
+        check!(args.when.as_deref() == Some("now-2h15m"));
+        let args = rewrite_args(["rn", "--zone", "UTC", "-2h"].map(OsString::from));
+        check!(Args::try_parse_from(args).unwrap().when.as_deref() == Some("now-2h"));
+        // only the top-level `WHEN` is rewritten
+        let args = ["rn", "diff", "12:00", "-1h"].map(OsString::from);
+        check!(rewrite_args(args.clone()) == args);
+        let args = ["rn", "-1h", "-2h"].map(OsString::from);
+        check!(rewrite_args(args) == ["rn", "now-1h", "-2h"]);
+        let args = ["rn", "systemd-gen", "-5m", "--", "ls", "-1s"].map(OsString::from);
+        check!(rewrite_args(args.clone()) == args);
+        check!(to_now("--longitude".into()) == "--longitude");
+        check!(to_now("-122".into()) == "-122");
+    }
+}
@@ -0,0 +1,63 @@
+//! Shell variable assignments for the time.
+
+use num::rational::Ratio;
+
+use rn::formatter::TimeFormatter;
+
+/// Write shell variable assignments for the value of each unit in the time
+/// `millis` milliseconds since the epoch, followed by `span`, unless the
+/// formatter has a unit of that name. Variables are named after their units,
+/// in upper case.
+pub fn variables(formatter: &TimeFormatter, millis: i64, span: &str) -> Vec<String> {
+    let variable = |name: &str, value: &str| {
+        let name: String = name
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c.to_ascii_uppercase(),
+                false => '_',
+            })
+            .collect();
+        let value = match value.chars().all(|c| c.is_ascii_alphanumeric()) {
+            true => value.to_string(),
+            false => format!("'{}'", value.replace('\'', "'\\''")),
+        };
+        format!("{}={}", name, value)
+    };
+    let values = formatter.unit_values(millis.unsigned_abs() as u128 * 1_000_000);
+    let mut lines: Vec<_> = values
+        .iter()
+        .map(|(unit, value)| variable(unit.name(), &unit.render(Ratio::from_integer(*value))))
+        .collect();
+    if values.iter().all(|(unit, _)| unit.name() != "span") {
+        lines.push(variable("span", span));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    use rn::{
+        formatter::{Segment, TimeUnit},
+        systems::Registry,
+    };
+
+    #[test]
+    fn shell_assignments() {
+        let registry = Registry::builtin();
+        let mkt = &registry.get("extended").unwrap().formatter;
+        check!(
+            variables(mkt, 47_521_888, "314")
+                == ["LAPSE=31", "LULL=44", "MOMENT=45", "SNAP=4", "SPAN=314"]
+        );
+        let span = &registry.get("span").unwrap().formatter;
+        check!(variables(span, 47_521_888, "314") == ["SPAN=314"]);
+        let mut hour = TimeUnit::new("Day's hour", 3_600_000, 24, 2);
+        hour.set_pad(' ');
+        let padded = TimeFormatter::new((1, 1), [Segment::Value(hour)]);
+        check!(variables(&padded, 3_600_000, "0") == ["DAY_S_HOUR=' 1'", "SPAN=0"]);
+    }
+}
@@ -0,0 +1,51 @@
+//! Conversion of instants to sidereal time, the hour angle of the vernal
+//! equinox.
+
+/// The number of microseconds in a sidereal day.
+pub const MICROS_PER_SIDEREAL_DAY: u64 = 86_164_090_500;
+
+/// The number of milliseconds in a solar day.
+#[cfg(feature = "std")]
+const MILLIS_PER_DAY: i64 = 86_400_000;
+/// The J2000.0 epoch (2000-01-01T12:00:00Z), in milliseconds since the Unix
+/// epoch.
+#[cfg(feature = "std")]
+const J2000: i64 = 946_728_000_000;
+/// Greenwich mean sidereal time at the J2000.0 epoch, in sidereal days.
+#[cfg(feature = "std")]
+const GMST_AT_J2000: f64 = 0.779_057_273_25;
+/// The number of sidereal days that elapse per solar day, less one.
+#[cfg(feature = "std")]
+const SIDEREAL_DAYS_PER_DAY: f64 = 0.002_737_909_350_795;
+
+/// Get the number of milliseconds since local sidereal midnight at the
+/// instant `unix_millis` milliseconds after the Unix epoch, as seen from
+/// `longitude` degrees east of Greenwich.
+#[cfg(feature = "std")]
+pub fn millis_since_midnight(unix_millis: i64, longitude: f64) -> u64 {
+    let days = (unix_millis - J2000).div_euclid(MILLIS_PER_DAY);
+    let remainder = (unix_millis - J2000).rem_euclid(MILLIS_PER_DAY) as f64 / MILLIS_PER_DAY as f64;
+
+    // whole days are split off so that their whole sidereal days don't eat
+    // into the precision of the fraction of the current sidereal day
+    let sidereal_days = GMST_AT_J2000
+        + SIDEREAL_DAYS_PER_DAY * days as f64
+        + (1.0 + SIDEREAL_DAYS_PER_DAY) * remainder
+        + longitude / 360.0;
+    (sidereal_days.rem_euclid(1.0) * MICROS_PER_SIDEREAL_DAY as f64 / 1000.0) as u64
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn greenwich_mean_sidereal_time() {
+        check!(millis_since_midnight(J2000, 0.0) == 67_126_761);
+        // 2024-01-01T00:00:00Z
+        check!(millis_since_midnight(1_704_067_200_000, 0.0) == 23_970_995);
+        check!(millis_since_midnight(1_704_067_200_000, -122.4194) == 80_834_651);
+    }
+}
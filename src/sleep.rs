@@ -0,0 +1,40 @@
+//! Waiting for a seximal duration.
+
+use std::{error::Error, thread, time::Duration};
+
+use rn::formatter::ParseError;
+
+use crate::parse_mk_duration;
+
+/// Get the real time equivalent to a seximal duration, given as separate
+/// words, rounded up to the nanosecond.
+pub fn real_duration(duration: &[String]) -> Result<Duration, ParseError> {
+    let millis = parse_mk_duration(&duration.join(" "))?;
+    Ok(Duration::from_nanos(
+        (millis * 1_000_000).ceil().to_integer(),
+    ))
+}
+
+/// Wait for the seximal `duration`, as for `rn sleep`.
+pub fn run(duration: &[String]) -> Result<(), Box<dyn Error>> {
+    thread::sleep(real_duration(duration)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn real_durations() {
+        let duration =
+            |words: &str| real_duration(&words.split(' ').map(String::from).collect::<Vec<_>>());
+        check!(duration("2 lulls") == Ok(Duration::from_nanos(133_333_333_334)));
+        check!(duration("1 snap") == Ok(Duration::from_nanos(308_641_976)));
+        check!(duration("0:0:1.0") == Ok(Duration::from_nanos(1_851_851_852)));
+        check!(duration("1 lapse and 1 snap") == Ok(Duration::from_nanos(2_400_308_641_976)));
+        check!(duration("3 minutes").is_err());
+    }
+}
@@ -0,0 +1,394 @@
+//! User-defined time formatters loaded from TOML files.
+//!
+//! A specification lists the base ratio and the segments to render:
+//!
+//! ```toml
+//! description = "French Revolutionary decimal time"
+//! # 100,000 decimal seconds per 86,400,000 ms
+//! base = [100000, 86400000]
+//!
+//! [[segments]]
+//! unit = "hour"
+//! value = 10000
+//! limit = 10
+//! width = 1
+//!
+//! [[segments]]
+//! literal = ":"
+//!
+//! [[segments]]
+//! unit = "minute"
+//! value = 100
+//! limit = 100
+//! ```
+
+use std::{
+    error::Error,
+    ffi::OsStr,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::formatter::{FormatterError, Rounding, Segment, SignPlacement, TimeFormatter, TimeUnit};
+
+/// The radix used by units that don't specify one.
+const DEFAULT_RADIX: u8 = 10;
+/// The padding width used by units that don't specify one.
+const DEFAULT_WIDTH: usize = 2;
+
+/// The specification of a time formatter.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FormatterSpec {
+    /// A short description of the system, shown by `--list-systems`.
+    pub description: Option<String>,
+    /// The number of base units per the number of milliseconds, as a
+    /// numerator and denominator.
+    pub base: (u64, u64),
+    /// How to handle the part of a time too small for the smallest unit.
+    /// Defaults to `floor`.
+    pub rounding: Option<Rounding>,
+    /// Where the sign of a negative time is written: `leading` (the default),
+    /// `before-units`, or `trailing`.
+    pub sign: Option<SignPlacement>,
+    /// The segments to render, in display order.
+    pub segments: Vec<SegmentSpec>,
+}
+
+/// The specification of a single segment: either a `literal` or a `unit`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SegmentSpec {
+    /// The text of a literal segment.
+    pub literal: Option<String>,
+    /// The name of the unit of a value segment.
+    pub unit: Option<String>,
+    /// The radix of the unit. Defaults to 10.
+    pub radix: Option<u8>,
+    /// The value of the unit as a multiple of the base unit.
+    pub value: Option<u32>,
+    /// The number of these units before the next larger unit.
+    pub limit: Option<u32>,
+    /// Whether the unit has no limit, such as a count of days. Only the
+    /// largest unit may be unbounded.
+    pub unbounded: Option<bool>,
+    /// How wide to pad the unit. Defaults to 2.
+    pub width: Option<usize>,
+    /// The character to pad the unit with. Defaults to `0`.
+    pub pad: Option<char>,
+    /// The glyph for each digit of the unit, in order of value. Defaults to
+    /// `0-9` then `a-z`.
+    pub digits: Option<String>,
+    /// Whether to write digits above 9 as uppercase letters. Defaults to
+    /// false.
+    pub uppercase: Option<bool>,
+    /// How many digits of the part of the unit below one to write after it,
+    /// such as the moments within a lull. Defaults to none.
+    pub fraction_digits: Option<usize>,
+    /// The radix point written before the fraction digits, such as `.` for
+    /// `50.40`. Defaults to none, running the digits on from the value's.
+    pub point: Option<String>,
+}
+
+/// Error returned when a formatter specification cannot be loaded.
+#[derive(Debug)]
+pub enum SpecError {
+    /// The file or directory could not be read.
+    Io(PathBuf, io::Error),
+    /// The file is not valid TOML or has unexpected fields.
+    Toml(PathBuf, toml::de::Error),
+    /// The base ratio has a zero numerator or denominator.
+    InvalidBase,
+    /// There are no unit segments.
+    NoUnits,
+    /// A segment is invalid. Segments are numbered from one.
+    InvalidSegment(usize, &'static str),
+    /// The segments don't describe a usable formatter.
+    Formatter(FormatterError),
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "could not read {}: {}", path.display(), err),
+            Self::Toml(path, err) => write!(f, "invalid system {}: {}", path.display(), err),
+            Self::InvalidBase => write!(f, "the base ratio must not contain zero"),
+            Self::NoUnits => write!(f, "at least one segment must be a unit"),
+            Self::InvalidSegment(i, reason) => write!(f, "segment {}: {}", i, reason),
+            Self::Formatter(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for SpecError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(_, err) => Some(err),
+            Self::Toml(_, err) => Some(err),
+            Self::Formatter(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Load and validate every specification (`*.toml`) in `dir`, returning each
+/// with the name of its file, without the extension. A missing directory
+/// contains no specifications.
+pub fn load_dir(dir: &Path) -> Result<Vec<(String, FormatterSpec)>, SpecError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(SpecError::Io(dir.to_owned(), err)),
+    };
+
+    let mut specs = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| SpecError::Io(dir.to_owned(), err))?
+            .path();
+        if path.extension() != Some(OsStr::new("toml")) {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(OsStr::to_str) {
+            specs.push((name.to_string(), FormatterSpec::load(&path)?));
+        }
+    }
+    specs.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(specs)
+}
+
+impl FormatterSpec {
+    /// Load and validate the specification at `path`.
+    pub fn load(path: &Path) -> Result<Self, SpecError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| SpecError::Io(path.to_owned(), err))?;
+        let spec: Self =
+            toml::from_str(&contents).map_err(|err| SpecError::Toml(path.to_owned(), err))?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Check that this specification describes a usable formatter.
+    pub fn validate(&self) -> Result<(), SpecError> {
+        if self.base.0 == 0 || self.base.1 == 0 {
+            return Err(SpecError::InvalidBase);
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            segment
+                .validate()
+                .map_err(|reason| SpecError::InvalidSegment(i + 1, reason))?;
+        }
+        if self.segments.iter().all(|segment| segment.unit.is_none()) {
+            return Err(SpecError::NoUnits);
+        }
+        self.formatter().validate().map_err(SpecError::Formatter)
+    }
+
+    /// Construct the formatter described by this specification. The
+    /// specification should be validated first.
+    pub fn formatter(&self) -> TimeFormatter<'_> {
+        let mut formatter =
+            TimeFormatter::new(self.base, self.segments.iter().map(SegmentSpec::segment));
+        formatter.set_rounding(self.rounding.unwrap_or_default());
+        formatter.set_sign(self.sign.unwrap_or_default());
+        formatter
+    }
+}
+
+impl SegmentSpec {
+    /// Check that this segment is either a literal or a complete unit.
+    fn validate(&self) -> Result<(), &'static str> {
+        let unit_fields = self.radix.is_some()
+            || self.value.is_some()
+            || self.limit.is_some()
+            || self.unbounded.is_some()
+            || self.width.is_some()
+            || self.pad.is_some()
+            || self.digits.is_some()
+            || self.uppercase.is_some()
+            || self.fraction_digits.is_some()
+            || self.point.is_some();
+        match (&self.literal, &self.unit) {
+            (Some(_), Some(_)) => Err("a segment cannot be both a `literal` and a `unit`"),
+            (None, None) => Err("a segment must be either a `literal` or a `unit`"),
+            (Some(_), None) if unit_fields => Err("a `literal` cannot have unit options"),
+            (Some(_), None) => Ok(()),
+            (None, Some(name)) if name.is_empty() => Err("a `unit` must have a name"),
+            (None, Some(_)) => match (self.radix, self.value, self.limit) {
+                (Some(radix), ..) if radix < 2 => Err("`radix` must be at least 2"),
+                (Some(radix), ..) if radix as usize > self.digit_count() => {
+                    Err("`radix` must be at most the number of `digits`")
+                }
+                (_, None, _) => Err("a `unit` must have a `value`"),
+                (_, _, None) if !self.is_unbounded() => Err("a `unit` must have a `limit`"),
+                (_, Some(0), _) => Err("`value` must not be zero"),
+                (_, _, Some(0)) => Err("`limit` must not be zero"),
+                _ if self.point.is_some() && self.fraction_digits.is_none() => {
+                    Err("a `point` needs `fraction_digits`")
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Whether the unit has no limit.
+    fn is_unbounded(&self) -> bool {
+        self.unbounded.unwrap_or_default()
+    }
+
+    /// The number of digits the unit has glyphs for.
+    fn digit_count(&self) -> usize {
+        self.digits
+            .as_ref()
+            .map_or(36, |digits| digits.chars().count())
+    }
+
+    /// Construct the segment described by this specification.
+    fn segment(&self) -> Segment<'_> {
+        match (&self.literal, &self.unit) {
+            (Some(literal), _) => Segment::Literal(literal),
+            (None, name) => {
+                let mut unit = TimeUnit::with_radix(
+                    self.radix.unwrap_or(DEFAULT_RADIX),
+                    name.as_deref().unwrap_or_default(),
+                    self.value.unwrap_or(1),
+                    self.limit.unwrap_or(u32::MAX),
+                    self.width.unwrap_or(DEFAULT_WIDTH),
+                );
+                if let Some(pad) = self.pad {
+                    unit.set_pad(pad);
+                }
+                if let Some(digits) = &self.digits {
+                    unit = unit.with_digits(digits);
+                }
+                if let Some(point) = &self.point {
+                    unit.set_point(point);
+                }
+                unit.set_uppercase(self.uppercase.unwrap_or_default());
+                unit.set_unbounded(self.is_unbounded());
+                unit.set_fraction_digits(self.fraction_digits.unwrap_or_default());
+                Segment::Value(unit)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn load_spec() {
+        let spec: FormatterSpec = toml::from_str(
+            r#"
+            base = [100000, 86400000]
+
+            [[segments]]
+            unit = "hour"
+            value = 10000
+            limit = 10
+            width = 1
+
+            [[segments]]
+            literal = ":"
+
+            [[segments]]
+            unit = "minute"
+            value = 100
+            limit = 100
+            pad = "_"
+
+            [[segments]]
+            literal = ":"
+
+            [[segments]]
+            unit = "second"
+            value = 1
+            limit = 100
+            "#,
+        )
+        .unwrap();
+        check!(spec.validate().is_ok());
+
+        let decimal = spec.formatter();
+        check!(decimal.render(0) == "0:_0:00");
+        check!(decimal.render(43_200_000) == "5:_0:00");
+        check!(decimal.render(86_399_999) == "9:99:99");
+    }
+
+    #[test]
+    fn fractional_units() {
+        let spec: FormatterSpec = toml::from_str(
+            r#"
+            base = [279936, 86400000]
+            segments = [
+                { unit = "lapse", radix = 6, value = 7776, limit = 36 },
+                { literal = ":" },
+                { unit = "lull", radix = 6, value = 216, limit = 36, fraction_digits = 2, point = "." },
+            ]
+            "#,
+        )
+        .unwrap();
+        check!(spec.validate().is_ok());
+
+        let lulls = spec.formatter();
+        check!(lulls.render(0) == "00:00.00");
+        check!(lulls.render(47_521_888) == "31:44.45");
+        check!(lulls.parse_base_units("31:44.45") == Ok(153_792));
+        check!(lulls.parse_base_units("31:44").is_err());
+        check!(lulls.parse_base_units("31:44.4").is_err());
+    }
+
+    #[test]
+    fn reject_invalid_specs() {
+        let invalid = |s: &str| toml::from_str::<FormatterSpec>(s).unwrap().validate();
+
+        check!(matches!(
+            invalid("base = [0, 1]\nsegments = [{ unit = \"x\", value = 1, limit = 1 }]"),
+            Err(SpecError::InvalidBase)
+        ));
+        check!(matches!(
+            invalid("base = [1, 1]\nsegments = [{ literal = \"x\" }]"),
+            Err(SpecError::NoUnits)
+        ));
+        check!(matches!(
+            invalid(
+                "base = [1, 1]\nsegments = [{ unit = \"x\", value = 1, limit = 1, radix = 1 }]"
+            ),
+            Err(SpecError::InvalidSegment(1, _))
+        ));
+        check!(matches!(
+            invalid("base = [1, 1]\nsegments = [{ literal = \":\" }, { unit = \"x\", limit = 1 }]"),
+            Err(SpecError::InvalidSegment(2, _))
+        ));
+        check!(matches!(
+            invalid("base = [1, 1]\nsegments = [{ unit = \"x\", literal = \":\" }]"),
+            Err(SpecError::InvalidSegment(1, _))
+        ));
+        check!(matches!(
+            invalid(
+                "base = [1, 1]\nsegments = [{ unit = \"h\", value = 60, limit = 24 }, \
+                 { unit = \"m\", value = 1, limit = 30 }]"
+            ),
+            Err(SpecError::Formatter(FormatterError::Gap(..)))
+        ));
+        check!(matches!(
+            invalid("base = [1, 1]\nsegments = [{ unit = \"x\", value = 1 }]"),
+            Err(SpecError::InvalidSegment(1, _))
+        ));
+        check!(matches!(
+            invalid("base = [1, 1]\nsegments = [{ unit = \"x\", value = 1, unbounded = true }]"),
+            Ok(())
+        ));
+        check!(matches!(
+            invalid(
+                "base = [1, 1]\nsegments = [{ unit = \"x\", value = 1, limit = 1, point = \".\" }]"
+            ),
+            Err(SpecError::InvalidSegment(1, _))
+        ));
+    }
+}
@@ -0,0 +1,50 @@
+//! Conversion of instants to stardates, in the style of _Star Trek: The Next
+//! Generation_: each year since 2323 is a thousand units, divided evenly
+//! across the days of the year.
+
+use chrono::{Datelike, TimeZone, Utc};
+
+/// The year of stardate 0.
+const EPOCH_YEAR: i32 = 2323;
+/// The number of hundredths of a stardate in a year.
+const HUNDREDTHS_PER_YEAR: i64 = 100_000;
+
+/// Get the number of milliseconds since the Unix epoch at the start of
+/// `year`.
+fn year_start_millis(year: i32) -> Option<i64> {
+    Some(
+        Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+            .single()?
+            .timestamp_millis(),
+    )
+}
+
+/// Get the stardate, in hundredths, at the instant `unix_millis`
+/// milliseconds after the Unix epoch. Stardates before 2323 are negative.
+pub fn hundredths(unix_millis: i64) -> Option<i64> {
+    let year = Utc.timestamp_millis_opt(unix_millis).single()?.year();
+    let start = year_start_millis(year)?;
+    let length = year_start_millis(year + 1)? - start;
+
+    Some(
+        HUNDREDTHS_PER_YEAR * (year - EPOCH_YEAR) as i64
+            + (unix_millis - start) * HUNDREDTHS_PER_YEAR / length,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn stardates() {
+        // 2323-01-01T00:00:00Z
+        check!(hundredths(11_139_552_000_000) == Some(0));
+        // 2364-07-02T00:00:00Z, halfway through a leap year
+        check!(hundredths(12_449_203_200_000) == Some(4_150_000));
+        // 2024-01-01T00:00:00Z
+        check!(hundredths(1_704_067_200_000) == Some(-29_900_000));
+    }
+}
@@ -0,0 +1,120 @@
+//! Timestamps of files in seximal time.
+
+use std::{error::Error, fs, io, path::Path, time::SystemTime};
+
+use chrono::{DateTime, Utc};
+
+use rn::mk::MkTime;
+
+use crate::SelectedZone;
+
+/// Print when each file in `paths` was last modified, accessed, and changed,
+/// as the civil date and the seximal time of day, in the time zone `tz`. Files
+/// that can't be read are reported and skipped, failing once every file has
+/// been shown.
+pub fn run<P: AsRef<Path>>(paths: &[P], tz: SelectedZone) -> Result<(), Box<dyn Error>> {
+    let mut failed = 0;
+    let mut shown = false;
+    for path in paths {
+        let path = path.as_ref();
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(err) => {
+                eprintln!("{}: {}", path.display(), err);
+                failed += 1;
+                continue;
+            }
+        };
+        // separate the files with blank lines
+        if shown {
+            println!();
+        }
+        shown = true;
+        println!("{}", path.display());
+        let times = [
+            ("modified", metadata.modified()),
+            ("accessed", metadata.accessed()),
+            (CHANGED, changed(&metadata)),
+        ];
+        for (label, time) in times {
+            let time = time.map_or_else(|_| "-".to_string(), |time| timestamp(time, tz));
+            println!("  {:8}  {}", label, time);
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        _ => Err("some files couldn't be read".into()),
+    }
+}
+
+/// What the third timestamp of a file records.
+#[cfg(unix)]
+const CHANGED: &str = "changed";
+/// What the third timestamp of a file records.
+#[cfg(not(unix))]
+const CHANGED: &str = "created";
+
+/// When the metadata of the file was last changed.
+#[cfg(unix)]
+fn changed(metadata: &fs::Metadata) -> io::Result<SystemTime> {
+    use std::os::unix::fs::MetadataExt;
+
+    Ok(unix_time(metadata.ctime(), metadata.ctime_nsec()))
+}
+
+/// The time `secs` seconds after the Unix epoch, or before it if `secs` is
+/// negative, and then `nsec` nanoseconds later, as a timestamp is recorded.
+#[cfg(unix)]
+fn unix_time(secs: i64, nsec: i64) -> SystemTime {
+    use std::time::Duration;
+
+    let whole = Duration::from_secs(secs.unsigned_abs());
+    let time = match secs < 0 {
+        true => SystemTime::UNIX_EPOCH - whole,
+        false => SystemTime::UNIX_EPOCH + whole,
+    };
+    time + Duration::from_nanos(nsec as u64)
+}
+
+/// When the file was created, since not every platform records when its
+/// metadata was last changed.
+#[cfg(not(unix))]
+fn changed(metadata: &fs::Metadata) -> io::Result<SystemTime> {
+    metadata.created()
+}
+
+/// Show `time` as the civil date and the seximal time of day, in the time
+/// zone `tz`. The date stays civil, as there's no seximal calendar.
+fn timestamp(time: SystemTime, tz: SelectedZone) -> String {
+    let time = tz.civil(DateTime::<Utc>::from(time).timestamp_millis());
+    format!("{} {}", time.format("%Y-%m-%d"), MkTime::from(time.time()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::time::Duration;
+
+    use assert2::check;
+
+    #[test]
+    fn file_timestamps() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        check!(timestamp(time, SelectedZone::Utc) == "2023-11-14 53:20:00.0");
+        check!(timestamp(SystemTime::UNIX_EPOCH, SelectedZone::Utc) == "1970-01-01 00:00:00.0");
+        let tokyo = SelectedZone::Named(chrono_tz::Asia::Tokyo);
+        check!(timestamp(time, tokyo) == "2023-11-15 14:50:00.0");
+        check!(run(&["/nonexistent/file"], SelectedZone::Utc).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn unix_times() {
+        let epoch = SystemTime::UNIX_EPOCH;
+        check!(unix_time(1, 500_000_000) == epoch + Duration::from_millis(1_500));
+        // before 1970, the nanoseconds still count forwards from the second
+        check!(unix_time(-2, 500_000_000) == epoch - Duration::from_millis(1_500));
+        check!(unix_time(-1, 0) == epoch - Duration::from_secs(1));
+    }
+}
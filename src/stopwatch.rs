@@ -0,0 +1,118 @@
+//! Stopwatch in seximal units.
+
+use std::{
+    error::Error,
+    io::{self, Write},
+    iter,
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{self, Event, KeyCode},
+    execute, terminal,
+};
+use rn::{formatter::TimeFormatter, systems::misalian_kunimunean_time_formatter};
+
+use crate::{
+    clock::is_quit,
+    watch::{CLEAR_TO_EOL, POLL_INTERVAL},
+};
+
+/// Run the stopwatch until the user quits with `q`, Escape, or Ctrl-C, marking
+/// a lap whenever Enter is pressed. The laps and the total time are printed on
+/// exit.
+pub fn run() -> Result<(), Box<dyn Error>> {
+    let formatter = misalian_kunimunean_time_formatter();
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, Hide)?;
+
+    let result = run_loop(&mut stdout, &formatter);
+
+    // always try to restore the terminal, even if the stopwatch failed
+    execute!(stdout, Show)?;
+    terminal::disable_raw_mode()?;
+    let (laps, total) = result?;
+    writeln!(stdout, "\r{}", CLEAR_TO_EOL)?;
+    for line in summary(&formatter, &laps, total) {
+        writeln!(stdout, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// Redraw the elapsed time whenever it changes, printing a line for each lap.
+/// Returns the times at which laps were marked and the total elapsed time.
+fn run_loop<W: Write>(
+    out: &mut W,
+    formatter: &TimeFormatter,
+) -> Result<(Vec<Duration>, Duration), Box<dyn Error>> {
+    let start = Instant::now();
+    let mut laps = Vec::new();
+    let mut last = String::new();
+    loop {
+        if event::poll(POLL_INTERVAL)? {
+            match event::read()? {
+                Event::Key(key) if is_quit(key) => return Ok((laps, start.elapsed())),
+                Event::Key(key) if key.code == KeyCode::Enter => {
+                    let elapsed = start.elapsed();
+                    let lap = elapsed - laps.last().copied().unwrap_or_default();
+                    laps.push(elapsed);
+                    let line = format!("lap {}  {}", laps.len(), formatter.render_duration(lap));
+                    // raw mode doesn't translate line feeds
+                    write!(out, "\r{}{}\r\n", line, CLEAR_TO_EOL)?;
+                    last.clear();
+                }
+                _ => {}
+            }
+        }
+
+        let current = formatter.render_duration(start.elapsed());
+        if current != last {
+            write!(out, "\r{}{}", current, CLEAR_TO_EOL)?;
+            out.flush()?;
+            last = current;
+        }
+    }
+}
+
+/// Summarize a run of the stopwatch: the time of each lap, ending with the
+/// time since the last mark, followed by the total time. Without any laps,
+/// only the total is shown.
+fn summary(formatter: &TimeFormatter, marks: &[Duration], total: Duration) -> Vec<String> {
+    let mut lines = Vec::with_capacity(marks.len() + 2);
+    if !marks.is_empty() {
+        let starts = iter::once(Duration::ZERO).chain(marks.iter().copied());
+        let ends = marks.iter().copied().chain(iter::once(total));
+        for (i, (start, end)) in starts.zip(ends).enumerate() {
+            let lap = formatter.render_duration(end.saturating_sub(start));
+            lines.push(format!("lap {}  {}", i + 1, lap));
+        }
+    }
+    lines.push(format!("total  {}", formatter.render_duration(total)));
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn lap_summary() {
+        let mkt = misalian_kunimunean_time_formatter();
+        let moments = |n: u64| Duration::from_nanos(1_851_851_852 * n);
+
+        check!(summary(&mkt, &[], moments(3)) == ["total  00:00:03.0"]);
+        check!(
+            summary(&mkt, &[moments(2), moments(8)], moments(45))
+                == [
+                    "lap 1  00:00:02.0",
+                    "lap 2  00:00:10.0",
+                    "lap 3  00:01:01.0",
+                    "total  00:01:13.0",
+                ]
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Generation of systemd timer and service units for seximal times of day.
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{NaiveTime, Timelike};
+
+use rn::{mk::MkTime, systems::mk_snap_time_formatter};
+
+use crate::{parse_seximal_target, SelectedZone};
+
+/// The units that run a command at a seximal time every day.
+#[derive(Debug)]
+pub struct Units {
+    /// The name of the units, without the `.timer` or `.service` suffix.
+    pub name: String,
+    /// The contents of the timer unit.
+    pub timer: String,
+    /// The contents of the service unit.
+    pub service: String,
+}
+
+impl Units {
+    /// Generate units named `name` that run `command` at `time` every day,
+    /// read in the time zone `tz`.
+    pub fn new(name: &str, time: MkTime, command: &[String], tz: SelectedZone) -> Self {
+        let description = format!("Run {} at {}", command.join(" "), time);
+        let timer = format!(
+            "[Unit]\n\
+             Description={}\n\
+             \n\
+             [Timer]\n\
+             OnCalendar={}\n\
+             AccuracySec=1ms\n\
+             \n\
+             [Install]\n\
+             WantedBy=timers.target\n",
+            description,
+            on_calendar(time.into(), tz),
+        );
+        let exec: Vec<_> = command.iter().map(|arg| quote(arg)).collect();
+        let service = format!(
+            "[Unit]\n\
+             Description={}\n\
+             \n\
+             [Service]\n\
+             Type=oneshot\n\
+             ExecStart={}\n",
+            description,
+            exec.join(" "),
+        );
+        Self {
+            name: name.to_string(),
+            timer,
+            service,
+        }
+    }
+
+    /// Write the units into `dir`, returning the paths written.
+    pub fn write(&self, dir: &Path) -> Result<[PathBuf; 2], Box<dyn Error>> {
+        let timer = dir.join(format!("{}.timer", self.name));
+        let service = dir.join(format!("{}.service", self.name));
+        fs::write(&timer, &self.timer)?;
+        fs::write(&service, &self.service)?;
+        Ok([timer, service])
+    }
+}
+
+/// The `OnCalendar=` expression for every day at `time`, to the millisecond.
+/// The time is rounded up, so that it stays within the same snap.
+fn on_calendar(time: NaiveTime, tz: SelectedZone) -> String {
+    // systemd only knows named time zones, so fixed offsets are taken out
+    let time = match tz {
+        SelectedZone::Fixed(offset) => {
+            time - chrono::Duration::seconds(offset.local_minus_utc() as i64)
+        }
+        _ => time,
+    };
+    let millis = (time.nanosecond() as u64).div_ceil(1_000_000);
+    let (seconds, millis) = match millis {
+        1_000 => (time.num_seconds_from_midnight() + 1, 0),
+        _ => (time.num_seconds_from_midnight(), millis),
+    };
+    let zone = match tz {
+        SelectedZone::Utc | SelectedZone::Fixed(_) => " UTC".to_string(),
+        SelectedZone::System => String::new(),
+        SelectedZone::Named(tz) => format!(" {}", tz.name()),
+    };
+    format!(
+        "*-*-* {:02}:{:02}:{:02}.{:03}{}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        millis,
+        zone,
+    )
+}
+
+/// Quote an argument of a systemd command line, escaping specifiers and
+/// variable expansions. Arguments that are empty, have whitespace, or needed
+/// escaping are quoted.
+fn quote(arg: &str) -> String {
+    let escaped = arg
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    match arg.is_empty() || arg.contains(char::is_whitespace) || escaped != arg {
+        true => format!("\"{}\"", escaped),
+        false => escaped,
+    }
+}
+
+/// Print or write the systemd units that run `command` at the seximal time of
+/// day `time`, as for `rn systemd-gen`.
+pub fn run(
+    time: &str,
+    name: Option<&str>,
+    dir: Option<&Path>,
+    command: &[String],
+    tz: SelectedZone,
+) -> Result<(), Box<dyn Error>> {
+    let time = parse_seximal_target(time)?;
+    let name = name.map_or_else(
+        || {
+            format!(
+                "rn-{}",
+                mk_snap_time_formatter().render(time.millis() as u64)
+            )
+        },
+        String::from,
+    );
+    let units = Units::new(&name, time, command, tz);
+    match dir {
+        Some(dir) => {
+            for path in units.write(dir)? {
+                println!("wrote {}", path.display());
+            }
+        }
+        None => print!(
+            "# {name}.timer\n{}\n# {name}.service\n{}",
+            units.timer,
+            units.service,
+            name = name
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn generate_units() {
+        let time: MkTime = "43:00:00.0".parse().unwrap();
+        let command = ["notify-send".to_string(), "time for lunch".to_string()];
+        let units = Units::new("lunch", time, &command, SelectedZone::Utc);
+
+        check!(units
+            .timer
+            .contains("\nOnCalendar=*-*-* 18:00:00.000 UTC\n"));
+        check!(units.timer.contains("\nWantedBy=timers.target\n"));
+        check!(units
+            .service
+            .contains("\nExecStart=notify-send \"time for lunch\"\n"));
+        check!(units
+            .service
+            .starts_with("[Unit]\nDescription=Run notify-send time for lunch at 43:00:00.0\n"));
+
+        let local = Units::new("lunch", time, &command, SelectedZone::System);
+        check!(local.timer.contains("\nOnCalendar=*-*-* 18:00:00.000\n"));
+    }
+
+    #[test]
+    fn calendar_times() {
+        let time = |s: &str| NaiveTime::from(s.parse::<MkTime>().unwrap());
+        check!(on_calendar(time("00:00:00.1"), SelectedZone::Utc) == "*-*-* 00:00:00.309 UTC");
+        check!(on_calendar(time("55:55:55.5"), SelectedZone::System) == "*-*-* 23:59:59.692");
+        let almost = NaiveTime::from_hms_nano_opt(1, 2, 3, 999_999_999).unwrap();
+        check!(on_calendar(almost, SelectedZone::System) == "*-*-* 01:02:04.000");
+        let tokyo = SelectedZone::Named(chrono_tz::Asia::Tokyo);
+        check!(on_calendar(time("43:00:00.0"), tokyo) == "*-*-* 18:00:00.000 Asia/Tokyo");
+        let offset = SelectedZone::Fixed("+09:30".parse().unwrap());
+        check!(on_calendar(time("43:00:00.0"), offset) == "*-*-* 08:30:00.000 UTC");
+    }
+
+    #[test]
+    fn quote_arguments() {
+        check!(quote("lunch") == "lunch");
+        check!(quote("a b") == "\"a b\"");
+        check!(quote("") == "\"\"");
+        check!(quote("say \"hi\"") == "\"say \\\"hi\\\"\"");
+        check!(quote("100%$HOME") == "\"100%%$$HOME\"");
+        check!(quote("C:\\") == "\"C:\\\\\"");
+    }
+}
@@ -0,0 +1,1088 @@
+//! The built-in unit systems and the registry of systems that the time can be
+//! displayed in.
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{error::Error, fmt};
+
+use crate::{
+    formatter::{
+        Color, FormatterError, Hierarchy, Segment, Style, TemplateError, Theme, TimeFormatter,
+        TimeUnit,
+    },
+    mars, sidereal,
+};
+use num::rational::Ratio;
+
+/// Format codes for the Misalian–Kunimunean units.
+pub const MK_FORMAT_CODES: [(char, &str); 5] = [
+    ('L', "lapse"),
+    ('U', "lull"),
+    ('M', "moment"),
+    ('S', "snap"),
+    ('P', "span"),
+];
+
+/// The name of the system used when none is selected.
+pub const DEFAULT_SYSTEM: &str = "extended";
+
+/// Return a time formatter for Misalian–Kunimunean Seximal Units.
+pub fn misalian_kunimunean_time_formatter() -> TimeFormatter<'static> {
+    mk_delimited_time_formatter([":", ":", "."])
+}
+
+/// Return the Misalian–Kunimunean units that make up a day, from the lapse
+/// down to the snap.
+pub fn mk_hierarchy() -> Hierarchy<'static> {
+    Hierarchy::new("lapse", 36)
+        .then("lull", 36)
+        .then("moment", 36)
+        .then("snap", 6)
+}
+
+/// Return a time formatter for Misalian–Kunimunean Seximal Units, with the
+/// passed delimiters after the lapse, lull, and moment.
+pub fn mk_delimited_time_formatter(delimiters: [&str; 3]) -> TimeFormatter<'_> {
+    let units = mk_hierarchy();
+    TimeFormatter::builder()
+        .base((units.total(), 86_400_000))
+        .unit("lapse")
+        .of(&units)
+        .radix(6)
+        .literal(delimiters[0])
+        .unit("lull")
+        .of(&units)
+        .radix(6)
+        .literal(delimiters[1])
+        .unit("moment")
+        .of(&units)
+        .radix(6)
+        .literal(delimiters[2])
+        .unit("snap")
+        .of(&units)
+        .radix(6)
+        .width(0)
+        .build()
+}
+
+/// Return a time formatter for Misalian–Kunimunean Seximal Units preceded by
+/// the number of whole days, for spans longer than a day (`2d 31:44:45.4`).
+pub fn mk_days_time_formatter() -> TimeFormatter<'static> {
+    let units = mk_hierarchy();
+    let day = units.total() as u32;
+    TimeFormatter::builder()
+        .base((units.total(), 86_400_000))
+        .unit("day")
+        .value(day)
+        .unbounded()
+        .width(0)
+        .literal("d ")
+        .unit("lapse")
+        .of(&units)
+        .radix(6)
+        .literal(":")
+        .unit("lull")
+        .of(&units)
+        .radix(6)
+        .literal(":")
+        .unit("moment")
+        .of(&units)
+        .radix(6)
+        .literal(".")
+        .unit("snap")
+        .of(&units)
+        .radix(6)
+        .width(0)
+        .build()
+}
+
+/// Return a time formatter for Misalian–Kunimunean spans.
+pub fn mk_span_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (36 * 36 * 36 * 6, 86_400_000),
+        [Segment::Value((6, "span", 1296, 216, 3).into())],
+    )
+}
+
+/// Return a time formatter for Misalian–Kunimunean snaps.
+pub fn mk_snap_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (36 * 36 * 36 * 6, 86_400_000),
+        [Segment::Value((6, "snap", 1, 36 * 36 * 36 * 6, 7).into())],
+    )
+}
+
+/// Return a time formatter for the niftimal shorthand of the extended form,
+/// where the two senary digits of each lapse, lull, and moment are written as
+/// a single base-36 digit. With `snap`, the snap is appended as a fourth digit.
+pub fn mk_niftimal_time_formatter(snap: bool) -> TimeFormatter<'static> {
+    let units = mk_hierarchy();
+    let builder = TimeFormatter::builder()
+        .base((units.total(), 86_400_000))
+        .unit("lapse")
+        .of(&units)
+        .radix(36)
+        .width(1)
+        .unit("lull")
+        .of(&units)
+        .radix(36)
+        .width(1)
+        .unit("moment")
+        .of(&units)
+        .radix(36)
+        .width(1);
+    if snap {
+        builder.unit("snap").of(&units).radix(6).width(1).build()
+    } else {
+        builder.build()
+    }
+}
+
+/// Return the theme that the Misalian–Kunimunean units are shown in when
+/// colors are enabled: lapses, lulls, moments, and snaps each in their own
+/// color, with dimmed separators.
+pub fn mk_theme() -> Theme<'static> {
+    Theme {
+        units: vec![
+            ("lapse", Style::new().color(Color::Magenta).bold()),
+            ("lull", Style::new().color(Color::Blue)),
+            ("moment", Style::new().color(Color::Cyan)),
+            ("snap", Style::new().color(Color::Green).dim()),
+            ("span", Style::new().color(Color::Yellow)),
+        ],
+        separators: Style::new().dim(),
+    }
+}
+
+/// Return all of the Misalian–Kunimunean units, for use in templates.
+pub fn mk_units() -> Vec<TimeUnit<'static>> {
+    let extended = misalian_kunimunean_time_formatter();
+    let span = mk_span_time_formatter();
+    extended.units().chain(span.units()).cloned().collect()
+}
+
+/// Return a time formatter for standard civil time (`HH:MM:SS.mmm`).
+pub fn civil_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (1, 1),
+        [
+            Segment::Value(("hour", 3_600_000, 24).into()),
+            Segment::Literal(":"),
+            Segment::Value(("minute", 60_000, 60).into()),
+            Segment::Literal(":"),
+            Segment::Value(("second", 1_000, 60).into()),
+            Segment::Literal("."),
+            Segment::Value(("millisecond", 1, 1_000, 3).into()),
+        ],
+    )
+}
+
+/// Return a time formatter for dozenal time: 12 hours of 144 minutes of 144
+/// seconds per day, all in base twelve.
+pub fn dozenal_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (12 * 144 * 144, 86_400_000),
+        [
+            Segment::Value((12, "hour", 144 * 144, 12, 1).into()),
+            Segment::Literal(":"),
+            Segment::Value((12, "minute", 144, 144).into()),
+            Segment::Literal(":"),
+            Segment::Value((12, "second", 1, 144).into()),
+        ],
+    )
+}
+
+/// Return a time formatter for French Revolutionary decimal time: 10 hours of
+/// 100 minutes of 100 seconds per day.
+pub fn decimal_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (100_000, 86_400_000),
+        [
+            Segment::Value(("hour", 10_000, 10, 1).into()),
+            Segment::Literal(":"),
+            Segment::Value(("minute", 100, 100).into()),
+            Segment::Literal(":"),
+            Segment::Value(("second", 1, 100).into()),
+        ],
+    )
+}
+
+/// Return a time formatter for Intuitor-style hexadecimal time: 16 hex hours
+/// of 256 maximes of 16 hex seconds per day, all in base sixteen.
+pub fn hexadecimal_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (16 * 256 * 16, 86_400_000),
+        [
+            Segment::Value((16, "hour", 256 * 16, 16, 1).into()),
+            Segment::Literal("_"),
+            Segment::Value((16, "maxime", 16, 256).into()),
+            Segment::Literal("_"),
+            Segment::Value((16, "second", 1, 16, 1).into()),
+        ],
+    )
+}
+
+/// Return a time formatter for Swatch Internet Time: 1000 beats per day,
+/// written `@XYZ`. Beats are always counted from midnight in UTC+1 (Biel Mean
+/// Time); see [`BIEL_MEAN_TIME`].
+pub fn beats_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (1_000, 86_400_000),
+        [
+            Segment::Literal("@"),
+            Segment::Value(("beat", 1, 1_000, 3).into()),
+        ],
+    )
+}
+
+/// Return a time formatter for the number of seconds since the Unix epoch, in
+/// base six. Only meaningful for times counted from [`Epoch::Unix`].
+pub fn unix_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::builder()
+        .base((1, 1_000))
+        .unit("second")
+        .value(1)
+        .unbounded()
+        .radix(6)
+        .width(0)
+        .build()
+}
+
+/// Return a time formatter for Coordinated Mars Time, shown both in standard
+/// units and in Misalian–Kunimunean units. Both divide a sol (about 24.6229
+/// hours) rather than a day, so the base unit is 1/6,998,400 of a sol: the
+/// smallest unit that both an MTC second and a snap are whole multiples of.
+/// Only meaningful for times counted from [`Epoch::MarsMidnight`].
+pub fn mars_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (6_998_400 * 1_000, mars::MICROS_PER_SOL),
+        [
+            Segment::Value(("hour", 81 * 3_600, 24).into()),
+            Segment::Literal(":"),
+            Segment::Value(("minute", 81 * 60, 60).into()),
+            Segment::Literal(":"),
+            Segment::Value(("second", 81, 60).into()),
+            Segment::Literal(" MTC ("),
+            Segment::Value((6, "lapse", 25 * 7776, 36).into()),
+            Segment::Literal(":"),
+            Segment::Value((6, "lull", 25 * 216, 36).into()),
+            Segment::Literal(":"),
+            Segment::Value((6, "moment", 25 * 6, 36).into()),
+            Segment::Literal("."),
+            Segment::Value((6, "snap", 25, 6, 1).into()),
+            Segment::Literal(")"),
+        ],
+    )
+}
+
+/// Return a time formatter for sidereal time (`HH:MM:SS`), whose day is about
+/// 86,164.0905 seconds long. Only meaningful for times counted from
+/// [`Epoch::SiderealMidnight`].
+pub fn sidereal_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (86_400_000 * 1_000, sidereal::MICROS_PER_SIDEREAL_DAY),
+        [
+            Segment::Value(("hour", 3_600_000, 24).into()),
+            Segment::Literal(":"),
+            Segment::Value(("minute", 60_000, 60).into()),
+            Segment::Literal(":"),
+            Segment::Value(("second", 1_000, 60).into()),
+        ],
+    )
+}
+
+/// Return a time formatter for Hebrew hours and halakim (`HH:PPPP`): 24 hours
+/// of 1080 parts.
+pub fn halakim_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (24 * 1080, 86_400_000),
+        [
+            Segment::Value(("hour", 1080, 24).into()),
+            Segment::Literal(":"),
+            Segment::Value(("part", 1, 1080, 4).into()),
+        ],
+    )
+}
+
+/// The time zone for counting hours and halakim from nightfall, taken to be
+/// 18:00 in the selected time zone.
+pub const HALAKIM_EVENING: Zone = Zone::Shifted(6 * 3_600);
+
+/// The twelve shí (double hours), named for the earthly branches, starting
+/// with 子 at 23:00.
+const SHI: [&str; 12] = [
+    "子时", "丑时", "寅时", "卯时", "辰时", "巳时", "午时", "未时", "申时", "酉时", "戌时", "亥时",
+];
+
+/// The eight kè of each shí: four in its first (初) hour and four in its
+/// second (正) hour.
+const SHI_KE: [&str; 8] = [
+    "初初刻",
+    "初一刻",
+    "初二刻",
+    "初三刻",
+    "正初刻",
+    "正一刻",
+    "正二刻",
+    "正三刻",
+];
+
+/// Return a time formatter for the traditional Chinese shí and kè, with 96 kè
+/// per day, e.g. `午时正二刻`. The day is counted from the start of 子时 at
+/// 23:00; see [`SHICHEN_ZONE`].
+pub fn shichen_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (1, 1),
+        [
+            Segment::Name(("shi", 7_200_000, 12).into(), &SHI),
+            Segment::Name(("ke", 900_000, 8).into(), &SHI_KE),
+        ],
+    )
+}
+
+/// The time zone for counting shí: the selected time zone, shifted so that
+/// the day starts at 23:00.
+pub const SHICHEN_ZONE: Zone = Zone::Shifted(3_600);
+
+/// Return a time formatter for the older division of the day into 100 kè,
+/// counted from midnight, e.g. `52刻`.
+pub fn ke_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (100, 86_400_000),
+        [
+            Segment::Value(("ke", 1, 100).into()),
+            Segment::Literal("刻"),
+        ],
+    )
+}
+
+/// The hours of the Thai six-hour clock, which names each hour by its period
+/// of the day and counts the hours of each period from one.
+const THAI_HOURS: [&str; 24] = [
+    "thiang khuen",
+    "ti 1",
+    "ti 2",
+    "ti 3",
+    "ti 4",
+    "ti 5",
+    "6 mong chao",
+    "1 mong chao",
+    "2 mong chao",
+    "3 mong chao",
+    "4 mong chao",
+    "5 mong chao",
+    "thiang",
+    "bai 1 mong",
+    "bai 2 mong",
+    "bai 3 mong",
+    "4 mong yen",
+    "5 mong yen",
+    "6 mong yen",
+    "1 thum",
+    "2 thum",
+    "3 thum",
+    "4 thum",
+    "5 thum",
+];
+
+/// Return a time formatter for the Thai six-hour clock, e.g.
+/// `bai 2 mong 30 nathi`.
+pub fn thai_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (1, 1),
+        [
+            Segment::Name(("hour", 3_600_000, 24).into(), &THAI_HOURS),
+            Segment::Literal(" "),
+            Segment::Value(("minute", 60_000, 60).into()),
+            Segment::Literal(" nathi"),
+        ],
+    )
+}
+
+/// The watches kept during each four hours of the day. The dog watch is
+/// shown by its halves (see [`DOG_WATCHES`]).
+const WATCHES: [&str; 6] = [
+    "Middle watch",
+    "Morning watch",
+    "Forenoon watch",
+    "Afternoon watch",
+    "Dog watch",
+    "First watch",
+];
+
+/// The halves of the dog watch, which is split so that the crew can eat.
+const DOG_WATCHES: [&str; 2] = ["First dog watch", "Last dog watch"];
+
+/// The number of bells last struck during each half hour of a watch. Eight
+/// bells are struck at the end of each four-hour watch. The bells run on
+/// through the dog watches, so the first dog watch ends at four bells.
+const BELLS: [&str; 8] = [
+    "8 bells", "1 bell", "2 bells", "3 bells", "4 bells", "5 bells", "6 bells", "7 bells",
+];
+
+/// Return a time formatter for the traditional ship's watches and bells, e.g.
+/// `Forenoon watch, 5 bells`.
+pub fn nautical_time_formatter() -> TimeFormatter<'static> {
+    let watch = || ("watch", 14_400_000, 6).into();
+    TimeFormatter::new(
+        (1, 1),
+        [
+            Segment::Conditional(
+                watch(),
+                |watch| watch != 4,
+                vec![Segment::Name(watch(), &WATCHES)],
+            ),
+            Segment::Conditional(
+                watch(),
+                |watch| watch == 4,
+                vec![Segment::Name(
+                    ("dog watch", 7_200_000, 2).into(),
+                    &DOG_WATCHES,
+                )],
+            ),
+            Segment::Literal(", "),
+            Segment::Name(("bell", 1_800_000, 8).into(), &BELLS),
+        ],
+    )
+}
+
+/// Return a time formatter for stardates to two decimal places, e.g.
+/// `41153.70`. Only meaningful for times counted from [`Epoch::Stardate`],
+/// which counts in hundredths of a stardate rather than in milliseconds.
+pub fn stardate_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::new(
+        (1, 1),
+        [
+            Segment::Value(TimeUnit::unbounded("stardate", 100, 0)),
+            Segment::Literal("."),
+            Segment::Value(("hundredth", 1, 100).into()),
+        ],
+    )
+}
+
+/// Return a time formatter for the Julian Date in base six, with six senary
+/// places of the fractional day. Only meaningful for times counted from
+/// [`Epoch::JulianDate`].
+pub fn julian_date_time_formatter() -> TimeFormatter<'static> {
+    TimeFormatter::builder()
+        .base((46_656, 86_400_000))
+        .unit("day")
+        .value(46_656)
+        .unbounded()
+        .radix(6)
+        .width(0)
+        .literal(".")
+        .unit("fraction")
+        .value(1)
+        .limit(46_656)
+        .radix(6)
+        .width(6)
+        .build()
+}
+
+/// The number of milliseconds from the start of the Julian Period to the Unix
+/// epoch, which is at Julian Date 2,440,587.5.
+pub const JULIAN_DATE_OF_UNIX_EPOCH: i64 = 210_866_760_000_000;
+
+/// The time zone that Swatch Internet Time is computed in.
+pub const BIEL_MEAN_TIME: Zone = Zone::Fixed(3_600);
+
+/// The time zone that a system's time is computed in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    /// The time zone selected by the user: UTC, or the system time zone with
+    /// `--local`.
+    #[default]
+    Selected,
+    /// A fixed offset from UTC in seconds, regardless of the user's selection.
+    Fixed(i32),
+    /// The time zone selected by the user, shifted by an offset in seconds, for
+    /// systems whose day starts at a fixed time other than midnight.
+    Shifted(i32),
+}
+
+/// The instant that a system counts time from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Epoch {
+    /// The most recent midnight in the system's time zone.
+    #[default]
+    Midnight,
+    /// The Unix epoch, 1970-01-01T00:00:00Z.
+    Unix,
+    /// The most recent midnight at the Martian prime meridian, so that time
+    /// is counted in milliseconds of Coordinated Mars Time.
+    MarsMidnight,
+    /// The most recent local sidereal midnight, when the vernal equinox
+    /// crosses the observer's meridian.
+    SiderealMidnight,
+    /// Stardate 0, at the start of 2323. Time is counted in hundredths of a
+    /// stardate rather than in milliseconds.
+    Stardate,
+    /// The start of the Julian Period, noon UTC on 1 January 4713 BC in the
+    /// proleptic Julian calendar.
+    JulianDate,
+}
+
+impl Epoch {
+    /// The length in milliseconds of the day that a system counting from this
+    /// epoch displays: a sol for Mars, a sidereal day for sidereal time, and
+    /// otherwise 24 hours.
+    pub fn day_millis(&self) -> Ratio<u64> {
+        match self {
+            Self::MarsMidnight => Ratio::new(mars::MICROS_PER_SOL, 1_000),
+            Self::SiderealMidnight => Ratio::new(sidereal::MICROS_PER_SIDEREAL_DAY, 1_000),
+            _ => Ratio::from_integer(86_400_000),
+        }
+    }
+}
+
+/// A named unit system.
+#[derive(Debug, Clone)]
+pub struct System<'s> {
+    /// The name used to select this system.
+    pub name: &'s str,
+    /// A short description of this system.
+    pub description: &'s str,
+    /// The formatter that displays the time in this system.
+    pub formatter: TimeFormatter<'s>,
+    /// Units that templates may name in addition to the formatter's own.
+    pub template_units: Vec<TimeUnit<'s>>,
+    /// Format codes that templates may use as shorthand for units.
+    pub codes: &'s [(char, &'s str)],
+    /// The time zone that this system's time is computed in.
+    pub zone: Zone,
+    /// The instant that this system counts time from.
+    pub epoch: Epoch,
+}
+
+impl<'s> System<'s> {
+    /// Construct a new system with the passed name, description, and
+    /// formatter. Templates may only name the formatter's units.
+    pub fn new(name: &'s str, description: &'s str, formatter: TimeFormatter<'s>) -> Self {
+        Self {
+            name,
+            description,
+            formatter,
+            template_units: Vec::new(),
+            codes: &[],
+            zone: Zone::Selected,
+            epoch: Epoch::Midnight,
+        }
+    }
+
+    /// Always compute this system's time in `zone`.
+    pub fn with_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// Allow templates to name `units` and use `codes` as well.
+    pub fn with_template_units(
+        mut self,
+        units: Vec<TimeUnit<'s>>,
+        codes: &'s [(char, &'s str)],
+    ) -> Self {
+        self.template_units = units;
+        self.codes = codes;
+        self
+    }
+
+    /// Count this system's time from `epoch` rather than from midnight.
+    pub fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// Check that this system's formatter makes sense for the day counted from
+    /// its epoch (see [`TimeFormatter::validate`]).
+    pub fn validate(&self) -> Result<(), FormatterError> {
+        self.formatter.validate_day(self.epoch.day_millis())
+    }
+
+    /// Construct a formatter for this system from a template. Placeholders
+    /// may name the formatter's units or any of the template units.
+    pub fn template(&self, template: &'s str) -> Result<TimeFormatter<'s>, TemplateError> {
+        let units: Vec<_> = self
+            .formatter
+            .units()
+            .chain(&self.template_units)
+            .cloned()
+            .collect();
+        TimeFormatter::from_template(self.formatter.base(), template, &units, self.codes)
+    }
+}
+
+/// Error returned when selecting a system that isn't registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownSystem(pub String);
+
+impl fmt::Display for UnknownSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown system `{}` (use `--list-systems` to see the available systems)",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownSystem {}
+
+/// A collection of named unit systems.
+#[derive(Debug, Clone, Default)]
+pub struct Registry<'s> {
+    systems: Vec<System<'s>>,
+}
+
+impl<'s> Registry<'s> {
+    /// Construct a registry containing the built-in systems.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        for (name, description, formatter) in [
+            (
+                "extended",
+                "Misalian–Kunimunean extended snapshot form (lp:ll:mt.sn)",
+                misalian_kunimunean_time_formatter(),
+            ),
+            (
+                "basic",
+                "Misalian–Kunimunean snapshot form: snaps since midnight",
+                mk_snap_time_formatter(),
+            ),
+            (
+                "span",
+                "Misalian–Kunimunean span form: spans since midnight",
+                mk_span_time_formatter(),
+            ),
+            (
+                "niftimal",
+                "Misalian–Kunimunean niftimal shorthand: base-36 lapse, lull, and moment, then the snap",
+                mk_niftimal_time_formatter(true),
+            ),
+            (
+                "niftimal-short",
+                "Misalian–Kunimunean niftimal shorthand, without the snap",
+                mk_niftimal_time_formatter(false),
+            ),
+        ] {
+            registry.register(
+                System::new(name, description, formatter)
+                    .with_template_units(mk_units(), &MK_FORMAT_CODES),
+            );
+        }
+        registry.register(System::new(
+            "standard",
+            "Standard civil time (HH:MM:SS.mmm)",
+            civil_time_formatter(),
+        ));
+        registry.register(System::new(
+            "dozenal",
+            "Dozenal time: 12 hours of 144 minutes of 144 seconds, in base twelve",
+            dozenal_time_formatter(),
+        ));
+        registry.register(System::new(
+            "decimal",
+            "French Revolutionary decimal time: 10 hours of 100 minutes of 100 seconds",
+            decimal_time_formatter(),
+        ));
+        registry.register(
+            System::new(
+                "beats",
+                "Swatch Internet Time: 1000 beats per day, in UTC+1 (@XYZ)",
+                beats_time_formatter(),
+            )
+            .with_zone(BIEL_MEAN_TIME),
+        );
+        registry.register(
+            System::new(
+                "unix",
+                "Seconds since the Unix epoch, in base six",
+                unix_time_formatter(),
+            )
+            .with_epoch(Epoch::Unix),
+        );
+        registry.register(
+            System::new(
+                "mars",
+                "Coordinated Mars Time, in standard and seximal units of the sol",
+                mars_time_formatter(),
+            )
+            .with_epoch(Epoch::MarsMidnight),
+        );
+        registry.register(
+            System::new(
+                "sidereal",
+                "Local sidereal time (Greenwich unless given a `--longitude`)",
+                sidereal_time_formatter(),
+            )
+            .with_epoch(Epoch::SiderealMidnight),
+        );
+        registry.register(System::new(
+            "halakim",
+            "Hebrew hours and halakim (1080 parts per hour) since midnight (HH:PPPP)",
+            halakim_time_formatter(),
+        ));
+        registry.register(
+            System::new(
+                "halakim-evening",
+                "Hebrew hours and halakim since nightfall, taken to be 18:00 (HH:PPPP)",
+                halakim_time_formatter(),
+            )
+            .with_zone(HALAKIM_EVENING),
+        );
+        registry.register(
+            System::new(
+                "shichen",
+                "Traditional Chinese shí and kè, with 96 kè per day (午时正二刻)",
+                shichen_time_formatter(),
+            )
+            .with_zone(SHICHEN_ZONE),
+        );
+        registry.register(System::new(
+            "ke",
+            "Traditional Chinese kè, with 100 kè per day (52刻)",
+            ke_time_formatter(),
+        ));
+        registry.register(System::new(
+            "thai",
+            "Thai six-hour clock (bai 2 mong 30 nathi)",
+            thai_time_formatter(),
+        ));
+        registry.register(System::new(
+            "nautical",
+            "Ship's watches and bells (Forenoon watch, 5 bells)",
+            nautical_time_formatter(),
+        ));
+        registry.register(
+            System::new(
+                "stardate",
+                "Stardates in the style of The Next Generation (41153.70)",
+                stardate_time_formatter(),
+            )
+            .with_epoch(Epoch::Stardate),
+        );
+        registry.register(
+            System::new(
+                "julian",
+                "Julian Date with the fractional day, in base six",
+                julian_date_time_formatter(),
+            )
+            .with_epoch(Epoch::JulianDate),
+        );
+        registry.register(System::new(
+            "hex",
+            "Hexadecimal time: 16 hex hours of 256 maximes of 16 hex seconds",
+            hexadecimal_time_formatter(),
+        ));
+        registry
+    }
+
+    /// Add a system to the registry, replacing any system with the same name.
+    pub fn register(&mut self, system: System<'s>) {
+        match self.systems.iter_mut().find(|s| s.name == system.name) {
+            Some(existing) => *existing = system,
+            None => self.systems.push(system),
+        }
+    }
+
+    /// Get the system with the passed name.
+    pub fn get(&self, name: &str) -> Result<&System<'s>, UnknownSystem> {
+        self.systems
+            .iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| UnknownSystem(name.to_string()))
+    }
+
+    /// The registered systems, in the order they were registered.
+    pub fn iter(&self) -> impl Iterator<Item = &System<'s>> {
+        self.systems.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn senary_formatter() {
+        let mkt = misalian_kunimunean_time_formatter();
+
+        check!(mkt.render(0) == "00:00:00.0");
+        check!(mkt.render(47521888) == "31:44:45.4");
+        check!(mkt.render(81218884) == "53:50:14.1");
+        check!(mkt.render(81246133) == "53:50:40.5");
+        check!(mkt.render(86_399_999) == "55:55:55.5");
+    }
+
+    #[test]
+    fn sub_millisecond_formatter() {
+        let mkt = misalian_kunimunean_time_formatter();
+
+        // the first snap ends 308,641,975.3 ns after midnight
+        check!(mkt.render(308) == "00:00:00.0");
+        check!(mkt.render_nanos(308_641_975) == "00:00:00.0");
+        check!(mkt.render_nanos(308_641_976) == "00:00:00.1");
+        check!(mkt.parse_nanos("00:00:00.1") == Ok(308_641_976));
+        check!(mkt.parse("00:00:00.1") == Ok(309));
+    }
+
+    #[test]
+    fn multi_day_formatter() {
+        let mkt = misalian_kunimunean_time_formatter();
+        let basic = mk_snap_time_formatter();
+        // 10^11 days, too many snaps to compute in a u64 without reducing
+        let days = 86_400_000 * 100_000_000_000;
+
+        check!(mkt.render(86_400_000) == "00:00:00.0");
+        check!(mkt.render(130967197) == "30:32:30.1");
+        check!(basic.render(130967197) == "3032301");
+        check!(mkt.render(days) == "00:00:00.0");
+        check!(mkt.render(days + 47521888) == "31:44:45.4");
+        check!(mkt.render(u64::MAX) == mkt.render(u64::MAX % 86_400_000));
+
+        let days = mk_days_time_formatter();
+        check!(days.render(47521888) == "0d 31:44:45.4");
+        check!(days.render(2 * 86_400_000 + 47521888) == "2d 31:44:45.4");
+        check!(days.render(130967197) == "1d 30:32:30.1");
+        check!(
+            days.parse("2d 31:44:45.4") == Ok(2 * 86_400_000 + mkt.parse("31:44:45.4").unwrap())
+        );
+        check!(days.validate() == Ok(()));
+    }
+
+    #[test]
+    fn themed_formatter() {
+        let mut mkt = misalian_kunimunean_time_formatter();
+        mkt.apply_theme(&mk_theme());
+
+        check!(mkt.render(47521888) == "31:44:45.4");
+        check!(
+            mkt.render_styled(47521888)
+                == "\x1b[1;35m31\x1b[0m\x1b[2m:\x1b[0m\x1b[34m44\x1b[0m\x1b[2m:\x1b[0m\
+                    \x1b[36m45\x1b[0m\x1b[2m.\x1b[0m\x1b[2;32m4\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn template_formatter() {
+        let registry = Registry::builtin();
+        let extended = registry.get("extended").unwrap();
+
+        let mkt = extended
+            .template("{lapse}l {lull}:{moment}.{snap} ({span})")
+            .unwrap();
+        check!(mkt.render(0) == "00l 00:00.0 (000)");
+        check!(mkt.render(47521888) == "31l 44:45.4 (314)");
+        check!(extended.template("{second}").is_err());
+
+        let codes = extended.template("%L:%U:%M.%S %P %%").unwrap();
+        check!(codes.render(47521888) == "31:44:45.4 314 %");
+
+        let basic = registry.get("basic").unwrap().template("%S").unwrap();
+        check!(basic.render(47521888) == "3144454");
+    }
+
+    #[test]
+    fn basic_formatter() {
+        let basic = mk_snap_time_formatter();
+
+        check!(basic.render(0) == "0000000");
+        check!(basic.render(47521888) == "3144454");
+        check!(basic.render(81218884) == "5350141");
+        check!(basic.render(81246133) == "5350405");
+    }
+
+    #[test]
+    fn niftimal_formatter() {
+        let niftimal = mk_niftimal_time_formatter(true);
+        let short = mk_niftimal_time_formatter(false);
+
+        check!(niftimal.render(0) == "0000");
+        check!(niftimal.render(47521888) == "jst4");
+        check!(niftimal.render(86_399_999) == "zzz5");
+        check!(short.render(47521888) == "jst");
+    }
+
+    #[test]
+    fn dozenal_formatter() {
+        let dozenal = dozenal_time_formatter();
+
+        check!(dozenal.render(0) == "0:00:00");
+        check!(dozenal.render(43_200_000) == "6:00:00");
+        check!(dozenal.render(47_521_888) == "6:72:53");
+        check!(dozenal.render(86_399_999) == "b:bb:bb");
+    }
+
+    #[test]
+    fn decimal_formatter() {
+        let decimal = decimal_time_formatter();
+
+        check!(decimal.render(0) == "0:00:00");
+        check!(decimal.render(43_200_000) == "5:00:00");
+        check!(decimal.render(47_521_888) == "5:50:02");
+        check!(decimal.render(86_399_999) == "9:99:99");
+    }
+
+    #[test]
+    fn hexadecimal_formatter() {
+        let hex = hexadecimal_time_formatter();
+
+        check!(hex.render(0) == "0_00_0");
+        check!(hex.render(43_200_000) == "8_00_0");
+        check!(hex.render(47_521_888) == "8_cc_e");
+        check!(hex.render(86_399_999) == "f_ff_f");
+    }
+
+    #[test]
+    fn beats_formatter() {
+        let beats = beats_time_formatter();
+
+        check!(beats.render(0) == "@000");
+        check!(beats.render(43_200_000) == "@500");
+        check!(beats.render(47_521_888) == "@550");
+        check!(beats.render(86_399_999) == "@999");
+    }
+
+    #[test]
+    fn unix_formatter() {
+        let unix = unix_time_formatter();
+
+        check!(unix.render(0) == "0");
+        check!(unix.render(86_400_000) == "1504000");
+        check!(unix.render(1_700_000_000_999) == "440404522212");
+        // 2200-01-01T00:00:00Z, past where a 32-bit count of seconds wraps
+        check!(unix.render(7_258_118_400_000) == "3200114400000");
+    }
+
+    #[test]
+    fn mars_formatter() {
+        let mars = mars_time_formatter();
+
+        check!(mars.render(0) == "00:00:00 MTC (00:00:00.0)");
+        check!(mars.render(44_387_622) == "11:59:59 MTC (25:55:55.5)");
+        check!(mars.render(88_758_968) == "23:59:44 MTC (55:55:43.2)");
+        check!(mars.render(88_775_244) == "23:59:59 MTC (55:55:55.5)");
+    }
+
+    #[test]
+    fn sidereal_formatter() {
+        let sidereal = sidereal_time_formatter();
+
+        check!(sidereal.render(0) == "00:00:00");
+        check!(sidereal.render(67_126_761) == "18:41:50");
+        check!(sidereal.render(23_970_995) == "06:40:36");
+        check!(sidereal.render(86_164_090) == "23:59:59");
+    }
+
+    #[test]
+    fn halakim_formatter() {
+        let halakim = halakim_time_formatter();
+
+        check!(halakim.render(0) == "00:0000");
+        check!(halakim.render(47_521_888) == "13:0216");
+        check!(halakim.render(86_399_999) == "23:1079");
+    }
+
+    #[test]
+    fn shichen_formatter() {
+        let shichen = shichen_time_formatter();
+
+        // times here are already shifted to start at 23:00
+        check!(shichen.render(0) == "子时初初刻");
+        check!(shichen.render(49_200_000) == "午时正二刻");
+        check!(shichen.render(86_399_999) == "亥时正三刻");
+
+        let ke = ke_time_formatter();
+        check!(ke.render(0) == "00刻");
+        check!(ke.render(45_600_000) == "52刻");
+        check!(ke.render(86_399_999) == "99刻");
+    }
+
+    #[test]
+    fn thai_formatter() {
+        let thai = thai_time_formatter();
+
+        check!(thai.render(0) == "thiang khuen 00 nathi");
+        check!(thai.render(3_660_000) == "ti 1 01 nathi");
+        check!(thai.render(25_200_000) == "1 mong chao 00 nathi");
+        check!(thai.render(43_200_000) == "thiang 00 nathi");
+        check!(thai.render(52_200_000) == "bai 2 mong 30 nathi");
+        check!(thai.render(61_200_000) == "5 mong yen 00 nathi");
+        check!(thai.render(86_399_999) == "5 thum 59 nathi");
+    }
+
+    #[test]
+    fn nautical_formatter() {
+        let nautical = nautical_time_formatter();
+
+        check!(nautical.render(0) == "Middle watch, 8 bells");
+        check!(nautical.render(1_800_000) == "Middle watch, 1 bell");
+        check!(nautical.render(37_800_000) == "Forenoon watch, 5 bells");
+        check!(nautical.render(64_800_000) == "Last dog watch, 4 bells");
+        check!(nautical.render(71_999_999) == "Last dog watch, 7 bells");
+        check!(nautical.render(86_399_999) == "First watch, 7 bells");
+    }
+
+    #[test]
+    fn stardate_formatter() {
+        let stardate = stardate_time_formatter();
+
+        check!(stardate.render(0) == "0.00");
+        check!(stardate.render(4_115_370) == "41153.70");
+    }
+
+    #[test]
+    fn julian_date_formatter() {
+        let julian = julian_date_time_formatter();
+
+        check!(julian.render(JULIAN_DATE_OF_UNIX_EPOCH as u64) == "124151003.300000");
+        // 2000-01-01T12:00:00Z, Julian Date 2,451,545.0
+        check!(julian.render(211_813_488_000_000) == "124313425.000000");
+        check!(julian.render(212_566_760_000_000) == "124422035.232000");
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let registry = Registry::builtin();
+        for name in [
+            "extended", "basic", "span", "niftimal", "dozenal", "hex", "thai",
+        ] {
+            let formatter = &registry.get(name).unwrap().formatter;
+            for ms in [0, 47_521_888, 86_399_999] {
+                let rendered = formatter.render(ms);
+                let parsed = formatter.parse(&rendered).unwrap();
+                check!(formatter.render(parsed) == rendered, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn validate_builtin() {
+        for system in Registry::builtin().iter() {
+            check!(system.validate() == Ok(()), "{}", system.name);
+        }
+    }
+
+    #[test]
+    fn registry() {
+        let mut registry = Registry::builtin();
+        check!(registry.get("extended").is_ok());
+        check!(registry.get("basic").is_ok());
+        check!(registry.get("span").is_ok());
+        check!(registry.get("extended").unwrap().zone == Zone::Selected);
+        check!(registry.get("beats").unwrap().zone == Zone::Fixed(3_600));
+        check!(registry.get("extended").unwrap().epoch == Epoch::Midnight);
+        check!(registry.get("unix").unwrap().epoch == Epoch::Unix);
+        check!(registry.get("nope").unwrap_err() == UnknownSystem("nope".into()));
+
+        let count = registry.iter().count();
+        registry.register(System::new("span", "", civil_time_formatter()));
+        check!(registry.iter().count() == count);
+        check!(registry.get("span").unwrap().formatter.render(0) == "00:00:00.000");
+    }
+}
@@ -0,0 +1,102 @@
+//! Countdown timer in seximal units.
+
+use std::{
+    error::Error,
+    io::{self, Write},
+    sync::atomic::Ordering,
+    thread,
+    time::{Duration, Instant},
+};
+
+use rn::{
+    formatter::Rounding,
+    mk::{MILLIS_PER_DAY, SNAPS_PER_DAY},
+    systems::misalian_kunimunean_time_formatter,
+};
+
+use crate::{
+    sleep::real_duration,
+    wait::interrupt_flag,
+    watch::{CLEAR_TO_EOL, HIDE_CURSOR, MAX_SLEEP, SHOW_CURSOR},
+};
+
+/// The number of nanoseconds in a day.
+const NANOS_PER_DAY: u128 = MILLIS_PER_DAY as u128 * 1_000_000;
+
+/// Count down from the seximal `duration`, read as for `rn sleep`, showing the
+/// time left in extended form on a single line. Rings the terminal bell at the
+/// end if `bell` is set. Fails if interrupted with Ctrl-C before the time is
+/// up.
+pub fn run(duration: &[String], bell: bool) -> Result<(), Box<dyn Error>> {
+    let duration = real_duration(duration)?;
+    let running = interrupt_flag()?;
+    let deadline = Instant::now() + duration;
+    // the time left is rounded up, so that zero is shown only once time is up
+    let mut formatter = misalian_kunimunean_time_formatter();
+    formatter.set_rounding(Rounding::Ceil);
+
+    let mut stdout = io::stdout();
+    write!(stdout, "{}", HIDE_CURSOR)?;
+
+    let mut last = String::new();
+    let finished = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let current = formatter.render_duration(remaining);
+        if current != last {
+            write!(stdout, "\r{}{}", current, CLEAR_TO_EOL)?;
+            stdout.flush()?;
+            last = current;
+        }
+        if remaining.is_zero() {
+            break true;
+        }
+        if !running.load(Ordering::SeqCst) {
+            break false;
+        }
+        thread::sleep(until_snap_boundary(remaining).min(MAX_SLEEP));
+    };
+
+    write!(stdout, "{}", SHOW_CURSOR)?;
+    if finished && bell {
+        write!(stdout, "\x07")?;
+    }
+    writeln!(stdout)?;
+    match finished {
+        true => Ok(()),
+        false => Err("the timer was interrupted".into()),
+    }
+}
+
+/// Get how long it is until `remaining` is next a whole number of snaps,
+/// which is when the displayed time changes. Never zero, so that the time is
+/// always past the boundary on waking.
+fn until_snap_boundary(remaining: Duration) -> Duration {
+    let nanos = remaining.as_nanos();
+    let mut snaps = nanos * SNAPS_PER_DAY as u128 / NANOS_PER_DAY;
+    // the first nanosecond at which at most `snaps` snaps are left
+    let mut boundary = (snaps * NANOS_PER_DAY).div_ceil(SNAPS_PER_DAY as u128);
+    if boundary >= nanos && snaps > 0 {
+        snaps -= 1;
+        boundary = (snaps * NANOS_PER_DAY).div_ceil(SNAPS_PER_DAY as u128);
+    }
+    Duration::from_nanos((nanos - boundary.min(nanos)).max(1) as u64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn sleep_to_snap_boundaries() {
+        // a snap is 308_641_975.3 nanoseconds
+        let snap = Duration::from_nanos(308_641_976);
+        let two_snaps = Duration::from_nanos(617_283_951);
+        let later = two_snaps + Duration::from_millis(5);
+        check!(until_snap_boundary(later) == Duration::from_millis(5));
+        check!(until_snap_boundary(snap) == snap);
+        check!(until_snap_boundary(Duration::from_millis(100)) == Duration::from_millis(100));
+        check!(until_snap_boundary(Duration::ZERO) == Duration::from_nanos(1));
+    }
+}
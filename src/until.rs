@@ -0,0 +1,74 @@
+//! The time remaining until a time of day.
+
+use std::error::Error;
+
+use rn::{mk, systems::misalian_kunimunean_time_formatter};
+
+use crate::{parse_millis_since_midnight, time_of_day, time_since_midnight, SelectedZone};
+
+/// Get the number of milliseconds from `now` until the next time it is
+/// `target`, both in milliseconds since midnight. A target earlier than `now`
+/// is taken to be tomorrow.
+pub fn millis_until(now: u32, target: u32) -> u32 {
+    (target as i64 - now as i64).rem_euclid(mk::MILLIS_PER_DAY as i64) as u32
+}
+
+/// Get the first instant, in milliseconds since the Unix epoch, at or after
+/// `after` at which it is `target` milliseconds after midnight on the clocks
+/// of the time zone `tz`. A change of offset in between, as for daylight
+/// saving time, is allowed for, so that the time of day stays the same.
+pub fn next_occurrence(after: i64, target: u32, tz: SelectedZone) -> i64 {
+    let next = after + millis_until(time_of_day(after, tz) as u32, target) as i64;
+    let shifted = next + tz.offset_millis(after) - tz.offset_millis(next);
+    match shifted >= after {
+        true => shifted,
+        false => next,
+    }
+}
+
+/// Print the time remaining until the passed time of day, as for `rn until`.
+pub fn run(target: &str, tz: SelectedZone) -> Result<(), Box<dyn Error>> {
+    let target = parse_millis_since_midnight(target)?;
+    let now = time_since_midnight(tz)?.as_millis() as u32;
+    let remaining = millis_until(now, target);
+    println!(
+        "{}",
+        misalian_kunimunean_time_formatter().render(remaining as u64)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn time_until_target() {
+        let mkt = misalian_kunimunean_time_formatter();
+        let target = parse_millis_since_midnight("16:30").unwrap();
+        check!(millis_until(0, target) == target);
+        check!(millis_until(target, target) == 0);
+        check!(millis_until(target + 1, target) == mk::MILLIS_PER_DAY - 1);
+        let now = parse_millis_since_midnight("12:00").unwrap();
+        check!(mkt.render(millis_until(now, target) as u64) == "10:43:00.0");
+        let now = parse_millis_since_midnight("20:00").unwrap();
+        check!(mkt.render(millis_until(now, target) as u64) == "50:43:00.0");
+    }
+
+    #[test]
+    fn next_occurrences() {
+        check!(next_occurrence(0, 1000, SelectedZone::Utc) == 1000);
+        check!(next_occurrence(1000, 1000, SelectedZone::Utc) == 1000);
+        check!(next_occurrence(1001, 1000, SelectedZone::Utc) == mk::MILLIS_PER_DAY as i64 + 1000);
+
+        // 09:00 every day in New York, across both changes of offset
+        let new_york = SelectedZone::Named(chrono_tz::America::New_York);
+        let nine = 9 * 3_600_000;
+        // 2023-11-04 09:00 EDT, and the next day at 09:00 EST
+        check!(next_occurrence(1_699_102_800_001, nine, new_york) == 1_699_192_800_000);
+        // 2023-03-11 09:00 EST, and the next day at 09:00 EDT
+        check!(next_occurrence(1_678_543_200_001, nine, new_york) == 1_678_626_000_000);
+    }
+}
@@ -0,0 +1,115 @@
+//! Waiting for a time of day, and running commands when it arrives.
+
+use std::{
+    error::Error,
+    ffi::OsString,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::watch::MAX_SLEEP;
+
+/// Install a Ctrl-C handler, returning a flag that is cleared when the user
+/// interrupts the program.
+pub fn interrupt_flag() -> Result<Arc<AtomicBool>, Box<dyn Error>> {
+    let running = Arc::new(AtomicBool::new(true));
+    {
+        let running = Arc::clone(&running);
+        ctrlc::set_handler(move || running.store(false, Ordering::SeqCst))?;
+    }
+    Ok(running)
+}
+
+/// Sleep until the system clock reaches `deadline`. Returns `false` if
+/// interrupted first, when `running` is cleared.
+pub fn sleep_until(deadline: DateTime<Utc>, running: &AtomicBool) -> bool {
+    while running.load(Ordering::SeqCst) {
+        let remaining = match (deadline - Utc::now()).to_std() {
+            Ok(remaining) if !remaining.is_zero() => remaining,
+            _ => return true,
+        };
+        thread::sleep(remaining.min(MAX_SLEEP));
+    }
+    false
+}
+
+/// Wait `millis` milliseconds, then replace this process with `command`. On
+/// platforms without `exec`, the command is run as a child instead, and its
+/// exit code passed on.
+pub fn run_at(millis: u32, command: &[OsString]) -> Result<(), Box<dyn Error>> {
+    let (program, args) = command.split_first().ok_or("no command to run")?;
+    let deadline = Utc::now() + chrono::Duration::milliseconds(millis as i64);
+    let running = interrupt_flag()?;
+    if !sleep_until(deadline, &running) {
+        return Err("interrupted before the command was run".into());
+    }
+
+    let mut command = process::Command::new(program);
+    command.args(args);
+    exec(command)
+}
+
+/// Wait until the instant `next` gives for now, and then call `fire`. With
+/// `daily`, `fire` is called again at each instant `next` gives after the
+/// last, until interrupted, so that the time of day of the alarm is kept as
+/// the clocks change. Interrupting before the first call is an error.
+pub fn alarm<N, F>(mut next: N, daily: bool, mut fire: F) -> Result<(), Box<dyn Error>>
+where
+    N: FnMut(DateTime<Utc>) -> DateTime<Utc>,
+    F: FnMut() -> Result<(), Box<dyn Error>>,
+{
+    let running = interrupt_flag()?;
+    let mut deadline = next(Utc::now());
+    let mut fired = false;
+    loop {
+        if !sleep_until(deadline, &running) {
+            return match fired {
+                true => Ok(()),
+                false => Err("interrupted before the alarm went off".into()),
+            };
+        }
+        fire()?;
+        fired = true;
+        if !daily {
+            return Ok(());
+        }
+        deadline = next(deadline + chrono::Duration::milliseconds(1));
+    }
+}
+
+/// Show a desktop notification with `summary` and `body`.
+#[cfg(feature = "notify")]
+pub fn notify(summary: &str, body: &str) -> Result<(), Box<dyn Error>> {
+    notify_rust::Notification::new()
+        .summary(summary)
+        .body(body)
+        .show()?;
+    Ok(())
+}
+
+/// Desktop notifications aren't shown without the `notify` feature.
+#[cfg(not(feature = "notify"))]
+pub fn notify(_summary: &str, _body: &str) -> Result<(), Box<dyn Error>> {
+    Ok(())
+}
+
+/// Replace this process with `command`, only returning if it can't be run.
+#[cfg(unix)]
+fn exec(mut command: process::Command) -> Result<(), Box<dyn Error>> {
+    use std::os::unix::process::CommandExt;
+
+    Err(command.exec().into())
+}
+
+/// Run `command` and exit with its exit code.
+#[cfg(not(unix))]
+fn exec(mut command: process::Command) -> Result<(), Box<dyn Error>> {
+    let status = command.status()?;
+    process::exit(status.code().unwrap_or(1))
+}
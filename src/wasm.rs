@@ -0,0 +1,61 @@
+//! Bindings for JavaScript, so that WebAssembly builds can show the same
+//! times as `rn`.
+//!
+//! ```js
+//! import { render, parse, Formatter } from "rn";
+//!
+//! render(47521888); // "31:44:45.4"
+//! Formatter.preset("span").render(47521888); // "314"
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    formatter::TimeFormatter,
+    systems::{misalian_kunimunean_time_formatter, Registry},
+};
+
+/// Render the time `ms` milliseconds after midnight in extended form. Times
+/// before midnight are shown with a leading `-`.
+#[wasm_bindgen]
+pub fn render(ms: f64) -> String {
+    misalian_kunimunean_time_formatter().render_signed(ms as i64)
+}
+
+/// Parse a time in extended form into milliseconds after midnight.
+#[wasm_bindgen]
+pub fn parse(s: &str) -> Result<f64, JsError> {
+    Ok(misalian_kunimunean_time_formatter().parse(s)? as f64)
+}
+
+/// The names of the built-in systems, for [`Formatter::preset`].
+#[wasm_bindgen]
+pub fn presets() -> Vec<String> {
+    Registry::builtin()
+        .iter()
+        .map(|system| system.name.to_string())
+        .collect()
+}
+
+/// A formatter for one of the built-in systems.
+#[wasm_bindgen]
+pub struct Formatter(TimeFormatter<'static>);
+
+#[wasm_bindgen]
+impl Formatter {
+    /// Look up the built-in system `name`, as listed by [`presets`].
+    pub fn preset(name: &str) -> Result<Formatter, JsError> {
+        Ok(Self(Registry::builtin().get(name)?.formatter.clone()))
+    }
+
+    /// Render the time `ms` milliseconds after the start of the day (or of
+    /// whichever epoch the system counts from).
+    pub fn render(&self, ms: f64) -> String {
+        self.0.render_signed(ms as i64)
+    }
+
+    /// Parse a time rendered by this formatter into milliseconds.
+    pub fn parse(&self, s: &str) -> Result<f64, JsError> {
+        Ok(self.0.parse(s)? as f64)
+    }
+}
@@ -0,0 +1,232 @@
+//! Continuously updating display of the current time.
+
+use std::{
+    error::Error,
+    io::{self, Write},
+    sync::atomic::Ordering,
+    thread,
+    time::Duration,
+};
+
+use crate::wait::interrupt_flag;
+
+/// How often to check whether the displayed time has changed, when there's no
+/// telling when it next will.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// The longest time to sleep before checking again, so that interrupts,
+/// changes to the system clock, and suspends are noticed quickly.
+pub const MAX_SLEEP: Duration = Duration::from_millis(250);
+
+/// Hide the terminal cursor.
+pub const HIDE_CURSOR: &str = "\x1b[?25l";
+/// Show the terminal cursor.
+pub const SHOW_CURSOR: &str = "\x1b[?25h";
+/// Clear from the cursor to the end of the line.
+pub const CLEAR_TO_EOL: &str = "\x1b[K";
+/// Save the terminal title, where the terminal supports it.
+const PUSH_TITLE: &str = "\x1b[22;0t";
+/// Restore the terminal title saved with [`PUSH_TITLE`].
+const POP_TITLE: &str = "\x1b[23;0t";
+
+/// Where watch mode shows the time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// On a line of the terminal.
+    Line,
+    /// In the terminal title.
+    Title,
+    /// Both on a line and in the title.
+    Both,
+}
+
+impl Target {
+    /// Whether the time is shown on a line.
+    fn line(self) -> bool {
+        self != Self::Title
+    }
+
+    /// Whether the time is shown in the title.
+    fn title(self) -> bool {
+        self != Self::Line
+    }
+}
+
+/// Split `styled` text into the start of each character shown, with the
+/// escape sequences just before it, returning them along with the escape
+/// sequences after the last character.
+fn cells(styled: &str) -> (Vec<(usize, &str, char)>, &str) {
+    let mut cells = Vec::new();
+    let mut start = 0;
+    let mut chars = styled.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            // the sequence ends with its first character from `@` to `~`
+            // after the `[`
+            '\x1b' => {
+                chars.next();
+                chars.find(|(_, c)| ('@'..='~').contains(c));
+            }
+            c => {
+                cells.push((start, &styled[start..i], c));
+                start = i + c.len_utf8();
+            }
+        }
+    }
+    (cells, &styled[start..])
+}
+
+/// Get what to write to turn the line showing `last` into one showing
+/// `current`, both of which may be styled: only the characters from the
+/// first that changed, after the styles before them. The whole line is
+/// written if the characters before the change aren't all ASCII, which might
+/// not each be one column wide.
+fn redraw(last: &str, current: &str) -> String {
+    let ((old, old_tail), (new, new_tail)) = (cells(last), cells(current));
+    let same = old
+        .iter()
+        .zip(&new)
+        .take_while(|((_, a, c), (_, b, d))| (a, c) == (b, d))
+        .count();
+    if new[..same].iter().any(|(_, _, c)| !c.is_ascii()) {
+        return format!("\r{}{}", current, CLEAR_TO_EOL);
+    }
+    // the styles of the first changed character are written with those
+    // before it, and the rest after them
+    let (own, rest) = match new.get(same) {
+        Some((start, styles, _)) => (*styles, &current[start + styles.len()..]),
+        None if same == old.len() && old_tail == new_tail => return String::new(),
+        None => (new_tail, ""),
+    };
+    let styles: String = new[..same]
+        .iter()
+        .map(|(_, styles, _)| *styles)
+        .chain([own])
+        .collect();
+    // styles before the last reset no longer apply
+    let styles = ["\x1b[0m", "\x1b[m"]
+        .iter()
+        .filter_map(|reset| styles.rfind(reset))
+        .max()
+        .map_or(&styles[..], |i| &styles[i..]);
+    // columns are numbered from one
+    format!("\x1b[{}G{}{}{}", same + 1, styles, rest, CLEAR_TO_EOL)
+}
+
+/// The OSC 0 escape sequence that sets the terminal title (and icon name) to
+/// `text`.
+fn set_title(text: &str) -> String {
+    format!("\x1b]0;{}\x07", text)
+}
+
+/// Repeatedly display the rendered time returned by `now` on a single line,
+/// rewriting it whenever it changes, or in the terminal title, or both, as
+/// `target` gives. `now` returns the time both as shown on the line and as
+/// plain text for the title, and how long to wait before it may next change.
+/// Runs until interrupted with Ctrl-C, at which point the cursor and title are
+/// restored and the line is terminated. Stops early if `now` fails.
+pub fn watch<F>(now: F, target: Target) -> Result<(), Box<dyn Error>>
+where
+    F: Fn() -> Result<(String, String, Duration), Box<dyn Error>>,
+{
+    let running = interrupt_flag()?;
+
+    let mut stdout = io::stdout();
+    if target.line() {
+        write!(stdout, "{}", HIDE_CURSOR)?;
+    }
+    if target.title() {
+        write!(stdout, "{}", PUSH_TITLE)?;
+    }
+
+    let mut last = (String::new(), String::new());
+    let mut result = Ok(());
+    while running.load(Ordering::SeqCst) {
+        let (line, title, wait) = match now() {
+            Ok(current) => current,
+            Err(err) => {
+                result = Err(err);
+                break;
+            }
+        };
+        let current = (line, title);
+        if current != last {
+            if target.line() {
+                write!(stdout, "{}", redraw(&last.0, &current.0))?;
+            }
+            if target.title() {
+                write!(stdout, "{}", set_title(&current.1))?;
+            }
+            stdout.flush()?;
+            last = current;
+        }
+        // sleep until the time may change, waking now and then for Ctrl-C
+        thread::sleep(wait.min(MAX_SLEEP));
+    }
+
+    if target.title() {
+        write!(stdout, "{}", POP_TITLE)?;
+    }
+    if target.line() {
+        writeln!(stdout, "\r{}{}", last.0, SHOW_CURSOR)?;
+    }
+    stdout.flush()?;
+    result
+}
+
+/// Print the rendered time returned by `now` on a new line whenever it
+/// changes, without any cursor control, for programs reading the output line
+/// by line. `now` also returns how long to wait before calling it again. Runs
+/// until interrupted, or until `now` or writing fails.
+pub fn follow<F>(now: F) -> Result<(), Box<dyn Error>>
+where
+    F: Fn() -> Result<(String, Duration), Box<dyn Error>>,
+{
+    let mut stdout = io::stdout();
+    let mut last = String::new();
+    loop {
+        let (current, wait) = now()?;
+        if current != last {
+            writeln!(stdout, "{}", current)?;
+            stdout.flush()?;
+            last = current;
+        }
+        thread::sleep(wait);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn watch_targets() {
+        check!(Target::Line.line() && !Target::Line.title());
+        check!(!Target::Title.line() && Target::Title.title());
+        check!(Target::Both.line() && Target::Both.title());
+        check!(set_title("31:44:45.4") == "\x1b]0;31:44:45.4\x07");
+    }
+
+    #[test]
+    fn redraw_changed_cells() {
+        check!(redraw("", "31:44:45.4") == "\x1b[1G31:44:45.4\x1b[K");
+        check!(redraw("31:44:45.4", "31:44:45.5") == "\x1b[10G5\x1b[K");
+        check!(redraw("31:44:45.5", "31:44:50.0") == "\x1b[7G50.0\x1b[K");
+        check!(redraw("31:44:45.5", "31:44:45") == "\x1b[9G\x1b[K");
+        check!(redraw("31", "31").is_empty());
+
+        // the styles in effect before the change are written again
+        let styled = |snap| format!("\x1b[31m31\x1b[0m:\x1b[32m{}\x1b[0m", snap);
+        check!(redraw(&styled(4), &styled(5)) == "\x1b[4G\x1b[0m\x1b[32m5\x1b[0m\x1b[K");
+        check!(
+            redraw("\x1b[1m\x1b[0m\x1b[31m31", "\x1b[1m\x1b[0m\x1b[31m32")
+                == "\x1b[2G\x1b[0m\x1b[31m2\x1b[K"
+        );
+        check!(redraw("\x1b[31m31", "\x1b[32m31") == "\x1b[1G\x1b[32m31\x1b[K");
+        check!(redraw("31\x1b[0m", "31") == "\x1b[3G\x1b[K");
+
+        // characters that might be wide are written from the start
+        check!(redraw("三十一", "三十二") == "\r三十二\x1b[K");
+    }
+}
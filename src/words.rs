@@ -0,0 +1,153 @@
+//! Parsing of times of day written in words, such as `quarter past three`.
+
+use chrono::NaiveTime;
+
+/// The number words below twenty.
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+/// The number words for multiples of ten, from twenty.
+const TENS: [&str; 4] = ["twenty", "thirty", "forty", "fifty"];
+
+/// Parse a time of day written in words: `noon`, `midnight`, an hour on its
+/// own or followed by `o'clock`, or a number of minutes `past` or `to` an
+/// hour. The minutes may also be `quarter` or `half`. Numbers are written in
+/// digits or in words, and hours up to twelve may be followed by `am` or `pm`.
+pub fn parse(when: &str) -> Option<NaiveTime> {
+    let when = when.trim().to_lowercase();
+    let mut words: Vec<&str> = when.split_whitespace().collect();
+    // the meridiem may follow the hour without a space, as in `4pm`
+    if let Some(&last) = words.last() {
+        let split = ["am", "pm"]
+            .iter()
+            .find_map(|&meridiem| Some((last.strip_suffix(meridiem)?, meridiem)));
+        if let Some((hour, meridiem)) = split.filter(|(hour, _)| !hour.is_empty()) {
+            words.pop();
+            words.extend([hour, meridiem]);
+        }
+    }
+    let (words, meridiem) = match words.split_last()? {
+        (&"am", rest) => (rest, Some(0)),
+        (&"pm", rest) => (rest, Some(12)),
+        _ => (&words[..], None),
+    };
+    let hour = |word: &str| match (word, meridiem) {
+        ("noon" | "midday", None) => Some(12),
+        ("midnight", None) => Some(0),
+        (word, None) => number(word).filter(|&hour| hour < 24),
+        (word, Some(offset)) => number(word)
+            .filter(|hour| (1..=12).contains(hour))
+            .map(|hour| hour % 12 + offset),
+    };
+
+    let minutes = match words {
+        [h] | [h, "o'clock"] => hour(h)? * 60,
+        [m @ .., "past", h] => hour(h)? * 60 + minutes(m)?,
+        [m @ .., "to", h] => (hour(h)? * 60 + 24 * 60 - minutes(m)?) % (24 * 60),
+        _ => return None,
+    };
+    NaiveTime::from_num_seconds_from_midnight_opt(minutes * 60, 0)
+}
+
+/// Parse a number of minutes from one to fifty-nine, which may be `quarter`
+/// or `half`, optionally followed by `minutes`.
+fn minutes(words: &[&str]) -> Option<u32> {
+    let words = match words {
+        ["a", rest @ ..] => rest,
+        [rest @ .., "minute" | "minutes"] => rest,
+        _ => words,
+    };
+    match words {
+        ["quarter"] => Some(15),
+        ["half"] => Some(30),
+        _ => number(&words.join("-")).filter(|minutes| (1..60).contains(minutes)),
+    }
+}
+
+/// Parse a number below a hundred, in digits or in words such as
+/// `twenty-five`.
+fn number(word: &str) -> Option<u32> {
+    if !word.is_empty() && word.chars().all(|c| c.is_ascii_digit()) {
+        return word.parse().ok().filter(|&n| n < 100);
+    }
+    let position = |words: &[&str], word: &str| words.iter().position(|&w| w == word);
+    match word.split_once('-') {
+        Some((tens, ones)) => {
+            let ones = position(&ONES[1..10], ones)? as u32 + 1;
+            Some(position(&TENS, tens)? as u32 * 10 + 20 + ones)
+        }
+        None => position(&ONES, word)
+            .map(|n| n as u32)
+            .or_else(|| position(&TENS, word).map(|n| n as u32 * 10 + 20)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use assert2::check;
+
+    fn time(hour: u32, minute: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    }
+
+    #[test]
+    fn times_in_words() {
+        check!(parse("noon") == time(12, 0));
+        check!(parse("Midnight") == time(0, 0));
+        check!(parse("quarter past three") == time(3, 15));
+        check!(parse("half past 17") == time(17, 30));
+        check!(parse("a quarter to four pm") == time(15, 45));
+        check!(parse("twenty-five past eleven") == time(11, 25));
+        check!(parse("ten minutes to midnight") == time(23, 50));
+        check!(parse("five to 12 am") == time(23, 55));
+        check!(parse("seven o'clock") == time(7, 0));
+        check!(parse("twelve pm") == time(12, 0));
+        check!(parse("4pm") == time(16, 0));
+        check!(parse("12AM") == time(0, 0));
+        check!(parse("half past 4pm") == time(16, 30));
+    }
+
+    #[test]
+    fn reject_other_words() {
+        check!(parse("").is_none());
+        check!(parse("teatime").is_none());
+        check!(parse("half past 25").is_none());
+        check!(parse("seventeen pm").is_none());
+        check!(parse("noon pm").is_none());
+        check!(parse("sixty past three").is_none());
+        check!(parse("quarter past").is_none());
+        check!(parse("pm").is_none());
+        check!(parse("noonpm").is_none());
+    }
+
+    #[test]
+    fn number_words() {
+        check!(number("seven") == Some(7));
+        check!(number("forty") == Some(40));
+        check!(number("fifty-nine") == Some(59));
+        check!(number("42") == Some(42));
+        check!(number("twenty-zero").is_none());
+        check!(number("-5").is_none());
+    }
+}